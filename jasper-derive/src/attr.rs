@@ -0,0 +1,203 @@
+use syn::{Attribute, ExprPath, LitStr, Result};
+
+///A `rename_all` naming convention
+#[derive(Clone, Copy)]
+pub(crate) enum Case {
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
+impl Case {
+    fn from_name(name: &str) -> Option<Case> {
+        Some(match name {
+            "lowercase" => Case::Lower,
+            "UPPERCASE" => Case::Upper,
+            "PascalCase" => Case::Pascal,
+            "camelCase" => Case::Camel,
+            "snake_case" => Case::Snake,
+            "SCREAMING_SNAKE_CASE" => Case::ScreamingSnake,
+            "kebab-case" => Case::Kebab,
+            "SCREAMING-KEBAB-CASE" => Case::ScreamingKebab,
+            _ => return None,
+        })
+    }
+
+    ///Renames a Rust identifier (a snake_case field or a PascalCase variant)
+    pub(crate) fn apply(self, ident: &str) -> String {
+        let mut words: Vec<String> = vec![];
+        for part in ident.split('_').filter(|part| !part.is_empty()) {
+            let mut word = String::new();
+            for c in part.chars() {
+                if c.is_uppercase() && word.chars().last().is_some_and(|x| !x.is_uppercase()) {
+                    words.push(std::mem::take(&mut word));
+                }
+                word.push(c);
+            }
+            words.push(word);
+        }
+        let capitalize = |word: &String| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first
+                    .to_uppercase()
+                    .chain(chars.flat_map(char::to_lowercase))
+                    .collect(),
+                None => String::new(),
+            }
+        };
+        match self {
+            Case::Lower => words.concat().to_lowercase(),
+            Case::Upper => words.concat().to_uppercase(),
+            Case::Pascal => words.iter().map(capitalize).collect(),
+            Case::Camel => {
+                let mut output = words.first().map(|x| x.to_lowercase()).unwrap_or_default();
+                output.extend(words.iter().skip(1).map(capitalize));
+                output
+            }
+            Case::Snake => words.join("_").to_lowercase(),
+            Case::ScreamingSnake => words.join("_").to_uppercase(),
+            Case::Kebab => words.join("-").to_lowercase(),
+            Case::ScreamingKebab => words.join("-").to_uppercase(),
+        }
+    }
+}
+
+///How the variant of an enum is recorded
+pub(crate) enum Tagging {
+    ///`{"Variant": content}`, or just `"Variant"` for a unit variant
+    External,
+
+    ///`{"tag": "Variant", ...fields}`
+    Internal(String),
+
+    ///`{"tag": "Variant", "content": content}`
+    Adjacent(String, String),
+
+    ///Just the content, with each variant tried in turn when converting from JSON
+    Untagged,
+}
+
+///The `#[json(...)]` attributes of a struct or enum
+pub(crate) struct ContainerAttrs {
+    pub(crate) rename_all: Option<Case>,
+    pub(crate) tagging: Tagging,
+}
+
+impl ContainerAttrs {
+    pub(crate) fn parse(attrs: &[Attribute]) -> Result<ContainerAttrs> {
+        let mut rename_all = None;
+        let mut tag = None;
+        let mut content = None;
+        let mut untagged = false;
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("json")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename_all") {
+                    let name: LitStr = meta.value()?.parse()?;
+                    rename_all = Some(
+                        Case::from_name(&name.value())
+                            .ok_or_else(|| meta.error("unknown rename_all convention"))?,
+                    );
+                } else if meta.path.is_ident("tag") {
+                    tag = Some(meta.value()?.parse::<LitStr>()?.value());
+                } else if meta.path.is_ident("content") {
+                    content = Some(meta.value()?.parse::<LitStr>()?.value());
+                } else if meta.path.is_ident("untagged") {
+                    untagged = true;
+                } else {
+                    return Err(meta.error("unknown json attribute"));
+                }
+                Ok(())
+            })?;
+        }
+        let error = |message| {
+            let attr = attrs.iter().find(|attr| attr.path().is_ident("json"));
+            Err(syn::Error::new_spanned(attr, message))
+        };
+        let tagging = match (tag, content, untagged) {
+            (None, None, false) => Tagging::External,
+            (Some(tag), None, false) => Tagging::Internal(tag),
+            (Some(tag), Some(content), false) => Tagging::Adjacent(tag, content),
+            (None, None, true) => Tagging::Untagged,
+            (None, Some(_), _) => return error("json(content) requires json(tag)"),
+            (Some(_), _, true) => return error("json(untagged) cannot be combined with json(tag)"),
+        };
+        Ok(ContainerAttrs {
+            rename_all,
+            tagging,
+        })
+    }
+}
+
+///The `#[json(...)]` attributes of a field
+#[derive(Default)]
+pub(crate) struct FieldAttrs {
+    pub(crate) rename: Option<String>,
+
+    ///`Some(None)` for `default` (which uses `Default::default()`), or `Some(Some(path))` for
+    ///`default = "path"` (which calls the function at that path)
+    pub(crate) default: Option<Option<ExprPath>>,
+
+    pub(crate) skip: bool,
+    pub(crate) flatten: bool,
+}
+
+impl FieldAttrs {
+    pub(crate) fn parse(attrs: &[Attribute]) -> Result<FieldAttrs> {
+        let mut output = FieldAttrs::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("json")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    output.rename = Some(meta.value()?.parse::<LitStr>()?.value());
+                } else if meta.path.is_ident("default") {
+                    output.default = Some(if meta.input.peek(syn::Token![=]) {
+                        Some(meta.value()?.parse::<LitStr>()?.parse()?)
+                    } else {
+                        None
+                    });
+                } else if meta.path.is_ident("skip") {
+                    output.skip = true;
+                } else if meta.path.is_ident("flatten") {
+                    output.flatten = true;
+                } else {
+                    return Err(meta.error("unknown json attribute"));
+                }
+                Ok(())
+            })?;
+            if output.flatten && (output.rename.is_some() || output.skip) {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    "json(flatten) cannot be combined with json(rename) or json(skip)",
+                ));
+            }
+        }
+        Ok(output)
+    }
+}
+
+///The `#[json(...)]` attributes of an enum variant
+pub(crate) struct VariantAttrs {
+    pub(crate) rename: Option<String>,
+}
+
+impl VariantAttrs {
+    pub(crate) fn parse(attrs: &[Attribute]) -> Result<VariantAttrs> {
+        let mut rename = None;
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("json")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    rename = Some(meta.value()?.parse::<LitStr>()?.value());
+                    Ok(())
+                } else {
+                    Err(meta.error("unknown json attribute"))
+                }
+            })?;
+        }
+        Ok(VariantAttrs { rename })
+    }
+}
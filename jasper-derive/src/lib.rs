@@ -0,0 +1,608 @@
+//!Derive macros for the `ToJson` and `FromJson` traits of jasper. Use them through jasper's
+//!`derive` feature rather than depending on this crate directly
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+use syn::spanned::Spanned;
+use syn::{
+    parse_macro_input, parse_quote, parse_quote_spanned, Data, DeriveInput, Fields, Generics,
+    Ident, Member, PathArguments, Result, Type,
+};
+
+mod attr;
+
+use attr::{Case, ContainerAttrs, FieldAttrs, Tagging, VariantAttrs};
+
+///Derives `jasper::ToJson`. Structs with named fields become objects, newtype structs become
+///their single field, tuple structs become arrays and unit structs become null. Fields of type
+///`Option` are left out of the object when they are None.
+///
+///Containers accept `#[json(rename_all = "...")]` (one of lowercase, UPPERCASE, PascalCase,
+///camelCase, snake_case, SCREAMING_SNAKE_CASE, kebab-case or SCREAMING-KEBAB-CASE), which
+///renames the fields of a struct or the variants of an enum. Enums are externally tagged
+///(`{"Variant": content}`, or `"Variant"` for a unit variant) unless given
+///`#[json(tag = "type")]` (internally tagged: `{"type": "Variant", ...fields}`),
+///`#[json(tag = "t", content = "c")]` (adjacently tagged: `{"t": "Variant", "c": content}`)
+///or `#[json(untagged)]` (just the content). The content of an internally tagged newtype
+///variant must implement `ToJsonObject`, which this derive implements for structs with named
+///fields and internally or adjacently tagged enums.
+///
+///Named fields accept `#[json(rename = "...")]`, `#[json(skip)]` (left out, and filled with
+///its default by `FromJson`), `#[json(default)]` or `#[json(default = "path::to::function")]`
+///(used by `FromJson` when the key is missing) and `#[json(flatten)]` (the field's own object
+///is merged into this one). Variants accept `#[json(rename = "...")]`
+#[proc_macro_derive(ToJson, attributes(json))]
+pub fn derive_to_json(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_to_json(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+///Derives `jasper::FromJson`, reading the JSON that `#[derive(ToJson)]` writes (see its
+///documentation for the attributes). Fields of type `Option` may be missing or null. Keys
+///that are not fields are ignored, and untagged enums take the first variant that converts
+#[proc_macro_derive(FromJson, attributes(json))]
+pub fn derive_from_json(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_from_json(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+///A field of a struct or variant, along with the name it is bound to while converting
+struct FieldInfo<'a> {
+    member: Member,
+    binding: Ident,
+    key: String,
+    ty: &'a Type,
+    attrs: FieldAttrs,
+}
+
+fn field_infos(fields: &Fields, rename_all: Option<Case>) -> Result<Vec<FieldInfo<'_>>> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let attrs = FieldAttrs::parse(&field.attrs)?;
+            let (member, key) = match &field.ident {
+                Some(ident) => {
+                    let name = ident.unraw().to_string();
+                    let key = match (&attrs.rename, rename_all) {
+                        (Some(rename), _) => rename.clone(),
+                        (None, Some(case)) => case.apply(&name),
+                        (None, None) => name,
+                    };
+                    (Member::Named(ident.clone()), key)
+                }
+                None => {
+                    let has_attrs = attrs.rename.is_some()
+                        || attrs.default.is_some()
+                        || attrs.skip
+                        || attrs.flatten;
+                    if has_attrs {
+                        return Err(syn::Error::new_spanned(
+                            field,
+                            "json attributes are only supported on named fields",
+                        ));
+                    }
+                    (Member::Unnamed(i.into()), i.to_string())
+                }
+            };
+            Ok(FieldInfo {
+                member,
+                binding: format_ident!("__field{}", i),
+                key,
+                ty: &field.ty,
+                attrs,
+            })
+        })
+        .collect()
+}
+
+fn is_option(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) if path.qself.is_none() => {
+            path.path.segments.last().is_some_and(|segment| {
+                segment.ident == "Option"
+                    && matches!(segment.arguments, PathArguments::AngleBracketed(_))
+            })
+        }
+        _ => false,
+    }
+}
+
+///Adds `bound` to every type parameter
+fn bounded(generics: &Generics, bound: TokenStream2) -> Generics {
+    let mut generics = generics.clone();
+    for param in generics.type_params_mut() {
+        param.bounds.push(parse_quote!(#bound));
+    }
+    generics
+}
+
+///A pattern binding each (non-skipped) field to its `binding`, e.g. `Self::V { a: __field0 }`
+fn destructure(path: TokenStream2, infos: &[FieldInfo]) -> TokenStream2 {
+    let members = infos.iter().map(|info| {
+        let member = &info.member;
+        let binding = &info.binding;
+        if info.attrs.skip {
+            quote!(#member: _)
+        } else {
+            quote!(#member: #binding)
+        }
+    });
+    quote!(#path { #(#members),* })
+}
+
+///An expression building an object from bound named fields, with an optional tag entry
+fn object_to_json(infos: &[FieldInfo], tag: Option<(&str, &str)>) -> TokenStream2 {
+    let inserts = infos.iter().filter(|info| !info.attrs.skip).map(|info| {
+        let binding = &info.binding;
+        let key = &info.key;
+        if info.attrs.flatten {
+            quote! {
+                if let ::jasper::JsonValue::Object(inner) = ::jasper::ToJson::to_json(#binding) {
+                    object.extend(inner);
+                }
+            }
+        } else if is_option(info.ty) {
+            quote! {
+                if let ::core::option::Option::Some(value) = #binding {
+                    object.insert(
+                        ::std::string::String::from(#key),
+                        ::jasper::ToJson::to_json(value),
+                    );
+                }
+            }
+        } else {
+            quote! {
+                object.insert(
+                    ::std::string::String::from(#key),
+                    ::jasper::ToJson::to_json(#binding),
+                );
+            }
+        }
+    });
+    let tag = tag.map(|(key, name)| {
+        quote! {
+            object.insert(
+                ::std::string::String::from(#key),
+                ::jasper::JsonValue::String(::std::string::String::from(#name)),
+            );
+        }
+    });
+    quote! {{
+        let mut object = ::std::collections::HashMap::new();
+        #(#inserts)*
+        #tag
+        ::jasper::JsonValue::Object(object)
+    }}
+}
+
+///An expression converting bound fields to JSON
+fn content_to_json(fields: &Fields, infos: &[FieldInfo]) -> TokenStream2 {
+    match fields {
+        Fields::Named(_) => object_to_json(infos, None),
+        Fields::Unnamed(_) if infos.len() == 1 => {
+            let binding = &infos[0].binding;
+            quote!(::jasper::ToJson::to_json(#binding))
+        }
+        Fields::Unnamed(_) => {
+            let bindings = infos.iter().map(|info| &info.binding);
+            quote!(::jasper::JsonValue::Array(::std::vec![
+                #(::jasper::ToJson::to_json(#bindings)),*
+            ]))
+        }
+        Fields::Unit => quote!(::jasper::JsonValue::Null),
+    }
+}
+
+///An expression for an object with a single entry
+fn single_entry(key: &str, value: TokenStream2) -> TokenStream2 {
+    quote!(::jasper::JsonValue::Object(::std::collections::HashMap::from([(
+        ::std::string::String::from(#key),
+        #value,
+    )])))
+}
+
+fn variant_name(ident: &Ident, attrs: &VariantAttrs, rename_all: Option<Case>) -> String {
+    match (&attrs.rename, rename_all) {
+        (Some(rename), _) => rename.clone(),
+        (None, Some(case)) => case.apply(&ident.unraw().to_string()),
+        (None, None) => ident.unraw().to_string(),
+    }
+}
+
+fn expand_to_json(input: &DeriveInput) -> Result<TokenStream2> {
+    let container = ContainerAttrs::parse(&input.attrs)?;
+    //The content of internally tagged newtype variants, which must convert to objects
+    let mut object_contents = vec![];
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let infos = field_infos(&data.fields, container.rename_all)?;
+            let pattern = destructure(quote!(Self), &infos);
+            let content = content_to_json(&data.fields, &infos);
+            quote! {
+                let #pattern = self;
+                #content
+            }
+        }
+        Data::Enum(data) => {
+            let mut arms = vec![];
+            for variant in &data.variants {
+                let attrs = VariantAttrs::parse(&variant.attrs)?;
+                let name = variant_name(&variant.ident, &attrs, container.rename_all);
+                let infos = field_infos(&variant.fields, None)?;
+                let ident = &variant.ident;
+                let pattern = destructure(quote!(Self::#ident), &infos);
+                let content = content_to_json(&variant.fields, &infos);
+                let value = match (&container.tagging, &variant.fields) {
+                    (Tagging::External, Fields::Unit) => {
+                        quote!(::jasper::JsonValue::String(::std::string::String::from(#name)))
+                    }
+                    (Tagging::External, _) => single_entry(&name, content),
+                    (Tagging::Internal(tag), Fields::Named(_)) => {
+                        object_to_json(&infos, Some((tag, &name)))
+                    }
+                    (Tagging::Internal(tag), Fields::Unit) => {
+                        object_to_json(&[], Some((tag, &name)))
+                    }
+                    (Tagging::Internal(tag), Fields::Unnamed(_)) if infos.len() == 1 => {
+                        object_contents.push(infos[0].ty);
+                        quote! {{
+                            let mut value = #content;
+                            if let ::jasper::JsonValue::Object(object) = &mut value {
+                                object.insert(
+                                    ::std::string::String::from(#tag),
+                                    ::jasper::JsonValue::String(::std::string::String::from(#name)),
+                                );
+                            }
+                            value
+                        }}
+                    }
+                    (Tagging::Internal(_), Fields::Unnamed(_)) => {
+                        return Err(syn::Error::new_spanned(
+                            variant,
+                            "tuple variants cannot be internally tagged",
+                        ))
+                    }
+                    (Tagging::Adjacent(tag, _), Fields::Unit) => {
+                        object_to_json(&[], Some((tag, &name)))
+                    }
+                    (Tagging::Adjacent(tag, key), _) => quote! {{
+                        let mut object = ::std::collections::HashMap::new();
+                        object.insert(
+                            ::std::string::String::from(#tag),
+                            ::jasper::JsonValue::String(::std::string::String::from(#name)),
+                        );
+                        object.insert(::std::string::String::from(#key), #content);
+                        ::jasper::JsonValue::Object(object)
+                    }},
+                    (Tagging::Untagged, _) => content,
+                };
+                arms.push(quote!(#pattern => #value,));
+            }
+            quote! {
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+        Data::Union(_) => {
+            return Err(syn::Error::new_spanned(
+                input,
+                "ToJson cannot be derived for unions",
+            ))
+        }
+    };
+    let name = &input.ident;
+    let mut generics = bounded(&input.generics, quote!(::jasper::ToJson));
+    let predicates = &mut generics.make_where_clause().predicates;
+    for ty in object_contents {
+        predicates.push(parse_quote_spanned!(ty.span()=> #ty: ::jasper::ToJsonObject));
+    }
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+    let object = match &input.data {
+        Data::Struct(data) => matches!(data.fields, Fields::Named(_)),
+        _ => matches!(
+            container.tagging,
+            Tagging::Internal(_) | Tagging::Adjacent(..)
+        ),
+    };
+    let object_impl = object.then(|| {
+        quote! {
+            impl #impl_generics ::jasper::ToJsonObject for #name #type_generics #where_clause {}
+        }
+    });
+    Ok(quote! {
+        impl #impl_generics ::jasper::ToJson for #name #type_generics #where_clause {
+            fn to_json(&self) -> ::jasper::JsonValue {
+                #body
+            }
+        }
+
+        #object_impl
+    })
+}
+
+///The value of a field which is skipped or has a default and is missing
+fn default_value(info: &FieldInfo) -> TokenStream2 {
+    match &info.attrs.default {
+        Some(Some(path)) => quote!(#path()),
+        _ => quote!(::core::default::Default::default()),
+    }
+}
+
+///Statements converting `input` into `path`, ending with an expression of type
+///`Result<Self, FromJsonError>` (and possibly returning early with an error)
+fn content_from_json(path: TokenStream2, fields: &Fields, infos: &[FieldInfo]) -> TokenStream2 {
+    match fields {
+        Fields::Named(_) => {
+            let values = infos.iter().map(|info| {
+                let member = &info.member;
+                let key = &info.key;
+                let value = if info.attrs.skip {
+                    default_value(info)
+                } else if info.attrs.flatten {
+                    quote!(::jasper::FromJson::from_json(input)?)
+                } else {
+                    let convert = quote! {
+                        ::jasper::FromJson::from_json(value).map_err(|e| e.within(#key))?
+                    };
+                    let (present, missing) = match (&info.attrs.default, is_option(info.ty)) {
+                        (Some(_), _) => (convert, default_value(info)),
+                        (None, true) => (
+                            quote! {
+                                match value {
+                                    ::jasper::JsonValue::Null => ::core::option::Option::None,
+                                    value => ::core::option::Option::Some(#convert),
+                                }
+                            },
+                            quote!(::core::option::Option::None),
+                        ),
+                        (None, false) => (
+                            convert,
+                            quote! {
+                                return ::core::result::Result::Err(
+                                    ::jasper::FromJsonError::missing(#key),
+                                )
+                            },
+                        ),
+                    };
+                    quote! {
+                        match object.get(#key) {
+                            ::core::option::Option::Some(value) => #present,
+                            ::core::option::Option::None => #missing,
+                        }
+                    }
+                };
+                quote!(#member: #value)
+            });
+            quote! {
+                let object = match input {
+                    ::jasper::JsonValue::Object(object) => object,
+                    other => {
+                        return ::core::result::Result::Err(
+                            ::jasper::FromJsonError::expected("an object", other),
+                        )
+                    }
+                };
+                ::core::result::Result::Ok(#path { #(#values),* })
+            }
+        }
+        Fields::Unnamed(_) if infos.len() == 1 => quote! {
+            ::core::result::Result::Ok(#path(::jasper::FromJson::from_json(input)?))
+        },
+        Fields::Unnamed(_) => {
+            let len = infos.len();
+            let expected = format!("an array of {} elements", len);
+            let values = (0..len).map(|i| {
+                let index = i.to_string();
+                quote!(::jasper::FromJson::from_json(&items[#i]).map_err(|e| e.within(#index))?)
+            });
+            quote! {
+                let items = match input {
+                    ::jasper::JsonValue::Array(items) if items.len() == #len => items,
+                    other => {
+                        return ::core::result::Result::Err(
+                            ::jasper::FromJsonError::expected(#expected, other),
+                        )
+                    }
+                };
+                ::core::result::Result::Ok(#path(#(#values),*))
+            }
+        }
+        Fields::Unit => quote! {
+            match input {
+                ::jasper::JsonValue::Null => ::core::result::Result::Ok(#path),
+                other => ::core::result::Result::Err(
+                    ::jasper::FromJsonError::expected("null", other),
+                ),
+            }
+        },
+    }
+}
+
+///Wraps `content_from_json` in a closure taking the input, so that it can be applied to the
+///content of a tagged variant
+fn closure(body: TokenStream2) -> TokenStream2 {
+    quote! {
+        (|input: &::jasper::JsonValue| -> ::core::result::Result<Self, ::jasper::FromJsonError> {
+            #body
+        })
+    }
+}
+
+///Statements reading the tag of an internally or adjacently tagged enum into `name`
+fn read_tag(tag: &str) -> TokenStream2 {
+    quote! {
+        let object = match input {
+            ::jasper::JsonValue::Object(object) => object,
+            other => {
+                return ::core::result::Result::Err(
+                    ::jasper::FromJsonError::expected("an object", other),
+                )
+            }
+        };
+        let name = match object.get(#tag) {
+            ::core::option::Option::Some(::jasper::JsonValue::String(name)) => name,
+            ::core::option::Option::Some(other) => {
+                return ::core::result::Result::Err(
+                    ::jasper::FromJsonError::expected("a string", other).within(#tag),
+                )
+            }
+            ::core::option::Option::None => {
+                return ::core::result::Result::Err(::jasper::FromJsonError::missing(#tag))
+            }
+        };
+    }
+}
+
+fn unknown_variant(tag: Option<&str>) -> TokenStream2 {
+    let within = tag.map(|tag| quote!(.within(#tag)));
+    quote! {
+        other => ::core::result::Result::Err(
+            ::jasper::FromJsonError::new(::std::format!("unknown variant {:?}", other))#within,
+        ),
+    }
+}
+
+fn expand_from_json(input: &DeriveInput) -> Result<TokenStream2> {
+    let container = ContainerAttrs::parse(&input.attrs)?;
+    let name = &input.ident;
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let infos = field_infos(&data.fields, container.rename_all)?;
+            content_from_json(quote!(Self), &data.fields, &infos)
+        }
+        Data::Enum(data) => {
+            let mut variants = vec![];
+            for variant in &data.variants {
+                let attrs = VariantAttrs::parse(&variant.attrs)?;
+                let key = variant_name(&variant.ident, &attrs, container.rename_all);
+                let infos = field_infos(&variant.fields, None)?;
+                let ident = &variant.ident;
+                let body = content_from_json(quote!(Self::#ident), &variant.fields, &infos);
+                variants.push((key, ident, &variant.fields, closure(body)));
+            }
+            match &container.tagging {
+                Tagging::External => {
+                    let units = variants
+                        .iter()
+                        .filter(|(_, _, fields, _)| matches!(fields, Fields::Unit))
+                        .map(|(key, ident, _, _)| {
+                            quote!(#key => ::core::result::Result::Ok(Self::#ident),)
+                        });
+                    let others = variants
+                        .iter()
+                        .filter(|(_, _, fields, _)| !matches!(fields, Fields::Unit))
+                        .map(|(key, _, _, closure)| {
+                            quote!(#key => #closure(content).map_err(|e| e.within(#key)),)
+                        });
+                    let unknown = unknown_variant(None);
+                    quote! {
+                        match input {
+                            ::jasper::JsonValue::String(name) => match name.as_str() {
+                                #(#units)*
+                                #unknown
+                            },
+                            ::jasper::JsonValue::Object(object) if object.len() == 1 => {
+                                let (name, content) = object.iter().next().unwrap();
+                                match name.as_str() {
+                                    #(#others)*
+                                    #unknown
+                                }
+                            }
+                            other => ::core::result::Result::Err(
+                                ::jasper::FromJsonError::expected(
+                                    "a variant name or an object with a single key",
+                                    other,
+                                ),
+                            ),
+                        }
+                    }
+                }
+                Tagging::Internal(tag) => {
+                    let read_tag = read_tag(tag);
+                    let arms = variants
+                        .iter()
+                        .map(|(key, ident, fields, closure)| match fields {
+                            Fields::Unit => {
+                                quote!(#key => ::core::result::Result::Ok(Self::#ident),)
+                            }
+                            _ => quote!(#key => #closure(input),),
+                        });
+                    let unknown = unknown_variant(Some(tag));
+                    quote! {
+                        #read_tag
+                        match name.as_str() {
+                            #(#arms)*
+                            #unknown
+                        }
+                    }
+                }
+                Tagging::Adjacent(tag, content) => {
+                    let read_tag = read_tag(tag);
+                    let arms = variants
+                        .iter()
+                        .map(|(key, ident, fields, closure)| match fields {
+                            Fields::Unit => {
+                                quote!(#key => ::core::result::Result::Ok(Self::#ident),)
+                            }
+                            _ => quote! {
+                                #key => match object.get(#content) {
+                                    ::core::option::Option::Some(content) => {
+                                        #closure(content).map_err(|e| e.within(#content))
+                                    }
+                                    ::core::option::Option::None => ::core::result::Result::Err(
+                                        ::jasper::FromJsonError::missing(#content),
+                                    ),
+                                },
+                            },
+                        });
+                    let unknown = unknown_variant(Some(tag));
+                    quote! {
+                        #read_tag
+                        match name.as_str() {
+                            #(#arms)*
+                            #unknown
+                        }
+                    }
+                }
+                Tagging::Untagged => {
+                    let attempts = variants.iter().map(|(_, _, _, closure)| {
+                        quote! {
+                            if let ::core::result::Result::Ok(value) = #closure(input) {
+                                return ::core::result::Result::Ok(value);
+                            }
+                        }
+                    });
+                    let message = format!("value does not match any variant of {}", name);
+                    quote! {
+                        #(#attempts)*
+                        ::core::result::Result::Err(::jasper::FromJsonError::new(#message))
+                    }
+                }
+            }
+        }
+        Data::Union(_) => {
+            return Err(syn::Error::new_spanned(
+                input,
+                "FromJson cannot be derived for unions",
+            ))
+        }
+    };
+    let generics = bounded(&input.generics, quote!(::jasper::FromJson));
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::jasper::FromJson for #name #type_generics #where_clause {
+            fn from_json(
+                input: &::jasper::JsonValue,
+            ) -> ::core::result::Result<Self, ::jasper::FromJsonError> {
+                #body
+            }
+        }
+    })
+}
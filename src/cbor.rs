@@ -0,0 +1,398 @@
+use crate::base64;
+use crate::{DateFormat, JsonDate, JsonValue, ParseError, ParseOptions};
+use std::collections::HashMap;
+
+const TAG_DATETIME_STRING: u64 = 0;
+const TAG_DATETIME_EPOCH: u64 = 1;
+const TAG_BASE64: u64 = 22;
+const TAG_BASE16: u64 = 23;
+
+const BREAK: u8 = 0xFF;
+
+impl JsonValue {
+    ///Encodes the value as CBOR (RFC 8949). Whole numbers are written as integers and other
+    ///numbers as the shortest of half-, single- or double-precision floats which is exact.
+    ///Object keys are sorted as deterministic encoding requires, so equal values always encode
+    ///the same way
+    pub fn to_cbor(&self) -> Vec<u8> {
+        let mut output = vec![];
+        write_value(self, &mut output);
+        output
+    }
+
+    ///Decodes a CBOR (RFC 8949) data item, with the default depth and size limits
+    pub fn from_cbor(input: &[u8]) -> Result<JsonValue, ParseError> {
+        Self::from_cbor_with(input, &ParseOptions::default())
+    }
+
+    ///Decodes a CBOR (RFC 8949) data item within the options' depth and size limits.
+    ///
+    ///Items are converted as RFC 8949 section 6.1 suggests: byte strings become base64url
+    ///strings (or base64 or hex ones, if tagged 22 or 23), undefined becomes null, integer map
+    ///keys become strings and other tags are dropped. Datetimes tagged 0 or 1 become RFC 3339
+    ///strings, which `JsonValue::get_json_date` reads
+    pub fn from_cbor_with(input: &[u8], options: &ParseOptions) -> Result<JsonValue, ParseError> {
+        if !options.allows_size(input.len()) {
+            return Err(ParseError {
+                offset: 0,
+                message: "input is too large".to_owned(),
+            });
+        }
+        let mut reader = CborReader {
+            input,
+            pos: 0,
+            options,
+        };
+        let value = reader.item(0)?;
+        if reader.pos < input.len() {
+            return Err(reader.error(reader.pos, "unexpected bytes after the value"));
+        }
+        Ok(value)
+    }
+}
+
+impl JsonDate {
+    ///Encodes the datetime as CBOR: an RFC 3339 string tagged 0
+    pub fn to_cbor(&self) -> Vec<u8> {
+        let mut output = vec![];
+        write_head(&mut output, 6, TAG_DATETIME_STRING);
+        write_value(&self.to_json_with(DateFormat::Rfc3339), &mut output);
+        output
+    }
+
+    ///Decodes a CBOR datetime: an RFC 3339 string tagged 0, or a Unix timestamp tagged 1
+    pub fn from_cbor(input: &[u8]) -> Result<JsonDate, ParseError> {
+        let error = || ParseError {
+            offset: 0,
+            message: "expected a datetime tagged 0 or 1".to_owned(),
+        };
+        if !matches!(input.first(), Some(0xC0 | 0xC1)) {
+            return Err(error());
+        }
+        match JsonValue::from_cbor(input)? {
+            JsonValue::String(str) => JsonDate::from_rfc3339(&str).map_err(|_| error()),
+            _ => Err(error()),
+        }
+    }
+}
+
+fn write_head(output: &mut Vec<u8>, major: u8, argument: u64) {
+    let major = major << 5;
+    if argument < 24 {
+        output.push(major | argument as u8);
+    } else if argument <= u8::MAX as u64 {
+        output.push(major | 24);
+        output.push(argument as u8);
+    } else if argument <= u16::MAX as u64 {
+        output.push(major | 25);
+        output.extend_from_slice(&(argument as u16).to_be_bytes());
+    } else if argument <= u32::MAX as u64 {
+        output.push(major | 26);
+        output.extend_from_slice(&(argument as u32).to_be_bytes());
+    } else {
+        output.push(major | 27);
+        output.extend_from_slice(&argument.to_be_bytes());
+    }
+}
+
+fn write_value(value: &JsonValue, output: &mut Vec<u8>) {
+    match value {
+        JsonValue::Null => output.push(0xF6),
+        JsonValue::Boolean(false) => output.push(0xF4),
+        JsonValue::Boolean(true) => output.push(0xF5),
+        JsonValue::Number(n) => write_number(*n, output),
+        JsonValue::String(str) => {
+            write_head(output, 3, str.len() as u64);
+            output.extend_from_slice(str.as_bytes());
+        }
+        JsonValue::Array(arr) => {
+            write_head(output, 4, arr.len() as u64);
+            for x in arr {
+                write_value(x, output);
+            }
+        }
+        JsonValue::Object(obj) => {
+            write_head(output, 5, obj.len() as u64);
+            let mut entries = obj.iter().collect::<Vec<_>>();
+            //Shorter keys first, then bytewise: the order of the encoded keys
+            entries.sort_by(|(a, _), (b, _)| a.len().cmp(&b.len()).then(a.cmp(b)));
+            for (key, x) in entries {
+                write_head(output, 3, key.len() as u64);
+                output.extend_from_slice(key.as_bytes());
+                write_value(x, output);
+            }
+        }
+    }
+}
+
+fn write_number(n: f64, output: &mut Vec<u8>) {
+    let negative_zero = n == 0.0 && n.is_sign_negative();
+    if n.fract() == 0.0 && !negative_zero && (0.0..18446744073709551616.0).contains(&n) {
+        write_head(output, 0, n as u64);
+    } else if n.fract() == 0.0 && (-18446744073709551616.0..0.0).contains(&n) {
+        write_head(output, 1, (-1.0 - n) as u64);
+    } else if let Some(half) = f64_to_half(n) {
+        output.push(0xF9);
+        output.extend_from_slice(&half.to_be_bytes());
+    } else if (n as f32) as f64 == n {
+        output.push(0xFA);
+        output.extend_from_slice(&(n as f32).to_bits().to_be_bytes());
+    } else {
+        output.push(0xFB);
+        output.extend_from_slice(&n.to_bits().to_be_bytes());
+    }
+}
+
+///Converts to an IEEE 754 half-precision float, if that can be done exactly
+fn f64_to_half(n: f64) -> Option<u16> {
+    if n.is_nan() {
+        return Some(0x7E00);
+    }
+    let bits = (n as f32).to_bits();
+    let sign = (bits >> 16) as u16 & 0x8000;
+    let exponent = ((bits >> 23) & 0xFF) as i32 - 127 + 15;
+    let mantissa = bits & 0x7F_FFFF;
+    let half = if n.is_infinite() {
+        sign | 0x7C00
+    } else if n == 0.0 {
+        sign
+    } else if (1..=30).contains(&exponent) {
+        sign | (exponent as u16) << 10 | (mantissa >> 13) as u16
+    } else if (-10..=0).contains(&exponent) {
+        sign | ((mantissa | 0x80_0000) >> (14 - exponent)) as u16
+    } else {
+        return None;
+    };
+    Some(half).filter(|half| half_to_f64(*half) == n)
+}
+
+///Converts an IEEE 754 half-precision float, as RFC 8949 appendix D does
+fn half_to_f64(bits: u16) -> f64 {
+    let exponent = (bits >> 10) & 0x1F;
+    let mantissa = (bits & 0x3FF) as f64;
+    let magnitude = match exponent {
+        0 => mantissa * 2f64.powi(-24),
+        31 if mantissa == 0.0 => f64::INFINITY,
+        31 => f64::NAN,
+        _ => (mantissa + 1024.0) * 2f64.powi(exponent as i32 - 25),
+    };
+    if bits & 0x8000 != 0 {
+        -magnitude
+    } else {
+        magnitude
+    }
+}
+
+struct CborReader<'a> {
+    input: &'a [u8],
+    pos: usize,
+    options: &'a ParseOptions,
+}
+
+///The initial bytes of a data item
+struct Head {
+    offset: usize,
+    major: u8,
+    info: u8,
+
+    ///The length, value or float bits that follow the initial byte; None for an indefinite
+    ///length
+    argument: Option<u64>,
+}
+
+impl<'a> CborReader<'a> {
+    fn error(&self, offset: usize, message: &str) -> ParseError {
+        ParseError {
+            offset,
+            message: message.to_owned(),
+        }
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], ParseError> {
+        if n > self.input.len() - self.pos {
+            return Err(self.error(self.input.len(), "unexpected end of input"));
+        }
+        self.pos += n;
+        Ok(&self.input[self.pos - n..self.pos])
+    }
+
+    fn head(&mut self) -> Result<Head, ParseError> {
+        let offset = self.pos;
+        let initial = self.take(1)?[0];
+        let info = initial & 0x1F;
+        let argument = match info {
+            0..=23 => Some(info as u64),
+            24..=27 => Some(
+                self.take(1 << (info - 24))?
+                    .iter()
+                    .fold(0, |n, b| n << 8 | *b as u64),
+            ),
+            31 => None,
+            _ => return Err(self.error(offset, "reserved additional information")),
+        };
+        Ok(Head {
+            offset,
+            major: initial >> 5,
+            info,
+            argument,
+        })
+    }
+
+    ///Reads a count of items, which cannot exceed the bytes left since each takes at least one
+    fn count(&self, head: &Head, n: u64) -> Result<usize, ParseError> {
+        match usize::try_from(n) {
+            Ok(n) if n <= self.input.len() - self.pos => Ok(n),
+            _ => Err(self.error(head.offset, "length exceeds the input")),
+        }
+    }
+
+    fn at_break(&mut self) -> bool {
+        if self.input.get(self.pos) == Some(&BREAK) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    ///Reads the content of a byte or text string, joining the chunks of an indefinite one
+    fn string(&mut self, head: &Head) -> Result<Vec<u8>, ParseError> {
+        match head.argument {
+            Some(n) => Ok(self.take(self.count(head, n)?)?.to_vec()),
+            None => {
+                let mut output = vec![];
+                while !self.at_break() {
+                    let chunk = self.head()?;
+                    match (chunk.major == head.major, chunk.argument) {
+                        (true, Some(n)) => {
+                            output.extend_from_slice(self.take(self.count(&chunk, n)?)?)
+                        }
+                        _ => return Err(self.error(chunk.offset, "invalid chunk in string")),
+                    }
+                }
+                Ok(output)
+            }
+        }
+    }
+
+    fn item(&mut self, depth: usize) -> Result<JsonValue, ParseError> {
+        //Tags are read in a loop rather than recursively, so that a long chain of them cannot
+        //exhaust the stack. The innermost one picks how a byte string is converted, and each
+        //datetime tag is checked against what the tags inside it produced
+        let mut tags = vec![];
+        let mut head = self.head()?;
+        while head.major == 6 {
+            tags.push((head.offset, head.argument));
+            head = self.head()?;
+        }
+        let tag = tags.last().copied();
+        let value = match (head.major, head.argument) {
+            (0, Some(n)) => JsonValue::Number(n as f64),
+            (1, Some(n)) => JsonValue::Number(-1.0 - n as f64),
+            (2, _) => {
+                let bytes = self.string(&head)?;
+                JsonValue::String(match tag {
+                    Some((_, Some(TAG_BASE64))) => base64::encode(&bytes, base64::STANDARD, true),
+                    Some((_, Some(TAG_BASE16))) => {
+                        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+                    }
+                    //base64url is the default, whether or not tag 21 asks for it
+                    _ => base64::encode(&bytes, base64::URL_SAFE, false),
+                })
+            }
+            (3, _) => String::from_utf8(self.string(&head)?)
+                .map(JsonValue::String)
+                .map_err(|_| self.error(head.offset, "text string is not valid UTF-8"))?,
+            (4 | 5, _) if !self.options.allows_depth(depth) => {
+                return Err(self.error(head.offset, "nesting is too deep"));
+            }
+            (4, argument) => {
+                let mut arr = vec![];
+                match argument {
+                    Some(n) => {
+                        for _ in 0..self.count(&head, n)? {
+                            arr.push(self.item(depth + 1)?);
+                        }
+                    }
+                    None => {
+                        while !self.at_break() {
+                            arr.push(self.item(depth + 1)?);
+                        }
+                    }
+                }
+                JsonValue::Array(arr)
+            }
+            (5, argument) => {
+                let mut obj = HashMap::new();
+                let mut entry = |reader: &mut Self| {
+                    let offset = reader.pos;
+                    let key = match reader.item(depth + 1)? {
+                        JsonValue::String(key) => key,
+                        JsonValue::Number(n) if n.fract() == 0.0 => n.to_string(),
+                        _ => {
+                            return Err(reader.error(offset, "map keys must be strings or integers"))
+                        }
+                    };
+                    let value = reader.item(depth + 1)?;
+                    match obj.insert(key, value) {
+                        Some(_) => Err(reader.error(offset, "duplicate map key")),
+                        None => Ok(()),
+                    }
+                };
+                match argument {
+                    Some(n) => {
+                        for _ in 0..self.count(&head, n)? {
+                            entry(self)?;
+                        }
+                    }
+                    None => {
+                        while !self.at_break() {
+                            entry(self)?;
+                        }
+                    }
+                }
+                JsonValue::Object(obj)
+            }
+            (7, _) => match (head.info, head.argument) {
+                (20, _) => JsonValue::Boolean(false),
+                (21, _) => JsonValue::Boolean(true),
+                (22 | 23, _) => JsonValue::Null,
+                (25, Some(bits)) => JsonValue::Number(half_to_f64(bits as u16)),
+                (26, Some(bits)) => JsonValue::Number(f32::from_bits(bits as u32) as f64),
+                (27, Some(bits)) => JsonValue::Number(f64::from_bits(bits)),
+                (31, _) => return Err(self.error(head.offset, "unexpected break")),
+                _ => return Err(self.error(head.offset, "unsupported simple value")),
+            },
+            _ => return Err(self.error(head.offset, "unexpected indefinite length")),
+        };
+        tags.into_iter()
+            .rev()
+            .try_fold(value, |value, (offset, tag)| {
+                self.apply_tag(offset, tag, value)
+            })
+    }
+
+    fn apply_tag(
+        &self,
+        offset: usize,
+        tag: Option<u64>,
+        value: JsonValue,
+    ) -> Result<JsonValue, ParseError> {
+        match tag {
+            Some(TAG_DATETIME_STRING) => match &value {
+                JsonValue::String(str) if JsonDate::from_rfc3339(str).is_ok() => Ok(value),
+                _ => Err(self.error(offset, "tag 0 must hold an RFC 3339 datetime")),
+            },
+            Some(TAG_DATETIME_EPOCH) => {
+                match JsonDate::from_json_with(&value, DateFormat::UnixSeconds) {
+                    Some(date) if matches!(value, JsonValue::Number(_)) => {
+                        Ok(date.to_json_with(DateFormat::Rfc3339))
+                    }
+                    _ => Err(self.error(offset, "tag 1 must hold a Unix timestamp")),
+                }
+            }
+            None => Err(self.error(offset, "tags cannot have an indefinite length")),
+            _ => Ok(value),
+        }
+    }
+}
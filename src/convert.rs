@@ -0,0 +1,587 @@
+use crate::{JsonDate, JsonDateOnly, JsonDuration, JsonTime, JsonValue, ValidationError};
+use std::collections::{BTreeMap, HashMap};
+use std::hash::BuildHasher;
+use std::rc::Rc;
+use std::sync::Arc;
+
+///Converts a value into a JsonValue. Implement it by hand or, with the `derive` feature, with
+///`#[derive(ToJson)]`
+pub trait ToJson {
+    fn to_json(&self) -> JsonValue;
+}
+
+///Marks `ToJson` types which always convert to an object, as the content of an internally
+///tagged newtype variant must (the tag is added to it). `#[derive(ToJson)]` implements it for
+///structs with named fields and for internally or adjacently tagged enums
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` does not always convert to a JSON object",
+    note = "the content of an internally tagged newtype variant must convert to an object"
+)]
+pub trait ToJsonObject: ToJson {}
+
+///Converts a JsonValue into a value. Implement it by hand or, with the `derive` feature, with
+///`#[derive(FromJson)]`
+pub trait FromJson: Sized {
+    fn from_json(input: &JsonValue) -> Result<Self, FromJsonError>;
+}
+
+///The reason a JsonValue could not be converted by `FromJson`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FromJsonError {
+    ///The JSON Pointer (RFC 6901) of the value that could not be converted, e.g. /items/0/id
+    ///(the empty string is the whole input)
+    pub path: String,
+
+    ///What was wrong with the value
+    pub message: String,
+}
+
+impl FromJsonError {
+    ///An error with the given message about the whole input. Use `FromJsonError::within` to
+    ///move it to where the input was found
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            path: String::new(),
+            message: message.into(),
+        }
+    }
+
+    ///An error for an input of the wrong kind, e.g. `FromJsonError::expected("a string", input)`
+    pub fn expected(expected: &str, found: &JsonValue) -> Self {
+        let found = match found {
+            JsonValue::Null => "null".to_owned(),
+            JsonValue::Boolean(_) => "a boolean".to_owned(),
+            JsonValue::Number(_) => "a number".to_owned(),
+            JsonValue::String(_) => "a string".to_owned(),
+            JsonValue::Array(arr) if arr.len() == 1 => "an array of 1 element".to_owned(),
+            JsonValue::Array(arr) => format!("an array of {} elements", arr.len()),
+            JsonValue::Object(_) => "an object".to_owned(),
+        };
+        Self::new(format!("expected {}, found {}", expected, found))
+    }
+
+    ///An error for an object which is missing a required key
+    pub fn missing(key: &str) -> Self {
+        Self::new(format!("missing key {:?}", key))
+    }
+
+    ///Prefixes the path with an object key or array index, for an error which came from
+    ///converting the value found there
+    pub fn within(mut self, token: &str) -> Self {
+        self.path = ValidationError::child_path("", token) + &self.path;
+        self
+    }
+}
+
+impl std::fmt::Display for FromJsonError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let path = if self.path.is_empty() {
+            "/"
+        } else {
+            &self.path
+        };
+        write!(f, "{}: {}", path, self.message)
+    }
+}
+
+impl std::error::Error for FromJsonError {}
+
+impl ToJson for JsonValue {
+    fn to_json(&self) -> JsonValue {
+        self.clone()
+    }
+}
+
+impl FromJson for JsonValue {
+    fn from_json(input: &JsonValue) -> Result<Self, FromJsonError> {
+        Ok(input.clone())
+    }
+}
+
+impl ToJson for () {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::Null
+    }
+}
+
+impl FromJson for () {
+    fn from_json(input: &JsonValue) -> Result<Self, FromJsonError> {
+        match input {
+            JsonValue::Null => Ok(()),
+            other => Err(FromJsonError::expected("null", other)),
+        }
+    }
+}
+
+impl ToJson for bool {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::Boolean(*self)
+    }
+}
+
+impl FromJson for bool {
+    fn from_json(input: &JsonValue) -> Result<Self, FromJsonError> {
+        match input {
+            JsonValue::Boolean(b) => Ok(*b),
+            other => Err(FromJsonError::expected("a boolean", other)),
+        }
+    }
+}
+
+macro_rules! integer_conversions {
+    ($($t:ty),*) => {$(
+        impl ToJson for $t {
+            fn to_json(&self) -> JsonValue {
+                JsonValue::Number(*self as f64)
+            }
+        }
+
+        impl FromJson for $t {
+            ///Converts only whole numbers within the range of the type
+            fn from_json(input: &JsonValue) -> Result<Self, FromJsonError> {
+                //MAX rounds up to a power of two for the wider types, so MAX + 1 is an exclusive
+                //bound that is exact for every type
+                match input {
+                    JsonValue::Number(n)
+                        if n.fract() == 0_f64
+                            && *n >= <$t>::MIN as f64
+                            && *n < <$t>::MAX as f64 + 1_f64 =>
+                    {
+                        Ok(*n as $t)
+                    }
+                    JsonValue::Number(n) => Err(FromJsonError::new(format!(
+                        "expected an integer from {} to {}, found {}",
+                        <$t>::MIN,
+                        <$t>::MAX,
+                        n
+                    ))),
+                    other => Err(FromJsonError::expected("an integer", other)),
+                }
+            }
+        }
+    )*};
+}
+
+integer_conversions!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+macro_rules! float_conversions {
+    ($($t:ty),*) => {$(
+        impl ToJson for $t {
+            ///Converts NaN and the infinities, which JSON cannot represent, to null
+            fn to_json(&self) -> JsonValue {
+                if self.is_finite() {
+                    JsonValue::Number(*self as f64)
+                } else {
+                    JsonValue::Null
+                }
+            }
+        }
+
+        impl FromJson for $t {
+            ///Fails on finite numbers too large for the type, rather than converting them to
+            ///infinity
+            fn from_json(input: &JsonValue) -> Result<Self, FromJsonError> {
+                match input {
+                    JsonValue::Number(n) if n.is_finite() && (*n as $t).is_infinite() => {
+                        Err(FromJsonError::new(format!(
+                            "expected a number from {} to {}, found {}",
+                            <$t>::MIN,
+                            <$t>::MAX,
+                            n
+                        )))
+                    }
+                    JsonValue::Number(n) => Ok(*n as $t),
+                    other => Err(FromJsonError::expected("a number", other)),
+                }
+            }
+        }
+    )*};
+}
+
+float_conversions!(f32, f64);
+
+impl ToJson for char {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::String(self.to_string())
+    }
+}
+
+impl FromJson for char {
+    fn from_json(input: &JsonValue) -> Result<Self, FromJsonError> {
+        let mut chars = match input {
+            JsonValue::String(s) => s.chars(),
+            other => return Err(FromJsonError::expected("a single character string", other)),
+        };
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Ok(c),
+            _ => Err(FromJsonError::new("expected a single character string")),
+        }
+    }
+}
+
+impl ToJson for str {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::String(self.to_owned())
+    }
+}
+
+impl ToJson for String {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::String(self.clone())
+    }
+}
+
+impl FromJson for String {
+    fn from_json(input: &JsonValue) -> Result<Self, FromJsonError> {
+        match input {
+            JsonValue::String(s) => Ok(s.clone()),
+            other => Err(FromJsonError::expected("a string", other)),
+        }
+    }
+}
+
+impl<T: ToJson + ?Sized> ToJson for &T {
+    fn to_json(&self) -> JsonValue {
+        (**self).to_json()
+    }
+}
+
+impl<T: ToJsonObject + ?Sized> ToJsonObject for &T {}
+
+macro_rules! pointer_conversions {
+    ($($p:ident),*) => {$(
+        impl<T: ToJson + ?Sized> ToJson for $p<T> {
+            fn to_json(&self) -> JsonValue {
+                (**self).to_json()
+            }
+        }
+
+        impl<T: ToJsonObject + ?Sized> ToJsonObject for $p<T> {}
+
+        impl<T: FromJson> FromJson for $p<T> {
+            fn from_json(input: &JsonValue) -> Result<Self, FromJsonError> {
+                T::from_json(input).map($p::new)
+            }
+        }
+    )*};
+}
+
+pointer_conversions!(Box, Rc, Arc);
+
+impl<T: ToJson> ToJson for Option<T> {
+    ///Converts None to null
+    fn to_json(&self) -> JsonValue {
+        match self {
+            Some(value) => value.to_json(),
+            None => JsonValue::Null,
+        }
+    }
+}
+
+impl<T: FromJson> FromJson for Option<T> {
+    ///Converts null to None
+    fn from_json(input: &JsonValue) -> Result<Self, FromJsonError> {
+        match input {
+            JsonValue::Null => Ok(None),
+            value => T::from_json(value).map(Some),
+        }
+    }
+}
+
+impl<T: ToJson> ToJson for [T] {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::Array(self.iter().map(ToJson::to_json).collect())
+    }
+}
+
+impl<T: ToJson> ToJson for Vec<T> {
+    fn to_json(&self) -> JsonValue {
+        self.as_slice().to_json()
+    }
+}
+
+impl<T: FromJson> FromJson for Vec<T> {
+    fn from_json(input: &JsonValue) -> Result<Self, FromJsonError> {
+        match input {
+            JsonValue::Array(arr) => arr
+                .iter()
+                .enumerate()
+                .map(|(i, x)| T::from_json(x).map_err(|e| e.within(&i.to_string())))
+                .collect(),
+            other => Err(FromJsonError::expected("an array", other)),
+        }
+    }
+}
+
+impl<T: ToJson, const N: usize> ToJson for [T; N] {
+    fn to_json(&self) -> JsonValue {
+        self.as_slice().to_json()
+    }
+}
+
+impl<T: FromJson, const N: usize> FromJson for [T; N] {
+    ///Converts only arrays with exactly `N` elements
+    fn from_json(input: &JsonValue) -> Result<Self, FromJsonError> {
+        match input {
+            JsonValue::Array(arr) if arr.len() == N => {
+                Vec::<T>::from_json(input).map(|v| v.try_into().unwrap_or_else(|_| unreachable!()))
+            }
+            other => Err(FromJsonError::expected(
+                &format!("an array of {} elements", N),
+                other,
+            )),
+        }
+    }
+}
+
+macro_rules! tuple_conversions {
+    ($($len:literal => ($($t:ident $i:tt),+)),*) => {$(
+        impl<$($t: ToJson),+> ToJson for ($($t,)+) {
+            fn to_json(&self) -> JsonValue {
+                JsonValue::Array(vec![$(self.$i.to_json()),+])
+            }
+        }
+
+        impl<$($t: FromJson),+> FromJson for ($($t,)+) {
+            ///Converts only arrays with exactly as many elements as the tuple
+            fn from_json(input: &JsonValue) -> Result<Self, FromJsonError> {
+                match input {
+                    JsonValue::Array(arr) if arr.len() == $len => Ok(($(
+                        $t::from_json(&arr[$i]).map_err(|e| e.within(stringify!($i)))?,
+                    )+)),
+                    other => Err(FromJsonError::expected(
+                        concat!("an array of ", $len, " elements"),
+                        other,
+                    )),
+                }
+            }
+        }
+    )*};
+}
+
+tuple_conversions!(
+    1 => (A 0),
+    2 => (A 0, B 1),
+    3 => (A 0, B 1, C 2),
+    4 => (A 0, B 1, C 2, D 3),
+    5 => (A 0, B 1, C 2, D 3, E 4),
+    6 => (A 0, B 1, C 2, D 3, E 4, F 5),
+    7 => (A 0, B 1, C 2, D 3, E 4, F 5, G 6),
+    8 => (A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7),
+    9 => (A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8),
+    10 => (A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9),
+    11 => (A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10),
+    12 => (A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10, L 11)
+);
+
+impl<T: ToJson, S: BuildHasher> ToJson for HashMap<String, T, S> {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::Object(
+            self.iter()
+                .map(|(key, value)| (key.clone(), value.to_json()))
+                .collect(),
+        )
+    }
+}
+
+impl<T: ToJson, S: BuildHasher> ToJsonObject for HashMap<String, T, S> {}
+
+impl<T: FromJson, S: BuildHasher + Default> FromJson for HashMap<String, T, S> {
+    fn from_json(input: &JsonValue) -> Result<Self, FromJsonError> {
+        match input {
+            JsonValue::Object(obj) => obj
+                .iter()
+                .map(|(key, value)| {
+                    Ok((key.clone(), T::from_json(value).map_err(|e| e.within(key))?))
+                })
+                .collect(),
+            other => Err(FromJsonError::expected("an object", other)),
+        }
+    }
+}
+
+impl<T: ToJson> ToJson for BTreeMap<String, T> {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::Object(
+            self.iter()
+                .map(|(key, value)| (key.clone(), value.to_json()))
+                .collect(),
+        )
+    }
+}
+
+impl<T: ToJson> ToJsonObject for BTreeMap<String, T> {}
+
+impl<T: FromJson> FromJson for BTreeMap<String, T> {
+    fn from_json(input: &JsonValue) -> Result<Self, FromJsonError> {
+        match input {
+            JsonValue::Object(obj) => obj
+                .iter()
+                .map(|(key, value)| {
+                    Ok((key.clone(), T::from_json(value).map_err(|e| e.within(key))?))
+                })
+                .collect(),
+            other => Err(FromJsonError::expected("an object", other)),
+        }
+    }
+}
+
+macro_rules! date_conversions {
+    ($($t:ty => $expected:literal),*) => {$(
+        impl ToJson for $t {
+            ///Converts to an ISO 8601 string
+            fn to_json(&self) -> JsonValue {
+                JsonValue::String(self.to_string())
+            }
+        }
+
+        impl FromJson for $t {
+            ///Converts an ISO 8601 string
+            fn from_json(input: &JsonValue) -> Result<Self, FromJsonError> {
+                match input {
+                    JsonValue::String(s) => <$t>::from_iso8601(s)
+                        .map_err(|e| FromJsonError::new(format!("invalid {}: {}", $expected, e))),
+                    other => Err(FromJsonError::expected(concat!("a ", $expected, " string"), other)),
+                }
+            }
+        }
+    )*};
+}
+
+date_conversions!(
+    JsonDate => "datetime",
+    JsonDateOnly => "date",
+    JsonTime => "time",
+    JsonDuration => "duration"
+);
+
+impl From<bool> for JsonValue {
+    fn from(b: bool) -> Self {
+        JsonValue::Boolean(b)
+    }
+}
+
+macro_rules! number_from {
+    ($($t:ty),*) => {$(
+        impl From<$t> for JsonValue {
+            ///Converts to a Number. Integers beyond 2^53 in magnitude may be rounded, since a
+            ///Number is an f64
+            fn from(n: $t) -> Self {
+                JsonValue::Number(n as f64)
+            }
+        }
+    )*};
+}
+
+number_from!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize, f32, f64);
+
+impl From<&str> for JsonValue {
+    fn from(s: &str) -> Self {
+        JsonValue::String(s.to_owned())
+    }
+}
+
+impl From<String> for JsonValue {
+    fn from(s: String) -> Self {
+        JsonValue::String(s)
+    }
+}
+
+impl From<&String> for JsonValue {
+    fn from(s: &String) -> Self {
+        JsonValue::String(s.clone())
+    }
+}
+
+impl From<char> for JsonValue {
+    fn from(c: char) -> Self {
+        JsonValue::String(c.to_string())
+    }
+}
+
+impl<T: Into<JsonValue>> From<Vec<T>> for JsonValue {
+    fn from(v: Vec<T>) -> Self {
+        JsonValue::Array(v.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<T: Into<JsonValue>> From<Option<T>> for JsonValue {
+    ///Converts None to null
+    fn from(o: Option<T>) -> Self {
+        o.map_or(JsonValue::Null, Into::into)
+    }
+}
+
+impl From<HashMap<String, JsonValue>> for JsonValue {
+    fn from(obj: HashMap<String, JsonValue>) -> Self {
+        JsonValue::Object(obj)
+    }
+}
+
+impl From<()> for JsonValue {
+    fn from(_: ()) -> Self {
+        JsonValue::Null
+    }
+}
+
+impl FromIterator<JsonValue> for JsonValue {
+    ///Collects into an Array
+    fn from_iter<I: IntoIterator<Item = JsonValue>>(iter: I) -> Self {
+        JsonValue::Array(iter.into_iter().collect())
+    }
+}
+
+impl FromIterator<(String, JsonValue)> for JsonValue {
+    ///Collects into an Object. Later entries replace earlier ones with the same key
+    fn from_iter<I: IntoIterator<Item = (String, JsonValue)>>(iter: I) -> Self {
+        JsonValue::Object(iter.into_iter().collect())
+    }
+}
+
+macro_rules! try_from_json_value {
+    ($($t:ty),*) => {$(
+        impl TryFrom<JsonValue> for $t {
+            type Error = FromJsonError;
+
+            ///Converts as `FromJson` does
+            fn try_from(input: JsonValue) -> Result<Self, FromJsonError> {
+                <$t>::from_json(&input)
+            }
+        }
+    )*};
+}
+
+try_from_json_value!(bool, i8, i16, i32, i64, isize, u8, u16, u32, u64, usize, f32, f64, char);
+
+impl TryFrom<JsonValue> for String {
+    type Error = FromJsonError;
+
+    fn try_from(input: JsonValue) -> Result<Self, FromJsonError> {
+        match input {
+            JsonValue::String(s) => Ok(s),
+            other => Err(FromJsonError::expected("a string", &other)),
+        }
+    }
+}
+
+impl TryFrom<JsonValue> for Vec<JsonValue> {
+    type Error = FromJsonError;
+
+    fn try_from(input: JsonValue) -> Result<Self, FromJsonError> {
+        match input {
+            JsonValue::Array(arr) => Ok(arr),
+            other => Err(FromJsonError::expected("an array", &other)),
+        }
+    }
+}
+
+impl TryFrom<JsonValue> for HashMap<String, JsonValue> {
+    type Error = FromJsonError;
+
+    fn try_from(input: JsonValue) -> Result<Self, FromJsonError> {
+        match input {
+            JsonValue::Object(obj) => Ok(obj),
+            other => Err(FromJsonError::expected("an object", &other)),
+        }
+    }
+}
@@ -0,0 +1,1523 @@
+use crate::JsonValue;
+use std::cmp::Ordering;
+use std::ops::{Add, Sub};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+///A structure for holding a DateTime, having been parsed or intended to parsed to standard
+///JavaScript format for dates (YYYY-MM-DDTHH:mm:ss), optionally with fractional seconds and a
+///UTC offset as RFC 3339 allows (e.g. 2023-06-06T12:30:00.25+05:30)
+///
+///Dates are ordered chronologically: two dates with different offsets are compared by the
+///instant they describe, and a date without an offset is taken to be in UTC. Dates that
+///describe the same instant in different ways (e.g. 12:00Z and 13:00+01:00) are still
+///distinct, and are ordered by their fields
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct JsonDate {
+    ///The actual year
+    year: u16,
+
+    ///The actual month (1 is January, 12 is December)
+    month: u8,
+
+    ///The actual date (e.g. 31 for January 31st)
+    date: u8,
+
+    ///The hour on a 24-hour scale (where 0 is midnight and 23 is 11 PM)
+    hour: u8,
+
+    ///The minute, zero-indexed
+    minute: u8,
+
+    ///The second, zero-indexed
+    second: u8,
+
+    ///The fraction of the second, in nanoseconds
+    nanosecond: u32,
+
+    ///The offset from UTC in minutes (e.g. 330 for +05:30), or None for a local time whose
+    ///offset is unknown
+    offset: Option<i16>,
+}
+
+///A component of a date and time, as named by `DateError`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DateComponent {
+    Year,
+    Month,
+    ///The day of the month
+    Date,
+    ///The week of an ISO 8601 week date
+    Week,
+    ///The day of the week of an ISO 8601 week date
+    Weekday,
+    ///The day of the year of an ISO 8601 ordinal date
+    DayOfYear,
+    Hour,
+    Minute,
+    Second,
+    ///The fractional seconds
+    Fraction,
+    ///The UTC offset
+    Offset,
+    ///The designators of an ISO 8601 duration (e.g. a duration with no components, or with
+    ///its components out of order)
+    Duration,
+}
+
+impl std::fmt::Display for DateComponent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            DateComponent::Year => "year",
+            DateComponent::Month => "month",
+            DateComponent::Date => "day of the month",
+            DateComponent::Week => "week",
+            DateComponent::Weekday => "day of the week",
+            DateComponent::DayOfYear => "day of the year",
+            DateComponent::Hour => "hour",
+            DateComponent::Minute => "minute",
+            DateComponent::Second => "second",
+            DateComponent::Fraction => "fractional seconds",
+            DateComponent::Offset => "UTC offset",
+            DateComponent::Duration => "duration",
+        })
+    }
+}
+
+///The reason a string could not be parsed as a `JsonDate`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DateError {
+    ///The component is missing, or is not written the way the format requires
+    Malformed(DateComponent),
+
+    ///The component was read but is out of range (e.g. month 13, February 29th in a common
+    ///year, or second 60 outside of a leap second)
+    OutOfRange(DateComponent),
+
+    ///The date is followed by characters that are not part of it, starting at this byte offset
+    TrailingCharacters(usize),
+}
+
+impl std::fmt::Display for DateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DateError::Malformed(component) => write!(f, "malformed {}", component),
+            DateError::OutOfRange(component) => write!(f, "{} out of range", component),
+            DateError::TrailingCharacters(offset) => {
+                write!(f, "unexpected characters at byte {}", offset)
+            }
+        }
+    }
+}
+
+impl std::error::Error for DateError {}
+
+///A day of the week
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl Weekday {
+    ///The ISO 8601 number of this day, from 1 for Monday to 7 for Sunday
+    pub fn number_from_monday(&self) -> u8 {
+        *self as u8 + 1
+    }
+}
+
+const WEEKDAY_NAMES: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+const MONTH_NAMES: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+///The ways a JsonDate may be encoded in JSON. See `JsonValue::get_json_date_with` and
+///`JsonDate::to_json_with`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DateFormat {
+    ///A string in any of the ISO 8601 forms `JsonDate::from_iso8601` accepts
+    Iso8601,
+
+    ///A strict RFC 3339 string (e.g. 2024-05-01T13:45:00Z)
+    Rfc3339,
+
+    ///A number of seconds (which may be fractional) since 1970-01-01T00:00:00Z
+    UnixSeconds,
+
+    ///A number of milliseconds since 1970-01-01T00:00:00Z, as JavaScript's `Date.now()`
+    ///returns
+    UnixMillis,
+
+    ///An RFC 2822 string, as used by email and HTTP (e.g. Wed, 01 May 2024 13:45:00 +0200)
+    Rfc2822,
+
+    ///An ASP.NET string of milliseconds since 1970-01-01T00:00:00Z and an optional UTC
+    ///offset (e.g. /Date(1714563900000)/ or /Date(1714563900000+0200)/)
+    AspNet,
+}
+
+///Reads the fixed-width numeric fields of date and time strings
+struct DateCursor<'a> {
+    input: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> DateCursor<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.input.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, b: u8) -> bool {
+        if self.peek() == Some(b) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn eat_any(&mut self, bytes: &[u8]) -> Option<u8> {
+        let b = self.peek().filter(|b| bytes.contains(b))?;
+        self.pos += 1;
+        Some(b)
+    }
+
+    ///Consumes `b`, which must introduce `component`
+    fn expect(&mut self, b: u8, component: DateComponent) -> Result<(), DateError> {
+        if self.eat(b) {
+            Ok(())
+        } else {
+            Err(DateError::Malformed(component))
+        }
+    }
+
+    fn at_end(&self) -> bool {
+        self.pos == self.input.len()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|b| b.is_ascii_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    ///Reads a run of ASCII letters
+    fn word(&mut self) -> &'a str {
+        let start = self.pos;
+        while self.peek().is_some_and(|b| b.is_ascii_alphabetic()) {
+            self.pos += 1;
+        }
+        std::str::from_utf8(&self.input[start..self.pos]).unwrap_or_default()
+    }
+
+    fn finish(&self) -> Result<(), DateError> {
+        if self.at_end() {
+            Ok(())
+        } else {
+            Err(DateError::TrailingCharacters(self.pos))
+        }
+    }
+
+    fn count_digits(&self) -> usize {
+        self.input[self.pos..]
+            .iter()
+            .take_while(|b| b.is_ascii_digit())
+            .count()
+    }
+
+    ///Reads between `min` and `max` digits (as many as are present) of `component`, failing if
+    ///fewer than `min` are present
+    fn digits(
+        &mut self,
+        min: usize,
+        max: usize,
+        component: DateComponent,
+    ) -> Result<u32, DateError> {
+        let len = self.count_digits().min(max);
+        if len < min {
+            return Err(DateError::Malformed(component));
+        }
+        let value = self.input[self.pos..self.pos + len]
+            .iter()
+            .fold(0_u32, |value, b| value * 10 + (b - b'0') as u32);
+        self.pos += len;
+        Ok(value)
+    }
+
+    ///Reads a fraction of a second (the digits after the decimal mark) as nanoseconds. Digits
+    ///beyond the ninth are read but ignored
+    fn nanoseconds(&mut self) -> Result<u32, DateError> {
+        let len = self.count_digits();
+        if len == 0 {
+            return Err(DateError::Malformed(DateComponent::Fraction));
+        }
+        let nanosecond = self.input[self.pos..self.pos + len.min(9)]
+            .iter()
+            .chain(std::iter::repeat(&b'0'))
+            .take(9)
+            .fold(0_u32, |value, b| value * 10 + (b - b'0') as u32);
+        self.pos += len;
+        Ok(nanosecond)
+    }
+
+    ///Reads a UTC offset written as a sign followed by four digits (e.g. +0200), if there is
+    ///one
+    fn numeric_offset(&mut self) -> Result<Option<i16>, DateError> {
+        let sign = match self.eat_any(b"+-") {
+            Some(b'+') => 1,
+            Some(_) => -1,
+            None => return Ok(None),
+        };
+        let offset = self.digits(4, 4, DateComponent::Offset)?;
+        if offset / 100 > 23 || offset % 100 > 59 {
+            return Err(DateError::OutOfRange(DateComponent::Offset));
+        }
+        Ok(Some(sign * (offset / 100 * 60 + offset % 100) as i16))
+    }
+
+    ///Reads a UTC offset: "Z" or "z", or a sign followed by hours and (optionally, unless
+    ///`strict`) minutes, separated by a colon (which, unless `strict`, may be omitted). Offsets
+    ///range from -23:59 to +23:59
+    fn offset(&mut self, strict: bool) -> Result<i16, DateError> {
+        if self.eat_any(b"Zz").is_some() {
+            return Ok(0);
+        }
+        let sign = match self.eat_any(b"+-") {
+            Some(b'+') => 1,
+            Some(_) => -1,
+            None => return Err(DateError::Malformed(DateComponent::Offset)),
+        };
+        let hours = self.digits(2, 2, DateComponent::Offset)?;
+        let minutes = if self.eat(b':') {
+            self.digits(2, 2, DateComponent::Offset)?
+        } else if strict {
+            return Err(DateError::Malformed(DateComponent::Offset));
+        } else if self.count_digits() > 0 {
+            self.digits(2, 2, DateComponent::Offset)?
+        } else {
+            0
+        };
+        if hours > 23 || minutes > 59 {
+            return Err(DateError::OutOfRange(DateComponent::Offset));
+        }
+        Ok(sign * (hours * 60 + minutes) as i16)
+    }
+}
+
+impl JsonDate {
+    const NANOS_PER_SECOND: i128 = 1_000_000_000;
+    const NANOS_PER_DAY: i128 = 86_400 * Self::NANOS_PER_SECOND;
+
+    ///Creates a date and time with no fractional seconds or UTC offset, failing if it is not a
+    ///real calendar date (e.g. February 30th) or time of day. Second 60 is allowed as a leap
+    ///second at the end of minute 59
+    pub fn new(
+        year: u16,
+        month: u8,
+        date: u8,
+        hour: u8,
+        minute: u8,
+        second: u8,
+    ) -> Result<JsonDate, DateError> {
+        Self {
+            year,
+            month,
+            date,
+            hour,
+            minute,
+            second,
+            nanosecond: 0,
+            offset: None,
+        }
+        .validated()
+    }
+
+    ///Returns this date with its fractional seconds set to `nanosecond`, which must be less
+    ///than one second
+    pub fn with_nanosecond(self, nanosecond: u32) -> Result<JsonDate, DateError> {
+        Self { nanosecond, ..self }.validated()
+    }
+
+    ///Returns this date with its UTC offset (in minutes, from -23:59 to +23:59) set to
+    ///`offset`, keeping the same local date and time. See `JsonDate::to_offset` to keep the
+    ///same instant instead
+    pub fn with_offset(self, offset: Option<i16>) -> Result<JsonDate, DateError> {
+        Self { offset, ..self }.validated()
+    }
+
+    ///Returns the same instant as this date, expressed with the UTC offset `offset` (in
+    ///minutes). A date without an offset is taken to be in UTC
+    pub fn to_offset(&self, offset: i16) -> Result<JsonDate, DateError> {
+        Self {
+            offset: Some(offset),
+            ..*self
+        }
+        .validated()?;
+        Self::from_instant(self.unix_timestamp_nanos(), Some(offset))
+    }
+
+    ///The current date and time in UTC
+    pub fn now() -> JsonDate {
+        SystemTime::now()
+            .try_into()
+            .expect("the system clock is set beyond the year 9999")
+    }
+
+    pub fn year(&self) -> u16 {
+        self.year
+    }
+
+    ///The month, from 1 for January to 12 for December
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+
+    ///The day of the month, starting from 1
+    pub fn date(&self) -> u8 {
+        self.date
+    }
+
+    pub fn hour(&self) -> u8 {
+        self.hour
+    }
+
+    pub fn minute(&self) -> u8 {
+        self.minute
+    }
+
+    ///The second, which is 60 during a leap second
+    pub fn second(&self) -> u8 {
+        self.second
+    }
+
+    ///The fraction of the second, in nanoseconds
+    pub fn nanosecond(&self) -> u32 {
+        self.nanosecond
+    }
+
+    ///The offset from UTC in minutes (e.g. 330 for +05:30), or None for a local time whose
+    ///offset is unknown
+    pub fn offset(&self) -> Option<i16> {
+        self.offset
+    }
+
+    pub fn weekday(&self) -> Weekday {
+        //1970-01-01 was a Thursday
+        match (Self::days_from_civil(self.year as i64, self.month as u32, self.date as u32) + 3)
+            .rem_euclid(7)
+        {
+            0 => Weekday::Monday,
+            1 => Weekday::Tuesday,
+            2 => Weekday::Wednesday,
+            3 => Weekday::Thursday,
+            4 => Weekday::Friday,
+            5 => Weekday::Saturday,
+            _ => Weekday::Sunday,
+        }
+    }
+
+    ///The number of whole seconds since 1970-01-01T00:00:00Z, rounded down. A date without an
+    ///offset is taken to be in UTC, and a leap second is counted as the first second of the
+    ///next minute
+    pub fn unix_timestamp(&self) -> i64 {
+        self.unix_timestamp_nanos()
+            .div_euclid(Self::NANOS_PER_SECOND) as i64
+    }
+
+    ///The number of whole milliseconds since 1970-01-01T00:00:00Z, rounded down
+    pub fn unix_timestamp_millis(&self) -> i64 {
+        self.unix_timestamp_nanos().div_euclid(1_000_000) as i64
+    }
+
+    ///The number of nanoseconds since 1970-01-01T00:00:00Z
+    pub fn unix_timestamp_nanos(&self) -> i128 {
+        let days = Self::days_from_civil(self.year as i64, self.month as u32, self.date as u32);
+        let seconds = days * 86_400 + self.hour as i64 * 3600 + self.minute as i64 * 60
+            - self.offset.unwrap_or(0) as i64 * 60
+            + self.second as i64;
+        seconds as i128 * Self::NANOS_PER_SECOND + self.nanosecond as i128
+    }
+
+    ///Creates a UTC date from a number of seconds since 1970-01-01T00:00:00Z, failing if it
+    ///falls outside of the years 0 to 9999
+    pub fn from_unix_timestamp(seconds: i64) -> Result<JsonDate, DateError> {
+        Self::from_unix_timestamp_nanos(seconds as i128 * Self::NANOS_PER_SECOND)
+    }
+
+    ///Creates a UTC date from a number of milliseconds since 1970-01-01T00:00:00Z
+    pub fn from_unix_timestamp_millis(millis: i64) -> Result<JsonDate, DateError> {
+        Self::from_unix_timestamp_nanos(millis as i128 * 1_000_000)
+    }
+
+    ///Creates a UTC date from a number of nanoseconds since 1970-01-01T00:00:00Z
+    pub fn from_unix_timestamp_nanos(nanos: i128) -> Result<JsonDate, DateError> {
+        Self::from_instant(nanos, Some(0))
+    }
+
+    ///Adds `duration`, keeping the same UTC offset. Returns None if the result is after the
+    ///year 9999
+    pub fn checked_add(&self, duration: Duration) -> Option<JsonDate> {
+        let nanos = self
+            .unix_timestamp_nanos()
+            .checked_add(duration.as_nanos().try_into().ok()?)?;
+        Self::from_instant(nanos, self.offset).ok()
+    }
+
+    ///Subtracts `duration`, keeping the same UTC offset. Returns None if the result is before
+    ///the year 0
+    pub fn checked_sub(&self, duration: Duration) -> Option<JsonDate> {
+        let nanos = self
+            .unix_timestamp_nanos()
+            .checked_sub(duration.as_nanos().try_into().ok()?)?;
+        Self::from_instant(nanos, self.offset).ok()
+    }
+
+    ///The time elapsed from `earlier` to this date, or None if `earlier` is actually later
+    pub fn duration_since(&self, earlier: &JsonDate) -> Option<Duration> {
+        let nanos = self.unix_timestamp_nanos() - earlier.unix_timestamp_nanos();
+        if nanos < 0 {
+            return None;
+        }
+        Some(Duration::new(
+            (nanos / Self::NANOS_PER_SECOND) as u64,
+            (nanos % Self::NANOS_PER_SECOND) as u32,
+        ))
+    }
+
+    ///Creates the date which is `nanos` nanoseconds after 1970-01-01T00:00:00Z, in the
+    ///given UTC offset
+    fn from_instant(nanos: i128, offset: Option<i16>) -> Result<JsonDate, DateError> {
+        let local = nanos + offset.unwrap_or(0) as i128 * 60 * Self::NANOS_PER_SECOND;
+        let days = local.div_euclid(Self::NANOS_PER_DAY);
+        let time = local.rem_euclid(Self::NANOS_PER_DAY);
+        let (year, month, date) = Self::civil_from_days(
+            days.try_into()
+                .map_err(|_| DateError::OutOfRange(DateComponent::Year))?,
+        );
+        if !(0..=9999).contains(&year) {
+            return Err(DateError::OutOfRange(DateComponent::Year));
+        }
+        let seconds = (time / Self::NANOS_PER_SECOND) as u32;
+        Self {
+            year: year as u16,
+            month: month as u8,
+            date: date as u8,
+            hour: (seconds / 3600) as u8,
+            minute: (seconds / 60 % 60) as u8,
+            second: (seconds % 60) as u8,
+            nanosecond: (time % Self::NANOS_PER_SECOND) as u32,
+            offset,
+        }
+        .validated()
+    }
+
+    ///Generates a JsonDate from a typical JavaScript date formatted string. It is tolerant of
+    ///missing values (for example, 2023-06-06 will be parsed just fine) and accepts any of the
+    ///ISO 8601 forms `JsonDate::from_iso8601` does, but returns None for impossible dates
+    ///(e.g. 2023-02-29 or 2023-06-06T25:00)
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(input: &str) -> Option<JsonDate> {
+        Self::from_iso8601(input).ok()
+    }
+
+    ///Strictly parses an RFC 3339 timestamp (e.g. 2023-06-06T12:30:00.25Z or
+    ///2023-06-06t12:30:00+05:30), which must have every field and a UTC offset
+    pub fn from_rfc3339(input: &str) -> Result<JsonDate, DateError> {
+        let mut cursor = DateCursor {
+            input: input.as_bytes(),
+            pos: 0,
+        };
+        let year = cursor.digits(4, 4, DateComponent::Year)?;
+        cursor.expect(b'-', DateComponent::Month)?;
+        let month = cursor.digits(2, 2, DateComponent::Month)?;
+        cursor.expect(b'-', DateComponent::Date)?;
+        let date = cursor.digits(2, 2, DateComponent::Date)?;
+        cursor
+            .eat_any(b"Tt")
+            .ok_or(DateError::Malformed(DateComponent::Hour))?;
+        let hour = cursor.digits(2, 2, DateComponent::Hour)?;
+        cursor.expect(b':', DateComponent::Minute)?;
+        let minute = cursor.digits(2, 2, DateComponent::Minute)?;
+        cursor.expect(b':', DateComponent::Second)?;
+        let second = cursor.digits(2, 2, DateComponent::Second)?;
+        let nanosecond = if cursor.eat(b'.') {
+            cursor.nanoseconds()?
+        } else {
+            0
+        };
+        let offset = cursor.offset(true)?;
+        cursor.finish()?;
+        Self {
+            year: year as u16,
+            month: month as u8,
+            date: date as u8,
+            hour: hour as u8,
+            minute: minute as u8,
+            second: second as u8,
+            nanosecond,
+            offset: Some(offset),
+        }
+        .validated()
+    }
+
+    ///Leniently parses an ISO 8601 date or date and time. The date may be a calendar date
+    ///(2023-06-06 or 20230606, where the month and day may also be omitted), a week date
+    ///(2023-W23-2 or 2023W232) or an ordinal date (2023-157 or 2023157). The time, which may
+    ///follow a "T" or a space, may omit its seconds or minutes, may be in basic format
+    ///(123000), and may have fractional seconds (after "." or ",") and a UTC offset ("Z",
+    ///+05:30, +0530 or +05)
+    pub fn from_iso8601(input: &str) -> Result<JsonDate, DateError> {
+        let mut cursor = DateCursor {
+            input: input.trim().as_bytes(),
+            pos: 0,
+        };
+        let date = JsonDateOnly::read(&mut cursor)?;
+        let time = if cursor.at_end() {
+            JsonTime::MIDNIGHT
+        } else if cursor.eat_any(b"Tt ").is_some() {
+            JsonTime::read(&mut cursor)?
+        } else {
+            return Err(DateError::TrailingCharacters(cursor.pos));
+        };
+        cursor.finish()?;
+        Self::from_parts(date, time).validated()
+    }
+
+    ///Parses an RFC 2822 date and time (e.g. Wed, 01 May 2024 13:45:00 +0200). The day of the
+    ///week is optional but, if present, must be correct; the seconds are optional; two and
+    ///three digit years are read as RFC 2822 says (49 is 2049, 50 is 1950 and 103 is 2003);
+    ///and the zone may be an offset or one of UT, GMT, Z and the US zone names (EST, PDT etc.)
+    pub fn from_rfc2822(input: &str) -> Result<JsonDate, DateError> {
+        let mut cursor = DateCursor {
+            input: input.trim().as_bytes(),
+            pos: 0,
+        };
+        let weekday = if cursor.peek().is_some_and(|b| b.is_ascii_alphabetic()) {
+            let name = cursor.word();
+            let weekday = WEEKDAY_NAMES
+                .iter()
+                .position(|x| x.eq_ignore_ascii_case(name))
+                .ok_or(DateError::Malformed(DateComponent::Weekday))?;
+            cursor.skip_whitespace();
+            cursor.expect(b',', DateComponent::Date)?;
+            cursor.skip_whitespace();
+            Some(weekday)
+        } else {
+            None
+        };
+        let date = cursor.digits(1, 2, DateComponent::Date)?;
+        cursor.skip_whitespace();
+        let name = cursor.word();
+        let month = MONTH_NAMES
+            .iter()
+            .position(|x| x.eq_ignore_ascii_case(name))
+            .ok_or(DateError::Malformed(DateComponent::Month))?
+            + 1;
+        cursor.skip_whitespace();
+        let year = match cursor.count_digits() {
+            2 => match cursor.digits(2, 2, DateComponent::Year)? {
+                year if year < 50 => year + 2000,
+                year => year + 1900,
+            },
+            3 => cursor.digits(3, 3, DateComponent::Year)? + 1900,
+            _ => cursor.digits(4, 4, DateComponent::Year)?,
+        };
+        cursor.skip_whitespace();
+        let hour = cursor.digits(2, 2, DateComponent::Hour)?;
+        cursor.expect(b':', DateComponent::Minute)?;
+        let minute = cursor.digits(2, 2, DateComponent::Minute)?;
+        let second = if cursor.eat(b':') {
+            cursor.digits(2, 2, DateComponent::Second)?
+        } else {
+            0
+        };
+        cursor.skip_whitespace();
+        let offset = match cursor.numeric_offset()? {
+            Some(offset) => offset,
+            None => match cursor.word().to_ascii_uppercase().as_str() {
+                "UT" | "GMT" | "Z" => 0,
+                "EDT" => -4 * 60,
+                "EST" | "CDT" => -5 * 60,
+                "CST" | "MDT" => -6 * 60,
+                "MST" | "PDT" => -7 * 60,
+                "PST" => -8 * 60,
+                //RFC 2822 says the other military zones should be treated as UT, since their
+                //signs were commonly reversed
+                zone if zone.len() == 1 && zone != "J" => 0,
+                _ => return Err(DateError::Malformed(DateComponent::Offset)),
+            },
+        };
+        cursor.skip_whitespace();
+        cursor.finish()?;
+        let output = Self {
+            year: year as u16,
+            month: month as u8,
+            date: date as u8,
+            hour: hour as u8,
+            minute: minute as u8,
+            second: second as u8,
+            nanosecond: 0,
+            offset: Some(offset),
+        }
+        .validated()?;
+        if weekday.is_some_and(|weekday| weekday != output.weekday() as usize) {
+            return Err(DateError::OutOfRange(DateComponent::Weekday));
+        }
+        Ok(output)
+    }
+
+    ///Parses an ASP.NET date string such as /Date(1714563900000+0200)/, which holds a number of
+    ///milliseconds since 1970-01-01T00:00:00Z and, optionally, the UTC offset to express that
+    ///instant in
+    pub fn from_asp_net(input: &str) -> Result<JsonDate, DateError> {
+        let mut cursor = DateCursor {
+            input: input.trim().as_bytes(),
+            pos: 0,
+        };
+        for b in b"/Date(" {
+            cursor.expect(*b, DateComponent::Year)?;
+        }
+        let start = cursor.pos;
+        cursor.eat(b'-');
+        if cursor.count_digits() == 0 {
+            return Err(DateError::Malformed(DateComponent::Year));
+        }
+        cursor.pos += cursor.count_digits();
+        let millis = std::str::from_utf8(&cursor.input[start..cursor.pos])
+            .ok()
+            .and_then(|millis| millis.parse::<i64>().ok())
+            .ok_or(DateError::OutOfRange(DateComponent::Year))?;
+        let offset = cursor.numeric_offset()?;
+        cursor.expect(b')', DateComponent::Offset)?;
+        cursor.expect(b'/', DateComponent::Offset)?;
+        cursor.finish()?;
+        let output = Self::from_unix_timestamp_millis(millis)?;
+        match offset {
+            Some(offset) => output.to_offset(offset),
+            None => Ok(output),
+        }
+    }
+
+    ///Decodes a date in the given format (see `JsonValue::get_json_date_with`)
+    pub(crate) fn from_json_with(input: &JsonValue, format: DateFormat) -> Option<JsonDate> {
+        match (format, input) {
+            (DateFormat::UnixSeconds, JsonValue::Number(_) | JsonValue::String(_)) => {
+                Self::from_unix_units(input.get_number()?, Self::NANOS_PER_SECOND)
+            }
+            (DateFormat::UnixMillis, JsonValue::Number(_) | JsonValue::String(_)) => {
+                Self::from_unix_units(input.get_number()?, 1_000_000)
+            }
+            (DateFormat::Iso8601, JsonValue::String(str)) => Self::from_iso8601(str).ok(),
+            (DateFormat::Rfc3339, JsonValue::String(str)) => Self::from_rfc3339(str).ok(),
+            (DateFormat::Rfc2822, JsonValue::String(str)) => Self::from_rfc2822(str).ok(),
+            (DateFormat::AspNet, JsonValue::String(str)) => Self::from_asp_net(str).ok(),
+            _ => None,
+        }
+    }
+
+    ///Converts a (possibly fractional) Unix timestamp, scaling the whole and fractional parts
+    ///separately so that the whole part is not rounded. NaN and the infinities are not instants
+    fn from_unix_units(value: f64, nanos_per_unit: i128) -> Option<JsonDate> {
+        if !value.is_finite() {
+            return None;
+        }
+        let whole = value.floor();
+        let fraction = ((value - whole) * nanos_per_unit as f64).round() as i128;
+        Self::from_unix_timestamp_nanos((whole as i128).checked_mul(nanos_per_unit)? + fraction)
+            .ok()
+    }
+
+    ///The inverse of `from_unix_units`
+    fn unix_units(&self, nanos_per_unit: i128) -> f64 {
+        let nanos = self.unix_timestamp_nanos();
+        nanos.div_euclid(nanos_per_unit) as f64
+            + nanos.rem_euclid(nanos_per_unit) as f64 / nanos_per_unit as f64
+    }
+
+    ///Encodes this date in the given format. Formats which need a UTC offset (RFC 3339,
+    ///RFC 2822 and the Unix timestamps) take a date without one to be in UTC; the Unix
+    ///timestamps and ASP.NET strings describe only the instant, so a leap second becomes the
+    ///first second of the next minute; and RFC 2822 strings, Unix milliseconds and ASP.NET
+    ///strings drop any fraction of a second below their precision
+    pub fn to_json_with(&self, format: DateFormat) -> JsonValue {
+        match format {
+            DateFormat::Iso8601 => JsonValue::String(self.to_string()),
+            DateFormat::Rfc3339 => JsonValue::String(
+                Self {
+                    offset: Some(self.offset.unwrap_or(0)),
+                    ..*self
+                }
+                .to_string(),
+            ),
+            DateFormat::UnixSeconds => JsonValue::Number(self.unix_units(Self::NANOS_PER_SECOND)),
+            DateFormat::UnixMillis => JsonValue::Number(self.unix_timestamp_millis() as f64),
+            DateFormat::Rfc2822 => {
+                let offset = self.offset.unwrap_or(0);
+                JsonValue::String(format!(
+                    "{}, {} {} {} {}:{}:{} {}{}{}",
+                    WEEKDAY_NAMES[self.weekday() as usize],
+                    Self::two_digits(self.date),
+                    MONTH_NAMES[self.month as usize - 1],
+                    Self::four_digits(self.year),
+                    Self::two_digits(self.hour),
+                    Self::two_digits(self.minute),
+                    Self::two_digits(self.second),
+                    if offset < 0 { "-" } else { "+" },
+                    Self::two_digits((offset.unsigned_abs() / 60) as u8),
+                    Self::two_digits((offset.unsigned_abs() % 60) as u8)
+                ))
+            }
+            DateFormat::AspNet => JsonValue::String(match self.offset {
+                Some(offset) if offset != 0 => format!(
+                    "/Date({}{}{}{})/",
+                    self.unix_timestamp_millis(),
+                    if offset < 0 { "-" } else { "+" },
+                    Self::two_digits((offset.unsigned_abs() / 60) as u8),
+                    Self::two_digits((offset.unsigned_abs() % 60) as u8)
+                ),
+                _ => format!("/Date({})/", self.unix_timestamp_millis()),
+            }),
+        }
+    }
+
+    ///Combines a date and a time of day (which are not checked, see `JsonDateOnly::and_time`)
+    fn from_parts(date: JsonDateOnly, time: JsonTime) -> JsonDate {
+        Self {
+            year: date.year,
+            month: date.month,
+            date: date.date,
+            hour: time.hour,
+            minute: time.minute,
+            second: time.second,
+            nanosecond: time.nanosecond,
+            offset: time.offset,
+        }
+    }
+
+    ///The date part of this date and time
+    pub fn to_date_only(&self) -> JsonDateOnly {
+        JsonDateOnly {
+            year: self.year,
+            month: self.month,
+            date: self.date,
+        }
+    }
+
+    ///The time of day part (including the UTC offset) of this date and time
+    pub fn to_time(&self) -> JsonTime {
+        JsonTime {
+            hour: self.hour,
+            minute: self.minute,
+            second: self.second,
+            nanosecond: self.nanosecond,
+            offset: self.offset,
+        }
+    }
+
+    ///Checks that this is a real calendar date and time of day
+    fn validated(self) -> Result<Self, DateError> {
+        self.to_date_only().validated()?;
+        self.to_time().validated()?;
+        Ok(self)
+    }
+
+    fn days_in_month(year: u32, month: u32) -> u8 {
+        match month {
+            2 if Self::is_leap_year(year) => 29,
+            2 => 28,
+            4 | 6 | 9 | 11 => 30,
+            _ => 31,
+        }
+    }
+
+    fn is_leap_year(year: u32) -> bool {
+        (year.is_multiple_of(4) && !year.is_multiple_of(100)) || year.is_multiple_of(400)
+    }
+
+    ///The number of days from 1970-01-01 to the given date in the proleptic Gregorian calendar
+    fn days_from_civil(year: i64, month: u32, date: u32) -> i64 {
+        let year = if month <= 2 { year - 1 } else { year };
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let month = month as i64;
+        let day_of_year =
+            (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + date as i64 - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        era * 146097 + day_of_era - 719468
+    }
+
+    ///The inverse of `days_from_civil`
+    fn civil_from_days(days: i64) -> (i64, u32, u32) {
+        let days = days + 719468;
+        let era = days.div_euclid(146097);
+        let day_of_era = days - era * 146097;
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let date = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+        let month = if shifted_month < 10 {
+            shifted_month + 3
+        } else {
+            shifted_month - 9
+        } as u32;
+        let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+        (year, month, date)
+    }
+
+    fn ordinal_date(year: u32, day: u32) -> Result<(u32, u32, u32), DateError> {
+        let days_in_year = if Self::is_leap_year(year) { 366 } else { 365 };
+        if day == 0 || day > days_in_year {
+            return Err(DateError::OutOfRange(DateComponent::DayOfYear));
+        }
+        let (_, month, date) =
+            Self::civil_from_days(Self::days_from_civil(year as i64, 1, 1) + day as i64 - 1);
+        Ok((year, month, date))
+    }
+
+    ///Converts an ISO 8601 week date, where week 1 is the week (starting on Monday) that holds
+    ///January 4th, and day 1 is Monday
+    fn week_date(year: u32, week: u32, day: u32) -> Result<(u32, u32, u32), DateError> {
+        if !(1..=53).contains(&week) {
+            return Err(DateError::OutOfRange(DateComponent::Week));
+        }
+        if !(1..=7).contains(&day) {
+            return Err(DateError::OutOfRange(DateComponent::Weekday));
+        }
+        let january_fourth = Self::days_from_civil(year as i64, 1, 4);
+        //1970-01-01 was a Thursday, so this is 0 for Monday through 6 for Sunday
+        let weekday = (january_fourth + 3).rem_euclid(7);
+        let monday = january_fourth - weekday + (week as i64 - 1) * 7;
+        //A week belongs to the year its Thursday falls in, so only some years have a week 53
+        if Self::civil_from_days(monday + 3).0 != year as i64 {
+            return Err(DateError::OutOfRange(DateComponent::Week));
+        }
+        let (year, month, date) = Self::civil_from_days(monday + day as i64 - 1);
+        if !(0..=9999).contains(&year) {
+            return Err(DateError::OutOfRange(DateComponent::Year));
+        }
+        Ok((year as u32, month, date))
+    }
+
+    fn four_digits(input: u16) -> String {
+        let input = input % 10000;
+        if input > 999 {
+            format!("{}", input)
+        } else if input > 99 {
+            format!("0{}", input)
+        } else if input > 9 {
+            format!("00{}", input)
+        } else {
+            format!("000{}", input)
+        }
+    }
+
+    fn two_digits(input: u8) -> String {
+        let input = input % 100;
+        if input > 9 {
+            format!("{}", input)
+        } else {
+            format!("0{}", input)
+        }
+    }
+}
+
+impl std::fmt::Display for JsonDate {
+    ///Formats this date as a typical JavaScript DateTime string, followed by the fractional
+    ///seconds (if any, without trailing zeros) and the UTC offset (if known, with "Z" for UTC)
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}T{}", self.to_date_only(), self.to_time())
+    }
+}
+
+impl PartialOrd for JsonDate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for JsonDate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.unix_timestamp_nanos()
+            .cmp(&other.unix_timestamp_nanos())
+            .then_with(|| {
+                (
+                    self.year,
+                    self.month,
+                    self.date,
+                    self.hour,
+                    self.minute,
+                    self.second,
+                    self.offset,
+                )
+                    .cmp(&(
+                        other.year,
+                        other.month,
+                        other.date,
+                        other.hour,
+                        other.minute,
+                        other.second,
+                        other.offset,
+                    ))
+            })
+    }
+}
+
+impl Add<Duration> for JsonDate {
+    type Output = JsonDate;
+
+    ///Adds `duration`, panicking if the result is after the year 9999. See
+    ///`JsonDate::checked_add` for a version which does not panic
+    fn add(self, duration: Duration) -> JsonDate {
+        self.checked_add(duration)
+            .expect("overflow when adding duration to date")
+    }
+}
+
+impl Sub<Duration> for JsonDate {
+    type Output = JsonDate;
+
+    ///Subtracts `duration`, panicking if the result is before the year 0. See
+    ///`JsonDate::checked_sub` for a version which does not panic
+    fn sub(self, duration: Duration) -> JsonDate {
+        self.checked_sub(duration)
+            .expect("overflow when subtracting duration from date")
+    }
+}
+
+impl From<JsonDate> for SystemTime {
+    ///Converts to the instant the date describes, taking a date without an offset to be in UTC
+    fn from(date: JsonDate) -> SystemTime {
+        let nanos = date.unix_timestamp_nanos();
+        let duration = Duration::new(
+            (nanos.unsigned_abs() / JsonDate::NANOS_PER_SECOND as u128) as u64,
+            (nanos.unsigned_abs() % JsonDate::NANOS_PER_SECOND as u128) as u32,
+        );
+        if nanos < 0 {
+            UNIX_EPOCH - duration
+        } else {
+            UNIX_EPOCH + duration
+        }
+    }
+}
+
+impl TryFrom<SystemTime> for JsonDate {
+    type Error = DateError;
+
+    ///Converts to a UTC date, failing if it falls outside of the years 0 to 9999
+    fn try_from(time: SystemTime) -> Result<JsonDate, DateError> {
+        let nanos = match time.duration_since(UNIX_EPOCH) {
+            Ok(duration) => duration.as_nanos() as i128,
+            Err(e) => -(e.duration().as_nanos() as i128),
+        };
+        JsonDate::from_unix_timestamp_nanos(nanos)
+    }
+}
+
+///A calendar date without a time of day, e.g. 2024-05-01
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct JsonDateOnly {
+    year: u16,
+    month: u8,
+    date: u8,
+}
+
+impl JsonDateOnly {
+    ///Creates a date, failing if it is not a real calendar date (e.g. February 30th)
+    pub fn new(year: u16, month: u8, date: u8) -> Result<JsonDateOnly, DateError> {
+        Self { year, month, date }.validated()
+    }
+
+    ///Parses an ISO 8601 date, returning None if it is invalid. See
+    ///`JsonDateOnly::from_iso8601`
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(input: &str) -> Option<JsonDateOnly> {
+        Self::from_iso8601(input).ok()
+    }
+
+    ///Parses an ISO 8601 date with no time of day: a calendar date (2024-05-01 or 20240501,
+    ///where the month and day may also be omitted), a week date (2024-W18-3 or 2024W183) or an
+    ///ordinal date (2024-122 or 2024122)
+    pub fn from_iso8601(input: &str) -> Result<JsonDateOnly, DateError> {
+        let mut cursor = DateCursor {
+            input: input.trim().as_bytes(),
+            pos: 0,
+        };
+        let output = Self::read(&mut cursor)?;
+        cursor.finish()?;
+        output.validated()
+    }
+
+    ///Reads the date at the start of an ISO 8601 string. Calendar dates are not checked
+    fn read(cursor: &mut DateCursor) -> Result<JsonDateOnly, DateError> {
+        let year = cursor.digits(4, 4, DateComponent::Year)?;
+        let (year, month, date) = if cursor.eat(b'-') {
+            if cursor.eat(b'W') {
+                let week = cursor.digits(2, 2, DateComponent::Week)?;
+                let day = if cursor.eat(b'-') {
+                    cursor.digits(1, 1, DateComponent::Weekday)?
+                } else {
+                    1
+                };
+                JsonDate::week_date(year, week, day)?
+            } else if cursor.count_digits() == 3 {
+                let day = cursor.digits(3, 3, DateComponent::DayOfYear)?;
+                JsonDate::ordinal_date(year, day)?
+            } else {
+                let month = cursor.digits(1, 2, DateComponent::Month)?;
+                let date = if cursor.eat(b'-') {
+                    cursor.digits(1, 2, DateComponent::Date)?
+                } else {
+                    1
+                };
+                (year, month, date)
+            }
+        } else if cursor.eat(b'W') {
+            let week = cursor.digits(2, 2, DateComponent::Week)?;
+            let day = if cursor.count_digits() > 0 {
+                cursor.digits(1, 1, DateComponent::Weekday)?
+            } else {
+                1
+            };
+            JsonDate::week_date(year, week, day)?
+        } else {
+            match cursor.count_digits() {
+                0 => (year, 1, 1),
+                3 => {
+                    let day = cursor.digits(3, 3, DateComponent::DayOfYear)?;
+                    JsonDate::ordinal_date(year, day)?
+                }
+                4 => (
+                    year,
+                    cursor.digits(2, 2, DateComponent::Month)?,
+                    cursor.digits(2, 2, DateComponent::Date)?,
+                ),
+                _ => return Err(DateError::Malformed(DateComponent::Month)),
+            }
+        };
+        Ok(Self {
+            year: year as u16,
+            month: month as u8,
+            date: date as u8,
+        })
+    }
+
+    fn validated(self) -> Result<Self, DateError> {
+        if self.year > 9999 {
+            return Err(DateError::OutOfRange(DateComponent::Year));
+        }
+        if !(1..=12).contains(&self.month) {
+            return Err(DateError::OutOfRange(DateComponent::Month));
+        }
+        if self.date == 0
+            || self.date > JsonDate::days_in_month(self.year as u32, self.month as u32)
+        {
+            return Err(DateError::OutOfRange(DateComponent::Date));
+        }
+        Ok(self)
+    }
+
+    pub fn year(&self) -> u16 {
+        self.year
+    }
+
+    ///The month, from 1 for January to 12 for December
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+
+    ///The day of the month, starting from 1
+    pub fn date(&self) -> u8 {
+        self.date
+    }
+
+    pub fn weekday(&self) -> Weekday {
+        JsonDate::from_parts(*self, JsonTime::MIDNIGHT).weekday()
+    }
+
+    ///Combines this date with a time of day
+    pub fn and_time(&self, time: JsonTime) -> JsonDate {
+        JsonDate::from_parts(*self, time)
+    }
+}
+
+impl std::fmt::Display for JsonDateOnly {
+    ///Formats this date as YYYY-MM-DD
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}-{}-{}",
+            JsonDate::four_digits(self.year),
+            JsonDate::two_digits(self.month),
+            JsonDate::two_digits(self.date)
+        )
+    }
+}
+
+///A time of day without a date, e.g. 13:45:00, optionally with fractional seconds and a UTC
+///offset. Times are ordered by their fields, so times with different offsets are not compared
+///by the instant they describe
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct JsonTime {
+    hour: u8,
+    minute: u8,
+    second: u8,
+    nanosecond: u32,
+    offset: Option<i16>,
+}
+
+impl JsonTime {
+    const MIDNIGHT: JsonTime = JsonTime {
+        hour: 0,
+        minute: 0,
+        second: 0,
+        nanosecond: 0,
+        offset: None,
+    };
+
+    ///Creates a time of day with no fractional seconds or UTC offset, failing if it is out of
+    ///range. Second 60 is allowed as a leap second at the end of minute 59
+    pub fn new(hour: u8, minute: u8, second: u8) -> Result<JsonTime, DateError> {
+        Self {
+            hour,
+            minute,
+            second,
+            ..Self::MIDNIGHT
+        }
+        .validated()
+    }
+
+    ///Returns this time with its fractional seconds set to `nanosecond`, which must be less
+    ///than one second
+    pub fn with_nanosecond(self, nanosecond: u32) -> Result<JsonTime, DateError> {
+        Self { nanosecond, ..self }.validated()
+    }
+
+    ///Returns this time with its UTC offset (in minutes, from -23:59 to +23:59) set to
+    ///`offset`
+    pub fn with_offset(self, offset: Option<i16>) -> Result<JsonTime, DateError> {
+        Self { offset, ..self }.validated()
+    }
+
+    ///Parses an ISO 8601 time of day, returning None if it is invalid. See
+    ///`JsonTime::from_iso8601`
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(input: &str) -> Option<JsonTime> {
+        Self::from_iso8601(input).ok()
+    }
+
+    ///Parses an ISO 8601 time of day (e.g. 13:45:00, 13:45, T134500 or 13:45:00.5+02:00),
+    ///which may omit its seconds or minutes, may be in basic format, and may have fractional
+    ///seconds (after "." or ",") and a UTC offset ("Z", +05:30, +0530 or +05)
+    pub fn from_iso8601(input: &str) -> Result<JsonTime, DateError> {
+        let mut cursor = DateCursor {
+            input: input.trim().as_bytes(),
+            pos: 0,
+        };
+        cursor.eat_any(b"Tt");
+        let output = Self::read(&mut cursor)?;
+        cursor.finish()?;
+        output.validated()
+    }
+
+    ///Reads the time (and offset) of an ISO 8601 string. The time is not checked
+    fn read(cursor: &mut DateCursor) -> Result<JsonTime, DateError> {
+        let mut output = Self::MIDNIGHT;
+        output.hour = cursor.digits(1, 2, DateComponent::Hour)? as u8;
+        if cursor.eat(b':') {
+            output.minute = cursor.digits(1, 2, DateComponent::Minute)? as u8;
+            if cursor.eat(b':') {
+                output.second = cursor.digits(1, 2, DateComponent::Second)? as u8;
+            }
+        } else if cursor.count_digits() > 0 {
+            output.minute = cursor.digits(2, 2, DateComponent::Minute)? as u8;
+            if cursor.count_digits() > 0 {
+                output.second = cursor.digits(2, 2, DateComponent::Second)? as u8;
+            }
+        }
+        if cursor.eat_any(b".,").is_some() {
+            output.nanosecond = cursor.nanoseconds()?;
+        }
+        if !cursor.at_end() {
+            output.offset = Some(cursor.offset(false)?);
+        }
+        Ok(output)
+    }
+
+    ///Checks that this is a real time of day. Second 60 is allowed as a leap second, which is
+    ///only ever inserted as the last second of a minute 59
+    fn validated(self) -> Result<Self, DateError> {
+        if self.hour > 23 {
+            return Err(DateError::OutOfRange(DateComponent::Hour));
+        }
+        if self.minute > 59 {
+            return Err(DateError::OutOfRange(DateComponent::Minute));
+        }
+        if self.second > 60 || (self.second == 60 && self.minute != 59) {
+            return Err(DateError::OutOfRange(DateComponent::Second));
+        }
+        if self.nanosecond > 999_999_999 {
+            return Err(DateError::OutOfRange(DateComponent::Fraction));
+        }
+        if self
+            .offset
+            .is_some_and(|offset| offset.abs() > 23 * 60 + 59)
+        {
+            return Err(DateError::OutOfRange(DateComponent::Offset));
+        }
+        Ok(self)
+    }
+
+    pub fn hour(&self) -> u8 {
+        self.hour
+    }
+
+    pub fn minute(&self) -> u8 {
+        self.minute
+    }
+
+    ///The second, which is 60 during a leap second
+    pub fn second(&self) -> u8 {
+        self.second
+    }
+
+    ///The fraction of the second, in nanoseconds
+    pub fn nanosecond(&self) -> u32 {
+        self.nanosecond
+    }
+
+    ///The offset from UTC in minutes (e.g. 330 for +05:30), or None for a local time whose
+    ///offset is unknown
+    pub fn offset(&self) -> Option<i16> {
+        self.offset
+    }
+}
+
+impl std::fmt::Display for JsonTime {
+    ///Formats this time as HH:MM:SS, followed by the fractional seconds (if any, without
+    ///trailing zeros) and the UTC offset (if known, with "Z" for UTC)
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}",
+            JsonDate::two_digits(self.hour),
+            JsonDate::two_digits(self.minute),
+            JsonDate::two_digits(self.second)
+        )?;
+        if self.nanosecond > 0 {
+            let fraction = format!("{:09}", self.nanosecond);
+            write!(f, ".{}", fraction.trim_end_matches('0'))?;
+        }
+        match self.offset {
+            None => Ok(()),
+            Some(0) => write!(f, "Z"),
+            Some(offset) => write!(
+                f,
+                "{}{}:{}",
+                if offset < 0 { "-" } else { "+" },
+                JsonDate::two_digits((offset.unsigned_abs() / 60) as u8),
+                JsonDate::two_digits((offset.unsigned_abs() % 60) as u8)
+            ),
+        }
+    }
+}
+
+///An ISO 8601 duration, e.g. P3DT4H (three days and four hours). Each component is kept as it
+///was written, since years, months and days vary in length, so P1D and PT24H are not equal
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct JsonDuration {
+    years: u32,
+    months: u32,
+    weeks: u32,
+    days: u32,
+    hours: u32,
+    minutes: u32,
+    seconds: u32,
+    nanosecond: u32,
+}
+
+impl JsonDuration {
+    ///Creates a duration from its components (see `JsonDuration::from_std` for a duration of
+    ///an exact length)
+    pub fn new(years: u32, months: u32, days: u32, hours: u32, minutes: u32, seconds: u32) -> Self {
+        Self {
+            years,
+            months,
+            days,
+            hours,
+            minutes,
+            seconds,
+            ..Self::default()
+        }
+    }
+
+    ///Parses an ISO 8601 duration, returning None if it is invalid. See
+    ///`JsonDuration::from_iso8601`
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(input: &str) -> Option<JsonDuration> {
+        Self::from_iso8601(input).ok()
+    }
+
+    ///Parses an ISO 8601 duration of the form PnYnMnWnDTnHnMnS (e.g. P1Y2M, P2W, PT30M or
+    ///PT1.5S), where any of the components may be omitted but at least one must be present.
+    ///Only the seconds may have a fraction (after "." or ",")
+    pub fn from_iso8601(input: &str) -> Result<JsonDuration, DateError> {
+        let mut cursor = DateCursor {
+            input: input.trim().as_bytes(),
+            pos: 0,
+        };
+        cursor.expect(b'P', DateComponent::Duration)?;
+        let mut output = Self::default();
+        let mut designators: &[u8] = b"YMWD";
+        let mut time = false;
+        let mut empty = true;
+        while !cursor.at_end() {
+            if !time && cursor.eat(b'T') {
+                //A "T" must be followed by at least one component of the time
+                if cursor.at_end() {
+                    return Err(DateError::Malformed(DateComponent::Duration));
+                }
+                designators = b"HMS";
+                time = true;
+                continue;
+            }
+            //Components are at most 9 digits long, so they always fit in a u32
+            if cursor.count_digits() > 9 {
+                return Err(DateError::OutOfRange(DateComponent::Duration));
+            }
+            let value = cursor.digits(1, 9, DateComponent::Duration)?;
+            let fraction = cursor.eat_any(b".,").is_some();
+            let nanosecond = if fraction { cursor.nanoseconds()? } else { 0 };
+            let position = cursor
+                .peek()
+                .and_then(|b| designators.iter().position(|d| *d == b))
+                .ok_or(DateError::Malformed(DateComponent::Duration))?;
+            let designator = designators[position];
+            if fraction && designator != b'S' {
+                return Err(DateError::Malformed(DateComponent::Duration));
+            }
+            cursor.pos += 1;
+            match (time, designator) {
+                (false, b'Y') => output.years = value,
+                (false, b'M') => output.months = value,
+                (false, b'W') => output.weeks = value,
+                (false, _) => output.days = value,
+                (true, b'H') => output.hours = value,
+                (true, b'M') => output.minutes = value,
+                (true, _) => {
+                    output.seconds = value;
+                    output.nanosecond = nanosecond;
+                }
+            }
+            designators = &designators[position + 1..];
+            empty = false;
+        }
+        if empty {
+            return Err(DateError::Malformed(DateComponent::Duration));
+        }
+        Ok(output)
+    }
+
+    pub fn years(&self) -> u32 {
+        self.years
+    }
+
+    pub fn months(&self) -> u32 {
+        self.months
+    }
+
+    pub fn weeks(&self) -> u32 {
+        self.weeks
+    }
+
+    pub fn days(&self) -> u32 {
+        self.days
+    }
+
+    pub fn hours(&self) -> u32 {
+        self.hours
+    }
+
+    pub fn minutes(&self) -> u32 {
+        self.minutes
+    }
+
+    ///The whole seconds
+    pub fn seconds(&self) -> u32 {
+        self.seconds
+    }
+
+    ///The fraction of the seconds, in nanoseconds
+    pub fn nanosecond(&self) -> u32 {
+        self.nanosecond
+    }
+
+    ///Converts to an exact length of time, taking a week to be 7 days and a day to be 24
+    ///hours. Returns None if the duration has years or months, whose length varies
+    pub fn to_std(&self) -> Option<Duration> {
+        if self.years > 0 || self.months > 0 {
+            return None;
+        }
+        let seconds = ((self.weeks as u64 * 7 + self.days as u64) * 24 + self.hours as u64) * 3600
+            + self.minutes as u64 * 60
+            + self.seconds as u64;
+        Some(Duration::new(seconds, self.nanosecond))
+    }
+
+    ///Converts an exact length of time into days, hours, minutes and seconds. Returns None if
+    ///it is too long to fit (more than about eleven million years)
+    pub fn from_std(duration: Duration) -> Option<JsonDuration> {
+        let seconds = duration.as_secs();
+        Some(Self {
+            days: (seconds / 86_400).try_into().ok()?,
+            hours: (seconds / 3600 % 24) as u32,
+            minutes: (seconds / 60 % 60) as u32,
+            seconds: (seconds % 60) as u32,
+            nanosecond: duration.subsec_nanos(),
+            ..Self::default()
+        })
+    }
+}
+
+impl std::fmt::Display for JsonDuration {
+    ///Formats this duration as ISO 8601, omitting zero components (a zero duration is PT0S)
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "P")?;
+        for (value, designator) in [
+            (self.years, "Y"),
+            (self.months, "M"),
+            (self.weeks, "W"),
+            (self.days, "D"),
+        ] {
+            if value > 0 {
+                write!(f, "{}{}", value, designator)?;
+            }
+        }
+        let seconds = self.seconds > 0 || self.nanosecond > 0;
+        if self.hours > 0 || self.minutes > 0 || seconds {
+            write!(f, "T")?;
+            if self.hours > 0 {
+                write!(f, "{}H", self.hours)?;
+            }
+            if self.minutes > 0 {
+                write!(f, "{}M", self.minutes)?;
+            }
+        } else if *self == Self::default() {
+            return write!(f, "T0S");
+        }
+        if seconds {
+            write!(f, "{}", self.seconds)?;
+            if self.nanosecond > 0 {
+                let fraction = format!("{:09}", self.nanosecond);
+                write!(f, ".{}", fraction.trim_end_matches('0'))?;
+            }
+            write!(f, "S")?;
+        }
+        Ok(())
+    }
+}
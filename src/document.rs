@@ -0,0 +1,593 @@
+use crate::{is_identifier, is_line_separator, JsonValue, ParseError, ParseOptions};
+use std::collections::HashSet;
+use std::ops::Range;
+
+///An error returned when editing a `JsonDocument`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DocumentError {
+    ///The edit would leave the document malformed, e.g. a NaN written into strict JSON
+    Syntax(ParseError),
+
+    ///The JSON Pointer does not lead to a place the edit can be made
+    Pointer {
+        ///The JSON Pointer (RFC 6901) that was given
+        pointer: String,
+
+        ///What was wrong with it
+        message: String,
+    },
+}
+
+impl std::fmt::Display for DocumentError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DocumentError::Syntax(e) => write!(f, "{}", e),
+            DocumentError::Pointer { pointer, message } => {
+                let pointer = if pointer.is_empty() { "/" } else { pointer };
+                write!(f, "{}: {}", pointer, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for DocumentError {}
+
+impl From<ParseError> for DocumentError {
+    fn from(e: ParseError) -> Self {
+        DocumentError::Syntax(e)
+    }
+}
+
+///A value within the text of a document, located by its byte range
+#[derive(Clone, Debug)]
+struct Node {
+    span: Range<usize>,
+    kind: NodeKind,
+}
+
+#[derive(Clone, Debug)]
+enum NodeKind {
+    Scalar,
+    Array(Vec<Entry>),
+    Object(Vec<Entry>),
+}
+
+///An element of an array or a member of an object, in the order it appears in the text
+#[derive(Clone, Debug)]
+struct Entry {
+    ///The decoded key of an object member; None for an array element
+    key: Option<String>,
+
+    ///Where the entry starts: its key for an object member, else its value
+    start: usize,
+
+    value: Node,
+
+    ///The offset of the comma after the entry, if any
+    comma: Option<usize>,
+}
+
+///A JSON (or JSONC, or JSON5) text which can be edited in place. Only the values an edit
+///touches are rewritten; whitespace, comments and key order elsewhere are kept byte-for-byte,
+///which makes it suitable for updating hand-edited config files.
+///
+///Values are found by JSON Pointer (RFC 6901), e.g. /servers/0/port. New values are written
+///compactly, as `JsonValue::stringify` writes them, with new entries laid out like the last
+///existing entry of their array or object
+#[derive(Clone, Debug)]
+pub struct JsonDocument {
+    text: String,
+    options: ParseOptions,
+    root: Node,
+}
+
+impl JsonDocument {
+    ///Parses a document in the dialect the options select
+    pub fn parse(text: &str, options: &ParseOptions) -> Result<JsonDocument, ParseError> {
+        if !options.allows_size(text.len()) {
+            return Err(ParseError {
+                offset: 0,
+                message: "input is too large".to_owned(),
+            });
+        }
+        let mut scanner = Scanner {
+            text: text.as_bytes(),
+            pos: 0,
+            options,
+            depth: 0,
+        };
+        scanner.trivia()?;
+        let root = scanner.value()?;
+        scanner.trivia()?;
+        if scanner.pos < text.len() {
+            return Err(scanner.error("unexpected characters after the value"));
+        }
+        Ok(JsonDocument {
+            text: text.to_owned(),
+            options: options.clone(),
+            root,
+        })
+    }
+
+    ///The text of the document, including any edits
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    pub fn into_string(self) -> String {
+        self.text
+    }
+
+    ///The whole document as a JsonValue
+    pub fn value(&self) -> JsonValue {
+        self.value_of(&self.root)
+    }
+
+    ///The value at the JSON Pointer, if there is one
+    pub fn get(&self, pointer: &str) -> Option<JsonValue> {
+        self.find(pointer).ok().map(|node| self.value_of(node))
+    }
+
+    ///The keys of the object at the JSON Pointer in the order they are written, which a
+    ///JsonValue does not keep
+    pub fn keys(&self, pointer: &str) -> Option<Vec<String>> {
+        match &self.find(pointer).ok()?.kind {
+            NodeKind::Object(entries) => {
+                Some(entries.iter().filter_map(|e| e.key.clone()).collect())
+            }
+            _ => None,
+        }
+    }
+
+    ///Sets the value at the JSON Pointer, replacing the value already there or else adding a
+    ///member to an object. A value is appended to an array with the index "-" (or the array's
+    ///length), as in RFC 6902
+    pub fn set(&mut self, pointer: &str, value: &JsonValue) -> Result<(), DocumentError> {
+        let text = value.stringify();
+        let (parent, token) = match pointer.rfind('/') {
+            Some(i) => (&pointer[..i], unescape(&pointer[i + 1..])),
+            None => {
+                let span = self.find(pointer)?.span.clone();
+                return self.splice(vec![(span, text)]);
+            }
+        };
+        let node = self.find_parent(pointer, parent)?;
+        let entries = match &node.kind {
+            NodeKind::Object(entries) => {
+                if let Some(entry) = entries.iter().find(|e| e.key.as_ref() == Some(&token)) {
+                    let span = entry.value.span.clone();
+                    return self.splice(vec![(span, text)]);
+                }
+                entries
+            }
+            NodeKind::Array(entries) => {
+                let index = if token == "-" {
+                    entries.len()
+                } else {
+                    array_index(&token)
+                        .filter(|i| *i <= entries.len())
+                        .ok_or_else(|| missing(pointer, "no such array index"))?
+                };
+                if let Some(entry) = entries.get(index) {
+                    let span = entry.value.span.clone();
+                    return self.splice(vec![(span, text)]);
+                }
+                entries
+            }
+            NodeKind::Scalar => return Err(missing(pointer, "parent is not an object or array")),
+        };
+        let entry = match &node.kind {
+            NodeKind::Object(_) => format!("{}: {}", JsonValue::String(token).stringify(), text),
+            _ => text,
+        };
+        let edits = self.insertion(node, entries, entry);
+        self.splice(edits)
+    }
+
+    ///Removes the value at the JSON Pointer from its array or object, along with its key and
+    ///its comma, returning the value removed
+    pub fn remove(&mut self, pointer: &str) -> Result<JsonValue, DocumentError> {
+        let (parent, token) = match pointer.rfind('/') {
+            Some(i) => (&pointer[..i], unescape(&pointer[i + 1..])),
+            None => return Err(missing(pointer, "the whole document cannot be removed")),
+        };
+        let (entries, index) = match &self.find_parent(pointer, parent)?.kind {
+            NodeKind::Object(entries) => (
+                entries,
+                entries.iter().position(|e| e.key.as_ref() == Some(&token)),
+            ),
+            NodeKind::Array(entries) => {
+                (entries, array_index(&token).filter(|i| *i < entries.len()))
+            }
+            NodeKind::Scalar => return Err(missing(pointer, "parent is not an object or array")),
+        };
+        let index = index.ok_or_else(|| missing(pointer, "no such value"))?;
+        let entry = &entries[index];
+        let removed = self.value_of(&entry.value);
+        let end = entry.value.span.end;
+        let edits = match (entries.get(index + 1), entry.comma) {
+            (Some(next), _) => vec![(entry.start..next.start, String::new())],
+            (None, Some(comma)) => {
+                let start = match self.line_start(entry.start) {
+                    //Keep the trailing comma style of an array or object written on one line
+                    start if start == entry.start && index > 0 => {
+                        entries[index - 1].comma.unwrap() + 1
+                    }
+                    start => start,
+                };
+                vec![(start..comma + 1, String::new())]
+            }
+            (None, None) if index > 0 => {
+                let comma = entries[index - 1].comma.unwrap();
+                let start = self.line_start(entry.start);
+                if start == entry.start {
+                    vec![(comma..end, String::new())]
+                } else {
+                    //Keep any comment after the comma, which belongs to the previous entry
+                    vec![
+                        (comma..comma + 1, String::new()),
+                        (start..end, String::new()),
+                    ]
+                }
+            }
+            (None, None) => vec![(self.line_start(entry.start)..end, String::new())],
+        };
+        self.splice(edits)?;
+        Ok(removed)
+    }
+
+    ///The edits which add an entry after the last one in a non-empty container, or else just
+    ///inside its opening bracket
+    fn insertion(
+        &self,
+        node: &Node,
+        entries: &[Entry],
+        entry: String,
+    ) -> Vec<(Range<usize>, String)> {
+        let last = match entries.last() {
+            Some(last) => last,
+            None => {
+                let at = node.span.start + 1;
+                return vec![(at..at, entry)];
+            }
+        };
+        let end = last.value.span.end;
+        let after = last.comma.map_or(end, |comma| comma + 1);
+        let close = node.span.end - 1;
+        //When the last entry has a line to itself, the new one gets its own line too, below
+        //any comment after the last entry
+        let line_end = self.text[after..close]
+            .find('\n')
+            .map(|i| after + i)
+            .filter(|line_end| !self.text[after..*line_end].contains("/*"));
+        match (self.line_start(last.start) != last.start, line_end) {
+            (true, Some(line_end)) => {
+                let indent = &self.text[self.line_start(last.start) + 1..last.start];
+                match last.comma {
+                    Some(_) => vec![(line_end..line_end, format!("\n{}{},", indent, entry))],
+                    None => vec![
+                        (line_end..line_end, format!("\n{}{}", indent, entry)),
+                        (end..end, ",".to_owned()),
+                    ],
+                }
+            }
+            _ => match last.comma {
+                Some(_) => vec![(after..after, format!(" {},", entry))],
+                None => vec![(end..end, format!(", {}", entry))],
+            },
+        }
+    }
+
+    ///The offset of the newline before `pos` when only indentation lies between them, else
+    ///`pos` itself
+    fn line_start(&self, pos: usize) -> usize {
+        match self.text[..pos].rfind('\n') {
+            Some(newline)
+                if self.text[newline + 1..pos]
+                    .bytes()
+                    .all(|b| b == b' ' || b == b'\t') =>
+            {
+                newline
+            }
+            _ => pos,
+        }
+    }
+
+    ///Applies edits, which must not overlap, and parses the result
+    fn splice(&mut self, mut edits: Vec<(Range<usize>, String)>) -> Result<(), DocumentError> {
+        edits.sort_by_key(|(range, _)| std::cmp::Reverse(range.start));
+        let mut text = self.text.clone();
+        for (range, replacement) in edits {
+            text.replace_range(range, &replacement);
+        }
+        *self = Self::parse(&text, &self.options)?;
+        Ok(())
+    }
+
+    ///Finds the array or object which would hold the value at `pointer`
+    fn find_parent(&self, pointer: &str, parent: &str) -> Result<&Node, DocumentError> {
+        self.find(parent)
+            .map_err(|_| missing(pointer, "parent does not exist"))
+    }
+
+    fn find(&self, pointer: &str) -> Result<&Node, DocumentError> {
+        if !pointer.is_empty() && !pointer.starts_with('/') {
+            return Err(missing(
+                pointer,
+                "a JSON Pointer must be empty or start with '/'",
+            ));
+        }
+        let mut node = &self.root;
+        for token in pointer.split('/').skip(1).map(unescape) {
+            let next = match &node.kind {
+                NodeKind::Object(entries) => {
+                    entries.iter().find(|e| e.key.as_ref() == Some(&token))
+                }
+                NodeKind::Array(entries) => array_index(&token).and_then(|i| entries.get(i)),
+                NodeKind::Scalar => None,
+            };
+            node = &next.ok_or_else(|| missing(pointer, "no such value"))?.value;
+        }
+        Ok(node)
+    }
+
+    fn value_of(&self, node: &Node) -> JsonValue {
+        match &node.kind {
+            NodeKind::Scalar => JsonValue::parse_with(
+                self.text[node.span.clone()].as_bytes().to_vec(),
+                &self.options,
+            )
+            .expect("scalars are checked when the document is parsed"),
+            NodeKind::Array(entries) => entries.iter().map(|e| self.value_of(&e.value)).collect(),
+            NodeKind::Object(entries) => entries
+                .iter()
+                .map(|e| (e.key.clone().unwrap(), self.value_of(&e.value)))
+                .collect(),
+        }
+    }
+}
+
+impl std::fmt::Display for JsonDocument {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+fn missing(pointer: &str, message: &str) -> DocumentError {
+    DocumentError::Pointer {
+        pointer: pointer.to_owned(),
+        message: message.to_owned(),
+    }
+}
+
+fn unescape(token: &str) -> String {
+    token.replace("~1", "/").replace("~0", "~")
+}
+
+///Reads an RFC 6901 array index, which has no leading zeros
+fn array_index(token: &str) -> Option<usize> {
+    token
+        .parse()
+        .ok()
+        .filter(|i: &usize| i.to_string() == token)
+}
+
+///Finds the spans of the values in a document. Scalars are handed to `JsonValue::parse_with`
+///to check them, so that a document is accepted exactly when `parse_with` would accept it
+struct Scanner<'a> {
+    text: &'a [u8],
+    pos: usize,
+    options: &'a ParseOptions,
+
+    ///How many arrays and objects enclose the current offset
+    depth: usize,
+}
+
+impl Scanner<'_> {
+    fn error(&self, message: &str) -> ParseError {
+        ParseError {
+            offset: self.pos,
+            message: message.to_owned(),
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.text.get(self.pos).copied()
+    }
+
+    ///The length of the whitespace at the current offset, if there is any
+    fn whitespace(&self) -> usize {
+        let rest = &self.text[self.pos..];
+        match rest {
+            [0x20 | 0x09 | 0x0A | 0x0D, ..] => 1,
+            _ if !self.options.json5() => 0,
+            [0x0B | 0x0C, ..] => 1,
+            [0xC2, 0xA0, ..] => 2,
+            [0xEF, 0xBB, 0xBF, ..] => 3,
+            _ if is_line_separator(rest) => 3,
+            _ => 0,
+        }
+    }
+
+    ///Skips whitespace and, if the dialect allows them, comments
+    fn trivia(&mut self) -> Result<(), ParseError> {
+        loop {
+            let rest = &self.text[self.pos..];
+            if self.whitespace() > 0 {
+                self.pos += self.whitespace();
+            } else if self.options.comments() && rest.starts_with(b"//") {
+                self.pos += rest.iter().position(|b| *b == 0x0A).unwrap_or(rest.len());
+            } else if self.options.comments() && rest.starts_with(b"/*") {
+                match rest[2..].windows(2).position(|w| w == b"*/") {
+                    Some(end) => self.pos += end + 4,
+                    None => return Err(self.error("unterminated comment")),
+                }
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn is_quote(&self, b: u8) -> bool {
+        b == b'"' || (b == b'\'' && self.options.json5())
+    }
+
+    fn value(&mut self) -> Result<Node, ParseError> {
+        let start = self.pos;
+        match self.peek() {
+            Some(b'{') => return self.container(true),
+            Some(b'[') => return self.container(false),
+            Some(b) if self.is_quote(b) => self.string()?,
+            Some(b'}' | b']' | b',' | b':') => return Err(self.error("unexpected character")),
+            Some(_) => {
+                self.literal();
+                if self.pos == start {
+                    return Err(self.error("unexpected character"));
+                }
+            }
+            None => return Err(self.error("unexpected end of input")),
+        }
+        let span = start..self.pos;
+        if JsonValue::parse_with(self.text[span.clone()].to_vec(), self.options).is_none() {
+            return Err(ParseError {
+                offset: start,
+                message: "invalid value".to_owned(),
+            });
+        }
+        Ok(Node {
+            span,
+            kind: NodeKind::Scalar,
+        })
+    }
+
+    fn string(&mut self) -> Result<(), ParseError> {
+        let quote = self.text[self.pos];
+        let start = self.pos;
+        self.pos += 1;
+        loop {
+            match self.peek() {
+                None => {
+                    self.pos = start;
+                    return Err(self.error("unterminated string"));
+                }
+                Some(b'\\') => self.pos += 2,
+                Some(b) => {
+                    self.pos += 1;
+                    if b == quote {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+    }
+
+    ///Skips a number, literal or unquoted key
+    fn literal(&mut self) {
+        while let Some(b) = self.peek() {
+            if matches!(b, b'{' | b'}' | b'[' | b']' | b',' | b':' | b'/')
+                || self.is_quote(b)
+                || self.whitespace() > 0
+            {
+                break;
+            }
+            self.pos += 1;
+        }
+    }
+
+    fn container(&mut self, is_object: bool) -> Result<Node, ParseError> {
+        let start = self.pos;
+        let close = if is_object { b'}' } else { b']' };
+        if !self.options.allows_depth(self.depth) {
+            return Err(self.error("nesting is too deep"));
+        }
+        self.depth += 1;
+        self.pos += 1;
+        let mut entries: Vec<Entry> = vec![];
+        let mut keys = HashSet::new();
+        loop {
+            self.trivia()?;
+            if self.peek() == Some(close) {
+                if let Some(comma) = entries.last().and_then(|e| e.comma) {
+                    if !self.options.trailing_commas() {
+                        self.pos = comma;
+                        return Err(self.error("trailing comma"));
+                    }
+                }
+                self.pos += 1;
+                self.depth -= 1;
+                break;
+            }
+            if entries.last().is_some_and(|e| e.comma.is_none()) {
+                return Err(self.error(if is_object {
+                    "expected ',' or '}'"
+                } else {
+                    "expected ',' or ']'"
+                }));
+            }
+            let entry_start = self.pos;
+            let key = if is_object {
+                let key = self.key()?;
+                if !keys.insert(key.clone()) {
+                    self.pos = entry_start;
+                    return Err(self.error("duplicate key"));
+                }
+                self.trivia()?;
+                if self.peek() != Some(b':') {
+                    return Err(self.error("expected ':'"));
+                }
+                self.pos += 1;
+                self.trivia()?;
+                Some(key)
+            } else {
+                None
+            };
+            let value = self.value()?;
+            self.trivia()?;
+            let comma = match self.peek() {
+                Some(b',') => {
+                    self.pos += 1;
+                    Some(self.pos - 1)
+                }
+                _ => None,
+            };
+            entries.push(Entry {
+                key,
+                start: entry_start,
+                value,
+                comma,
+            });
+        }
+        Ok(Node {
+            span: start..self.pos,
+            kind: if is_object {
+                NodeKind::Object(entries)
+            } else {
+                NodeKind::Array(entries)
+            },
+        })
+    }
+
+    fn key(&mut self) -> Result<String, ParseError> {
+        let start = self.pos;
+        match self.peek() {
+            Some(b) if self.is_quote(b) => {
+                self.string()?;
+                let key = JsonValue::parse_with(self.text[start..self.pos].to_vec(), self.options);
+                if let Some(JsonValue::String(key)) = key {
+                    return Ok(key);
+                }
+            }
+            Some(_) if self.options.json5() => {
+                self.literal();
+                if let Ok(key) = std::str::from_utf8(&self.text[start..self.pos]) {
+                    if is_identifier(key) {
+                        return Ok(key.to_owned());
+                    }
+                }
+            }
+            _ => {}
+        }
+        self.pos = start;
+        Err(self.error("expected a key"))
+    }
+}
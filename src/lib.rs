@@ -1,4 +1,108 @@
 use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+
+mod base64;
+mod cbor;
+mod convert;
+mod date;
+mod document;
+mod lines;
+mod macros;
+mod msgpack;
+mod reader;
+mod regex;
+mod schema;
+#[cfg(feature = "serde")]
+mod serde_impl;
+mod spec;
+
+pub use convert::{FromJson, FromJsonError, ToJson, ToJsonObject};
+pub use date::{
+    DateComponent, DateError, DateFormat, JsonDate, JsonDateOnly, JsonDuration, JsonTime, Weekday,
+};
+pub use document::{DocumentError, JsonDocument};
+pub use lines::{JsonLinesError, JsonLinesReader, JsonLinesWriter};
+pub use reader::{ParseError, ParseValidateError, StreamFormat};
+pub use regex::Regex;
+pub use schema::{JsonSchema, SchemaError};
+pub use spec::{AdditionalKeysSpec, FieldSpec, ValidatorSpec};
+
+#[cfg(feature = "serde")]
+pub use serde_impl::{from_value, to_value};
+
+#[cfg(feature = "derive")]
+pub use jasper_derive::{FromJson, ToJson};
+
+///The syntax `JsonValue::parse_with` accepts
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Dialect {
+    ///RFC 8259 JSON, with no extensions
+    #[default]
+    Strict,
+
+    ///JSON with `//` and `/* */` comments and trailing commas in arrays and objects
+    Jsonc,
+
+    ///JSON5: everything JSONC allows, plus unquoted keys, single-quoted strings, the extra
+    ///JavaScript string escapes (including backslash-newline continuations for multiline
+    ///strings), hexadecimal numbers, leading or trailing decimal points, a leading plus sign,
+    ///Infinity and NaN
+    Json5,
+}
+
+///Options for `JsonValue::parse_with`, `JsonDocument::parse`,
+///`JsonValidator::parse_and_validate_with`, `JsonValue::parse_stream_with`,
+///`JsonLinesReader::with_options` and the binary decoders. Start from
+///`ParseOptions::default()` and override fields as needed, e.g.
+///`ParseOptions { dialect: Dialect::Jsonc, ..ParseOptions::default() }`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseOptions {
+    ///The syntax to accept. The binary decoders, `JsonValidator::parse_and_validate_with`,
+    ///`JsonValue::parse_stream_with` and `JsonLinesReader` ignore it
+    pub dialect: Dialect,
+
+    ///How deeply arrays and objects may be nested, where `[1]` has a depth of 1 and `1` a depth
+    ///of 0, if there is a limit. Defaults to 128, which keeps hostile input from exhausting
+    ///the stack
+    pub max_depth: Option<usize>,
+
+    ///The largest input accepted, in bytes, if there is a limit. Defaults to no limit
+    pub max_size: Option<usize>,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            dialect: Dialect::Strict,
+            max_depth: Some(128),
+            max_size: None,
+        }
+    }
+}
+
+impl ParseOptions {
+    ///Returns TRUE if an array or object may be opened at the given depth, counting from 0 for
+    ///the outermost value
+    fn allows_depth(&self, depth: usize) -> bool {
+        self.max_depth.is_none_or(|max| depth < max)
+    }
+
+    fn allows_size(&self, size: usize) -> bool {
+        self.max_size.is_none_or(|max| size <= max)
+    }
+
+    fn comments(&self) -> bool {
+        self.dialect != Dialect::Strict
+    }
+
+    fn trailing_commas(&self) -> bool {
+        self.dialect != Dialect::Strict
+    }
+
+    fn json5(&self) -> bool {
+        self.dialect == Dialect::Json5
+    }
+}
 
 #[derive(Clone, Eq, PartialEq, Debug)]
 enum JsonToken {
@@ -11,12 +115,17 @@ enum JsonToken {
     QuoteMark,
     QuotedValue(u8),
     LiteralValue(u8),
+    //Whitespace or a comment after a literal, which ends it
+    EndLiteral,
     TerminalError,
 }
 
 impl JsonToken {
-    fn to_json_tokens(input: Vec<u8>) -> VecDeque<JsonToken> {
+    fn to_json_tokens(input: Vec<u8>, options: &ParseOptions) -> VecDeque<JsonToken> {
+        let json5 = options.json5();
         let mut in_quote = false;
+        //The byte which closes the current string: a double quote, or in JSON5 a single quote
+        let mut quote = 0x22_u8;
         let mut in_escape = false;
         let mut output = VecDeque::<JsonToken>::new();
         let mut i = 0_usize;
@@ -51,43 +160,63 @@ impl JsonToken {
                             0x74 => {
                                 output.push_back(JsonToken::QuotedValue(0x09));
                             }
-                            0x75 => {
-                                i += 4;
-                                if i >= input.len() {
+                            0x75 => match unicode_escape(&input[i + 1..]) {
+                                Some((c, len)) => {
+                                    let mut buf = [0; 4];
+                                    for u in c.encode_utf8(&mut buf).bytes() {
+                                        output.push_back(JsonToken::QuotedValue(u));
+                                    }
+                                    i += len;
+                                }
+                                None => {
                                     output.push_back(JsonToken::TerminalError);
-                                } else {
-                                    let mut val = 0_u16;
-                                    let hex = [input[i - 3], input[i - 2], input[i - 1], input[i]];
-                                    for j in 0..4 {
-                                        val <<= 4;
-                                        val |= match hex[j] {
-                                            48 => 0,
-                                            49 => 1,
-                                            50 => 2,
-                                            51 => 3,
-                                            52 => 4,
-                                            53 => 5,
-                                            54 => 6,
-                                            55 => 7,
-                                            56 => 8,
-                                            57 => 9,
-                                            65 | 97 => 10,
-                                            66 | 98 => 11,
-                                            67 | 99 => 12,
-                                            68 | 100 => 13,
-                                            69 | 101 => 14,
-                                            70 | 102 => 15,
-                                            _ => {
-                                                output.push_back(JsonToken::TerminalError);
-                                                i = input.len();
-                                                0
-                                            }
+                                    i = input.len();
+                                }
+                            },
+                            0x27 if json5 => {
+                                output.push_back(JsonToken::QuotedValue(0x27));
+                            }
+                            0x76 if json5 => {
+                                output.push_back(JsonToken::QuotedValue(0x0B));
+                            }
+                            0x30 if json5 && !input.get(i + 1).is_some_and(u8::is_ascii_digit) => {
+                                output.push_back(JsonToken::QuotedValue(0x00));
+                            }
+                            0x78 if json5 => {
+                                match input
+                                    .get(i + 1..i + 3)
+                                    .filter(|hex| hex.iter().all(u8::is_ascii_hexdigit))
+                                {
+                                    Some(hex) => {
+                                        let c = char::from(
+                                            hex.iter().fold(0, |n, h| n * 16 + hex_digit(*h)),
+                                        );
+                                        let mut buf = [0; 4];
+                                        for u in c.encode_utf8(&mut buf).bytes() {
+                                            output.push_back(JsonToken::QuotedValue(u));
                                         }
+                                        i += 2;
+                                    }
+                                    None => {
+                                        output.push_back(JsonToken::TerminalError);
+                                        i = input.len();
                                     }
-                                    output.push_back(JsonToken::QuotedValue((val >> 8) as u8));
-                                    output.push_back(JsonToken::QuotedValue((val & 255) as u8));
                                 }
                             }
+                            //A line continuation, which adds nothing to the string
+                            0x0A if json5 => {}
+                            0x0D if json5 => {
+                                if input.get(i + 1) == Some(&0x0A) {
+                                    i += 1;
+                                }
+                            }
+                            0xE2 if json5 && is_line_separator(&input[i..]) => {
+                                i += 2;
+                            }
+                            //JSON5 lets any other character but a digit escape itself
+                            r if json5 && !r.is_ascii_digit() => {
+                                output.push_back(JsonToken::QuotedValue(r));
+                            }
                             _ => {
                                 output.push_back(JsonToken::TerminalError);
                                 i = input.len()
@@ -96,7 +225,7 @@ impl JsonToken {
                         in_escape = false;
                     } else {
                         match input[i] {
-                            0x22 => {
+                            r if r == quote => {
                                 in_quote = false;
                                 output.push_back(JsonToken::QuoteMark);
                             }
@@ -135,46 +264,19 @@ impl JsonToken {
                             0x74 => {
                                 output.push_back(JsonToken::LiteralValue(0x09));
                             }
-                            0x75 => {
-                                i += 4;
-                                if i >= input.len() {
-                                    {
-                                        output.push_back(JsonToken::TerminalError);
-                                        i = input.len()
-                                    }
-                                } else {
-                                    let mut val = 0_u16;
-                                    let hex = [input[i - 3], input[i - 2], input[i - 1], input[i]];
-                                    for j in 0..4 {
-                                        val <<= 4;
-                                        val |= match hex[j] {
-                                            48 => 0,
-                                            49 => 1,
-                                            50 => 2,
-                                            51 => 3,
-                                            52 => 4,
-                                            53 => 5,
-                                            54 => 6,
-                                            55 => 7,
-                                            56 => 8,
-                                            57 => 9,
-                                            65 | 97 => 10,
-                                            66 | 98 => 11,
-                                            67 | 99 => 12,
-                                            68 | 100 => 13,
-                                            69 | 101 => 14,
-                                            70 | 102 => 15,
-                                            _ => {
-                                                output.push_back(JsonToken::TerminalError);
-                                                i = input.len();
-                                                0
-                                            }
-                                        }
+                            0x75 => match unicode_escape(&input[i + 1..]) {
+                                Some((c, len)) => {
+                                    let mut buf = [0; 4];
+                                    for u in c.encode_utf8(&mut buf).bytes() {
+                                        output.push_back(JsonToken::LiteralValue(u));
                                     }
-                                    output.push_back(JsonToken::LiteralValue((val >> 8) as u8));
-                                    output.push_back(JsonToken::LiteralValue((val & 255) as u8));
+                                    i += len;
                                 }
-                            }
+                                None => {
+                                    output.push_back(JsonToken::TerminalError);
+                                    i = input.len();
+                                }
+                            },
                             _ => {
                                 output.push_back(JsonToken::TerminalError);
                                 i = input.len();
@@ -183,7 +285,7 @@ impl JsonToken {
                         in_escape = false;
                     } else {
                         match input[i] {
-                            0x20 | 0x09 | 0x0A | 0x0D => {}
+                            0x20 | 0x09 | 0x0A | 0x0D => end_literal(&mut output),
                             0x7B => {
                                 output.push_back(JsonToken::BeginObject);
                             }
@@ -204,8 +306,46 @@ impl JsonToken {
                             }
                             0x22 => {
                                 in_quote = true;
+                                quote = 0x22;
                                 output.push_back(JsonToken::QuoteMark);
                             }
+                            0x27 if json5 => {
+                                in_quote = true;
+                                quote = 0x27;
+                                output.push_back(JsonToken::QuoteMark);
+                            }
+                            0x2F if options.comments() && input.get(i + 1) == Some(&0x2F) => {
+                                end_literal(&mut output);
+                                i = input[i..]
+                                    .iter()
+                                    .position(|u| *u == 0x0A)
+                                    .map_or(input.len(), |end| i + end);
+                            }
+                            0x2F if options.comments() && input.get(i + 1) == Some(&0x2A) => {
+                                end_literal(&mut output);
+                                match input[i + 2..].windows(2).position(|w| w == b"*/") {
+                                    Some(end) => i += end + 3,
+                                    None => {
+                                        output.push_back(JsonToken::TerminalError);
+                                        i = input.len();
+                                    }
+                                }
+                            }
+                            //The extra whitespace JSON5 allows: vertical tab, form feed,
+                            //no-break space, byte order mark and the Unicode line separators
+                            0x0B | 0x0C if json5 => end_literal(&mut output),
+                            0xC2 if json5 && input.get(i + 1) == Some(&0xA0) => {
+                                end_literal(&mut output);
+                                i += 1;
+                            }
+                            0xEF if json5 && input.get(i + 1..i + 3) == Some(&[0xBB, 0xBF]) => {
+                                end_literal(&mut output);
+                                i += 2;
+                            }
+                            0xE2 if json5 && is_line_separator(&input[i..]) => {
+                                end_literal(&mut output);
+                                i += 2;
+                            }
                             0x5C => {
                                 in_escape = true;
                             }
@@ -222,109 +362,76 @@ impl JsonToken {
     }
 }
 
-///A structure for holding a DateTime, having been parsed or intended to parsed to standard
-///JavaScript format for dates (YYYY-MM-DDTHH:mm:ss)
-pub struct JsonDate {
-    ///The actual year
-    year: u16,
-
-    ///The actual month (1 is January, 12 is December)
-    month: u8,
-
-    ///The actual date (e.g. 31 for January 31st)
-    date: u8,
-
-    ///The hour on a 24-hour scale (where 0 is midnight and 23 is 11 PM)
-    hour: u8,
-
-    ///The minute, zero-indexed
-    minute: u8,
-
-    ///The second, zero-indexed
-    second: u8,
+///Marks the end of a literal, so that literals separated by whitespace are not read as one
+fn end_literal(output: &mut VecDeque<JsonToken>) {
+    if matches!(output.back(), Some(JsonToken::LiteralValue(_))) {
+        output.push_back(JsonToken::EndLiteral);
+    }
 }
 
-impl JsonDate {
-    ///Generates a JsonDate from a typical JavaScript date formatted string. It
-    ///is tolerant of missing values (for example, 2023-06-06 will be parsed just fine)
-    pub fn from_str(input: &str) -> Option<JsonDate> {
-        let datetimesplit = input.split("T").collect::<Vec<&str>>();
-        let datesplit = datetimesplit[0].split("-").collect::<Vec<&str>>();
-        let timesplit = datetimesplit
-            .get(1)
-            .unwrap_or(&"")
-            .split(":")
-            .collect::<Vec<&str>>();
-        let year_string = datesplit.get(0).unwrap_or(&"");
-        let month_string = datesplit.get(1).unwrap_or(&"");
-        let date_string = datesplit.get(2).unwrap_or(&"");
-        let (hour_string, minute_string, second_string) = (
-            timesplit.get(0).unwrap_or(&""),
-            timesplit.get(1).unwrap_or(&""),
-            timesplit.get(2).unwrap_or(&""),
-        );
-        match (
-            year_string.parse::<u16>(),
-            month_string.parse::<u8>(),
-            date_string.parse::<u8>(),
-        ) {
-            (Ok(year), Ok(month), Ok(date)) => {
-                let hour = hour_string.parse::<u8>().unwrap_or(0);
-                let minute = minute_string.parse::<u8>().unwrap_or(0);
-                let second = second_string.parse::<u8>().unwrap_or(0);
-                Some(Self {
-                    year,
-                    month,
-                    date,
-                    hour,
-                    minute,
-                    second,
-                })
-            }
-            _ => None,
-        }
+///Decodes the four hex digits of a `\u` escape at the start of the input, and the low half
+///which must follow a high surrogate, returning the character and how many bytes were read
+fn unicode_escape(input: &[u8]) -> Option<(char, usize)> {
+    let hex4 = |hex: Option<&[u8]>| {
+        let hex = hex.filter(|hex| hex.iter().all(u8::is_ascii_hexdigit))?;
+        Some(hex.iter().fold(0, |n, h| n * 16 + u32::from(hex_digit(*h))))
+    };
+    let code = hex4(input.get(..4))?;
+    if !(0xD800..0xDC00).contains(&code) {
+        //Lone low surrogates are not characters, so from_u32 rejects them
+        return char::from_u32(code).map(|c| (c, 4));
     }
-
-    fn four_digits(input: u16) -> String {
-        let input = input % 10000;
-        if input > 999 {
-            format!("{}", input)
-        } else if input > 99 {
-            format!("0{}", input)
-        } else if input > 9 {
-            format!("00{}", input)
-        } else {
-            format!("000{}", input)
-        }
+    if input.get(4..6) != Some(b"\\u") {
+        return None;
     }
+    let low = hex4(input.get(6..10)).filter(|low| (0xDC00..0xE000).contains(low))?;
+    char::from_u32(0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00)).map(|c| (c, 10))
+}
 
-    fn two_digits(input: u8) -> String {
-        let input = input % 100;
-        if input > 9 {
-            format!("{}", input)
-        } else {
-            format!("0{}", input)
-        }
+fn hex_digit(h: u8) -> u8 {
+    match h {
+        b'0'..=b'9' => h - b'0',
+        b'a'..=b'f' => h - b'a' + 10,
+        _ => h - b'A' + 10,
     }
+}
 
-    ///Converts this date to a typical JavaScript DateTime formatted string
-    pub fn to_string(&self) -> String {
-        format!(
-            "{}-{}-{}T{}:{}:{}",
-            Self::four_digits(self.year),
-            Self::two_digits(self.month),
-            Self::two_digits(self.date),
-            Self::two_digits(self.hour),
-            Self::two_digits(self.minute),
-            Self::two_digits(self.second)
-        )
-    }
+///Returns TRUE if the input starts with U+2028 or U+2029
+fn is_line_separator(input: &[u8]) -> bool {
+    matches!(input, [0xE2, 0x80, 0xA8 | 0xA9, ..])
+}
+
+///Returns TRUE if the input is an identifier, as JSON5 requires of unquoted keys
+fn is_identifier(input: &str) -> bool {
+    let mut chars = input.chars();
+    let is_start = |c: char| c.is_alphabetic() || c == '$' || c == '_';
+    chars.next().is_some_and(is_start) && chars.all(|c| is_start(c) || c.is_alphanumeric())
 }
 
 struct JsonParserUtilities();
 
 impl JsonParserUtilities {
+    fn parse_value(
+        first: JsonToken,
+        input: &mut VecDeque<JsonToken>,
+        options: &ParseOptions,
+        depth: usize,
+    ) -> Option<JsonValue> {
+        match first {
+            JsonToken::BeginArray | JsonToken::BeginObject if !options.allows_depth(depth) => None,
+            JsonToken::BeginArray => Self::parse_array(input, options, depth + 1),
+            JsonToken::BeginObject => Self::parse_object(input, options, depth + 1),
+            JsonToken::LiteralValue(u) => Self::parse_literal(input, u, options),
+            JsonToken::QuoteMark => Self::parse_quote(input),
+            _ => None,
+        }
+    }
+
     fn parse_quote(input: &mut VecDeque<JsonToken>) -> Option<JsonValue> {
+        Self::quoted_string(input).map(JsonValue::String)
+    }
+
+    fn quoted_string(input: &mut VecDeque<JsonToken>) -> Option<String> {
         let mut bytes: Vec<u8> = vec![];
         loop {
             match input.pop_front() {
@@ -339,156 +446,191 @@ impl JsonParserUtilities {
                 }
             }
         }
-        match String::from_utf8(bytes) {
-            Ok(str) => Some(JsonValue::String(str)),
+        String::from_utf8(bytes).ok()
+    }
+
+    fn literal_string(input: &mut VecDeque<JsonToken>, first_value: u8) -> Option<String> {
+        let mut bytes: Vec<u8> = vec![first_value];
+        while let Some(JsonToken::LiteralValue(u)) = input.front() {
+            bytes.push(*u);
+            input.pop_front().unwrap();
+        }
+        if input.front() == Some(&JsonToken::EndLiteral) {
+            input.pop_front().unwrap();
+        }
+        String::from_utf8(bytes).ok()
+    }
+
+    fn parse_literal(
+        input: &mut VecDeque<JsonToken>,
+        first_value: u8,
+        options: &ParseOptions,
+    ) -> Option<JsonValue> {
+        let str = Self::literal_string(input, first_value)?;
+        match str.as_str() {
+            "true" => Some(JsonValue::Boolean(true)),
+            "false" => Some(JsonValue::Boolean(false)),
+            "null" => Some(JsonValue::Null),
+            _ if options.json5() => Self::json5_number(&str).map(JsonValue::Number),
+            _ if Self::is_rfc_number(&str) => str.parse::<f64>().ok().map(JsonValue::Number),
             _ => None,
         }
     }
 
-    fn parse_literal(input: &mut VecDeque<JsonToken>, first_value: u8) -> Option<JsonValue> {
-        let mut bytes: Vec<u8> = vec![first_value];
-        loop {
-            match input.front() {
-                Some(JsonToken::LiteralValue(u)) => {
-                    bytes.push(*u);
-                    input.pop_front().unwrap();
-                }
-                _ => {
-                    break;
-                }
+    ///Returns TRUE if the literal is a number as RFC 8259 writes them
+    fn is_rfc_number(literal: &str) -> bool {
+        let b = literal.as_bytes();
+        let digits = |i: &mut usize| {
+            let start = *i;
+            while b.get(*i).is_some_and(u8::is_ascii_digit) {
+                *i += 1;
+            }
+            *i > start
+        };
+        let mut i = usize::from(b.first() == Some(&b'-'));
+        match b.get(i) {
+            Some(b'0') => i += 1,
+            Some(b'1'..=b'9') => {
+                digits(&mut i);
             }
+            _ => return false,
         }
-        match String::from_utf8(bytes) {
-            Ok(str) => {
-                let str = str.to_lowercase();
-                if str == "true" {
-                    Some(JsonValue::Boolean(true))
-                } else if str == "false" {
-                    Some(JsonValue::Boolean(false))
-                } else if str == "null" {
-                    Some(JsonValue::Null)  
-                } else {
-                    match str.parse::<f64>() {
-                        Ok(f) => Some(JsonValue::Number(f)),
-                        _ => None,
-                    }
-                }
+        if b.get(i) == Some(&b'.') {
+            i += 1;
+            if !digits(&mut i) {
+                return false;
+            }
+        }
+        if matches!(b.get(i), Some(b'e' | b'E')) {
+            i += 1;
+            if matches!(b.get(i), Some(b'+' | b'-')) {
+                i += 1;
+            }
+            if !digits(&mut i) {
+                return false;
             }
-            _ => None,
         }
+        i == b.len()
+    }
+
+    ///Reads a JSON5 number, which may also be hexadecimal, Infinity or NaN, have a leading
+    ///plus sign, or start or end with a decimal point
+    fn json5_number(literal: &str) -> Option<f64> {
+        let (negative, unsigned) = match literal.as_bytes().first() {
+            Some(b'-') => (true, &literal[1..]),
+            Some(b'+') => (false, &literal[1..]),
+            _ => (false, literal),
+        };
+        let magnitude = if unsigned == "Infinity" {
+            f64::INFINITY
+        } else if unsigned == "NaN" {
+            f64::NAN
+        } else if let Some(hex) = unsigned
+            .strip_prefix("0x")
+            .or_else(|| unsigned.strip_prefix("0X"))
+        {
+            if hex.is_empty() || !hex.bytes().all(|h| h.is_ascii_hexdigit()) {
+                return None;
+            }
+            hex.bytes()
+                .fold(0.0, |n, h| n * 16.0 + f64::from(hex_digit(h)))
+        } else {
+            //f64::from_str accepts the decimal forms JSON5 allows, but also inf and nan
+            let decimal = unsigned
+                .bytes()
+                .next()
+                .is_some_and(|u| u.is_ascii_digit() || u == b'.')
+                && unsigned
+                    .bytes()
+                    .all(|u| matches!(u, b'0'..=b'9' | b'.' | b'e' | b'E' | b'+' | b'-'));
+            if !decimal {
+                return None;
+            }
+            unsigned.parse::<f64>().ok()?
+        };
+        Some(if negative { -magnitude } else { magnitude })
+    }
+
+    ///Reads an unquoted JSON5 object key, which must be an identifier
+    fn identifier(input: &mut VecDeque<JsonToken>, first_value: u8) -> Option<String> {
+        Some(Self::literal_string(input, first_value)?).filter(|str| is_identifier(str))
     }
 
-    fn parse_array(input: &mut VecDeque<JsonToken>) -> Option<JsonValue> {
+    fn parse_array(
+        input: &mut VecDeque<JsonToken>,
+        options: &ParseOptions,
+        depth: usize,
+    ) -> Option<JsonValue> {
         let mut v: Vec<JsonValue> = Vec::new();
+        let mut after_comma = false;
         loop {
             match input.pop_front() {
                 None => {
                     return None;
                 }
-                Some(JsonToken::BeginArray) => match Self::parse_array(input) {
-                    None => {
-                        return None;
-                    }
-                    Some(arr) => {
-                        v.push(arr);
-                    }
-                },
-                Some(JsonToken::BeginObject) => match Self::parse_object(input) {
-                    None => {
-                        return None;
-                    }
-                    Some(obj) => {
-                        v.push(obj);
-                    }
-                },
-                Some(JsonToken::LiteralValue(u)) => match Self::parse_literal(input, u) {
-                    None => {
+                Some(JsonToken::EndArray) => {
+                    if after_comma && !options.trailing_commas() {
                         return None;
                     }
-                    Some(litval) => {
-                        v.push(litval);
-                    }
-                },
-                Some(JsonToken::QuoteMark) => match Self::parse_quote(input) {
-                    None => {
+                    break;
+                }
+                Some(JsonToken::EndValue) => {
+                    if v.is_empty() || after_comma {
                         return None;
                     }
-                    Some(quote) => {
-                        v.push(quote);
-                    }
-                },
-                Some(JsonToken::EndArray) => {
-                    break;
+                    after_comma = true;
                 }
-                Some(JsonToken::EndValue) => {}
-                _ => {
+                Some(_) if !v.is_empty() && !after_comma => {
                     return None;
                 }
+                Some(token) => {
+                    v.push(Self::parse_value(token, input, options, depth)?);
+                    after_comma = false;
+                }
             }
         }
-        if input.front() == Some(&JsonToken::EndValue) {
-            input.pop_front();
-        }
         Some(JsonValue::Array(v))
     }
 
-    fn parse_object(input: &mut VecDeque<JsonToken>) -> Option<JsonValue> {
+    fn parse_object(
+        input: &mut VecDeque<JsonToken>,
+        options: &ParseOptions,
+        depth: usize,
+    ) -> Option<JsonValue> {
         let mut v: HashMap<String, JsonValue> = HashMap::new();
+        let mut after_comma = false;
         loop {
-            let key: String;
-            match input.pop_front() {
+            let key = match input.pop_front() {
                 Some(JsonToken::EndObject) => {
+                    if after_comma && !options.trailing_commas() {
+                        return None;
+                    }
                     break;
                 }
-                Some(JsonToken::QuoteMark) => {
-                    let mut bytes: Vec<u8> = vec![];
-                    loop {
-                        match input.pop_front() {
-                            Some(JsonToken::QuotedValue(u)) => {
-                                bytes.push(u);
-                            }
-                            Some(JsonToken::QuoteMark) => {
-                                break;
-                            }
-                            _ => {
-                                return None;
-                            }
-                        }
-                    }
-                    match String::from_utf8(bytes) {
-                        Ok(str) => key = str,
-                        _ => {
-                            return None;
-                        }
-                    }
+                //Members after the first must follow a comma
+                Some(_) if !v.is_empty() && !after_comma => {
+                    return None;
                 }
+                Some(JsonToken::QuoteMark) => Self::quoted_string(input)?,
+                Some(JsonToken::LiteralValue(u)) if options.json5() => Self::identifier(input, u)?,
                 _ => {
                     return None;
                 }
-            }
+            };
             if input.pop_front() != Some(JsonToken::EndKey) {
                 return None;
             }
-            let value = match input.pop_front() {
-                Some(JsonToken::BeginArray) => Self::parse_array(input),
-                Some(JsonToken::BeginObject) => Self::parse_object(input),
-                Some(JsonToken::LiteralValue(u)) => Self::parse_literal(input, u),
-                Some(JsonToken::QuoteMark) => Self::parse_quote(input),
-                _ => None,
-            };
-            if input.front() == Some(&JsonToken::EndValue) {
+            let value = Self::parse_value(input.pop_front()?, input, options, depth)?;
+            after_comma = input.front() == Some(&JsonToken::EndValue);
+            if after_comma {
                 input.pop_front().unwrap();
             }
-            if v.contains_key(&key) {
-                return None;
-            } else {
-                match value {
-                    Some(value) => {
-                        v.insert(key, value);
-                    }
-                    _ => {
-                        return None;
-                    }
+            match v.entry(key) {
+                std::collections::hash_map::Entry::Vacant(e) => {
+                    e.insert(value);
+                }
+                std::collections::hash_map::Entry::Occupied(_) => {
+                    return None;
                 }
             }
         }
@@ -497,7 +639,7 @@ impl JsonParserUtilities {
 }
 
 ///A JSON value
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum JsonValue {
     ///A null value
     Null,
@@ -526,7 +668,7 @@ impl JsonValue {
 
     fn stringify_actual_string(input: &str) -> String {
         let mut v: Vec<u8> = vec![34];
-        input.as_bytes().clone().into_iter().for_each(|x| match x {
+        input.as_bytes().iter().for_each(|x| match x {
             0x22 => {
                 v.push(0x5C);
                 v.push(0x22);
@@ -607,7 +749,7 @@ impl JsonValue {
         match self {
             Self::Boolean(b) => Some(*b),
             Self::Number(n) => Some(*n != 0_f64),
-            Self::String(s) => Some(s != "0" && s != ""),
+            Self::String(s) => Some(s != "0" && !s.is_empty()),
             Self::Array(a) => {
                 if a.len() == 1 {
                     Self::get_boolean(&a[0])
@@ -624,10 +766,7 @@ impl JsonValue {
         match self {
             Self::Boolean(b) => Some(if *b { 1_f64 } else { 0_f64 }),
             Self::Number(n) => Some(*n),
-            Self::String(s) => match s.parse::<f64>() {
-                Ok(n) => Some(n),
-                _ => None,
-            },
+            Self::String(s) => s.parse::<f64>().ok(),
             Self::Array(a) => {
                 if a.len() == 1 {
                     Self::get_number(&a[0])
@@ -670,12 +809,7 @@ impl JsonValue {
             Self::Number(n) => vec![JsonValue::Number(*n)],
             Self::String(s) => vec![JsonValue::String(s.clone())],
             Self::Array(arr) => arr.clone(),
-            Self::Object(obj) => obj
-                .values()
-                .clone()
-                .into_iter()
-                .map(|x| x.clone())
-                .collect::<Vec<JsonValue>>(),
+            Self::Object(obj) => obj.values().cloned().collect::<Vec<JsonValue>>(),
         }
     }
 
@@ -690,27 +824,23 @@ impl JsonValue {
 
     ///Returns TRUE if and only if the value is null
     pub fn is_null(&self) -> bool {
-        match self {
-            Self::Null => true,
-            _ => false,
-        }
+        matches!(self, Self::Null)
     }
 
     ///Returns Some(a u64 integer) if the value can be parsed as such; else None
     pub fn get_integer(&self) -> Option<u64> {
         match self.get_string() {
-            None => match self.get_number(){
-                Some(f) => if ((f as u64) as f64) == f {
-                    Some(f as u64)
-                } else {
-                    None
+            None => match self.get_number() {
+                Some(f) => {
+                    if ((f as u64) as f64) == f {
+                        Some(f as u64)
+                    } else {
+                        None
+                    }
                 }
                 _ => None,
             },
-            Some(s) => match s.parse::<u64>() {
-                Ok(u) => Some(u),
-                _ => None,
-            },
+            Some(s) => s.parse::<u64>().ok(),
         }
     }
 
@@ -724,17 +854,77 @@ impl JsonValue {
         }
     }
 
-    ///Parses a JsonValue from an input array of bytes
-    pub fn parse(input: Vec<u8>) -> Option<JsonValue> {
-        let mut tokens = JsonToken::to_json_tokens(input);
-        match tokens.pop_front() {
-            Some(JsonToken::BeginArray) => JsonParserUtilities::parse_array(&mut tokens),
-            Some(JsonToken::BeginObject) => JsonParserUtilities::parse_object(&mut tokens),
-            Some(JsonToken::LiteralValue(u)) => JsonParserUtilities::parse_literal(&mut tokens, u),
-            Some(JsonToken::QuoteMark) => JsonParserUtilities::parse_quote(&mut tokens),
+    ///Returns Some(a JsonDate) if the value is encoded in the given format; else None. The
+    ///Unix timestamp formats accept numbers and strings holding numbers; the other formats
+    ///accept only strings
+    pub fn get_json_date_with(&self, format: DateFormat) -> Option<JsonDate> {
+        JsonDate::from_json_with(self, format)
+    }
+
+    ///Returns Some(a JsonDateOnly) if the value is a String holding an ISO 8601 date with no
+    ///time of day (e.g. 2024-05-01); else None
+    pub fn get_json_date_only(&self) -> Option<JsonDateOnly> {
+        match self {
+            Self::String(str) => JsonDateOnly::from_str(str),
             _ => None,
         }
     }
+
+    ///Returns Some(a JsonTime) if the value is a String holding an ISO 8601 time of day (e.g.
+    ///13:45:00); else None
+    pub fn get_json_time(&self) -> Option<JsonTime> {
+        match self {
+            Self::String(str) => JsonTime::from_str(str),
+            _ => None,
+        }
+    }
+
+    ///Returns Some(a JsonDuration) if the value is a String holding an ISO 8601 duration (e.g.
+    ///P3DT4H); else None
+    pub fn get_json_duration(&self) -> Option<JsonDuration> {
+        match self {
+            Self::String(str) => JsonDuration::from_str(str),
+            _ => None,
+        }
+    }
+
+    ///Parses a series of JsonValues from an input array of bytes, delimited as the format
+    ///describes. Error offsets are from the start of the whole input
+    pub fn parse_stream(
+        input: &[u8],
+        format: StreamFormat,
+    ) -> impl Iterator<Item = Result<JsonValue, ParseError>> + '_ {
+        Self::parse_stream_with(input, format, &ParseOptions::default())
+    }
+
+    ///Parses a series of JsonValues as `parse_stream` does, within the depth and size limits the
+    ///options set. The size limit applies to the whole input, which yields a single error if it
+    ///is too large. The dialect is ignored
+    pub fn parse_stream_with<'a>(
+        input: &'a [u8],
+        format: StreamFormat,
+        options: &ParseOptions,
+    ) -> impl Iterator<Item = Result<JsonValue, ParseError>> + 'a {
+        reader::JsonStream::new(input, format, options)
+    }
+
+    ///Parses a JsonValue from an input array of bytes of strict RFC 8259 JSON
+    pub fn parse(input: Vec<u8>) -> Option<JsonValue> {
+        Self::parse_with(input, &ParseOptions::default())
+    }
+
+    ///Parses a JsonValue from an input array of bytes in the dialect the options select, e.g.
+    ///JSONC for a config file with comments
+    pub fn parse_with(input: Vec<u8>, options: &ParseOptions) -> Option<JsonValue> {
+        if !options.allows_size(input.len()) {
+            return None;
+        }
+        let mut tokens = JsonToken::to_json_tokens(input, options);
+        let first = tokens.pop_front()?;
+        let value = JsonParserUtilities::parse_value(first, &mut tokens, options, 0)?;
+        //Anything after the value, such as a second value, makes the input invalid
+        tokens.is_empty().then_some(value)
+    }
 }
 
 ///JsonValidators hold validation parameters for parsing JSON inputs. For example,
@@ -760,84 +950,1096 @@ pub enum JsonValidator {
     ///Validates only integer values (may be in form of string or number) that fit a functional
     ///criteria
     Integer(fn(&u64) -> bool),
-    
+
+    ///Validates only float values within the (inclusive) bounds. Unlike a `Number` validator,
+    ///a failure reports which bound was broken
+    NumberRange {
+        ///The minimum, if any
+        min: Option<f64>,
+
+        ///The maximum, if any
+        max: Option<f64>,
+    },
+
+    ///Validates only integer values (may be in form of string or number) within the
+    ///(inclusive) bounds, which are compared exactly rather than as floats
+    IntegerRange {
+        ///The minimum, if any
+        min: Option<u64>,
+
+        ///The maximum, if any
+        max: Option<u64>,
+    },
+
+    ///Validates only string values with the given length and pattern. See `StringConstraints`
+    StringOf(StringConstraints),
+
     ///Validates only DateTime values (strings) that fit a functional criteria
     DateTime(fn(&JsonDate) -> bool),
 
+    ///Validates only DateTime values encoded in any of the given formats (tried in order) that
+    ///fit a functional criteria
+    DateTimeWith(Vec<DateFormat>, fn(&JsonDate) -> bool),
+
+    ///Validates only date values (strings such as 2024-05-01, with no time of day) that fit a
+    ///functional criteria
+    Date(fn(&JsonDateOnly) -> bool),
+
+    ///Validates only time of day values (strings such as 13:45:00) that fit a functional
+    ///criteria
+    Time(fn(&JsonTime) -> bool),
+
+    ///Validates only ISO 8601 duration values (strings such as P3DT4H) that fit a functional
+    ///criteria
+    Duration(fn(&JsonDuration) -> bool),
+
     ///Validates an array based on whether every value within it is validated by its internal
     ///JsonValidator. Note that this internal JsonValidator must be boxed. Also note that
     ///an Array validator will automatically validate an empty array
     Array(Box<JsonValidator>),
 
+    ///Validates an array against size, uniqueness and containment constraints as well as
+    ///validating each of its elements. See `ArrayConstraints`
+    ArrayOf(ArrayConstraints),
+
     ///Validates an object based on whether its key,value pairs are validated by the corresponding
     ///key,value validators. Key,value pairs not mentioned in the validator are not checked
     Object(Vec<(String, JsonValidator)>),
 
+    ///Validates an object field by field. Unlike `Object`, fields may be optional, map-like keys
+    ///can be validated by pattern, and keys that are neither listed in `fields` nor matched by
+    ///any of `patterns` are handled according to `additional_keys`
+    ObjectFields {
+        ///The named keys of the object
+        fields: Vec<Field>,
+
+        ///Validators for every key matching the corresponding pattern
+        patterns: Vec<(KeyPattern, JsonValidator)>,
+
+        ///The policy for keys matched by neither `fields` nor `patterns`
+        additional_keys: AdditionalKeys,
+    },
+
     ///Holds multiple validators, and will return true if any of its internal members return true
     Or(Vec<JsonValidator>),
+
+    ///Holds multiple validators, and will return true only if all of its internal members
+    ///return true
+    And(Vec<JsonValidator>),
+
+    ///Returns true if and only if its internal validator returns false
+    Not(Box<JsonValidator>),
+
+    ///Holds multiple validators, and will return true if exactly one of its internal members
+    ///returns true
+    ExactlyOne(Vec<JsonValidator>),
+
+    ///Validates only values equal to the given value (no coercion is performed, so "1" does
+    ///not equal 1)
+    Const(JsonValue),
+
+    ///Validates only values equal to one of the given values (no coercion is performed)
+    Enum(Vec<JsonValue>),
+
+    ///Validates a fixed-shape array, which must have exactly as many elements as there are
+    ///internal validators, each element being validated by the validator in the same position
+    Tuple(Vec<JsonValidator>),
+
+    ///Validates any value using a closure. Unlike the function pointers held by the other
+    ///variants, the closure may capture runtime configuration (e.g. a list of allowed values
+    ///loaded from config). See `JsonValidator::custom`
+    Custom(Arc<dyn Fn(&JsonValue) -> bool + Send + Sync>),
 }
 
+///The constraints checked by a `JsonValidator::StringOf` validator. Start from
+///`StringConstraints::default()`, which accepts any string, and override fields as needed, e.g.
+///`StringConstraints { max_length: Some(10), ..StringConstraints::default() }`
+#[derive(Clone, Debug, Default)]
+pub struct StringConstraints {
+    ///The minimum length in characters, if any
+    pub min_length: Option<usize>,
+
+    ///The maximum length in characters, if any
+    pub max_length: Option<usize>,
+
+    ///A regular expression the string must match (anywhere, unless anchored), if any
+    pub pattern: Option<Regex>,
+}
+
+///The constraints checked by a `JsonValidator::ArrayOf` validator. Start from
+///`ArrayConstraints::new` and override fields as needed, e.g.
+///`ArrayConstraints { min_items: Some(1), ..ArrayConstraints::new(JsonValidator::RubberStamp) }`
+#[derive(Clone)]
+pub struct ArrayConstraints {
+    ///The validator for every element
+    pub items: Box<JsonValidator>,
+
+    ///The minimum number of elements, if any
+    pub min_items: Option<usize>,
+
+    ///The maximum number of elements, if any
+    pub max_items: Option<usize>,
+
+    ///Whether duplicate elements fail validation
+    pub unique_items: bool,
+
+    ///A requirement that some number of elements be validated by another validator
+    pub contains: Option<Contains>,
+
+    ///When true, values that are not arrays fail validation. Otherwise they are coerced the
+    ///same way `JsonValue::get_array` coerces them, so a scalar counts as a one-element array
+    pub strict: bool,
+}
+
+impl ArrayConstraints {
+    ///Creates constraints that validate each element with the given validator and impose no
+    ///other requirement
+    pub fn new(items: JsonValidator) -> Self {
+        Self {
+            items: Box::new(items),
+            min_items: None,
+            max_items: None,
+            unique_items: false,
+            contains: None,
+            strict: false,
+        }
+    }
+}
+
+///Requires that between `min` and `max` (inclusive) elements of an array be validated by
+///`validator`. Other elements are allowed
+#[derive(Clone)]
+pub struct Contains {
+    ///The validator matching elements must pass
+    pub validator: Box<JsonValidator>,
+
+    ///The minimum number of matching elements
+    pub min: usize,
+
+    ///The maximum number of matching elements, if any
+    pub max: Option<usize>,
+}
+
+///A named key within a `JsonValidator::ObjectFields` validator
+#[derive(Clone)]
+pub struct Field {
+    ///The key
+    pub name: String,
+
+    ///The validator for the key's value
+    pub validator: JsonValidator,
+
+    ///Whether validation fails when the key is missing
+    pub required: bool,
+
+    ///The value `JsonValidator::normalize` fills in when the key is missing, if any
+    pub default: Option<JsonValue>,
+}
+
+impl Field {
+    ///Creates a field that must be present
+    pub fn required(name: &str, validator: JsonValidator) -> Self {
+        Self {
+            name: name.to_owned(),
+            validator,
+            required: true,
+            default: None,
+        }
+    }
+
+    ///Creates a field that is validated only when present
+    pub fn optional(name: &str, validator: JsonValidator) -> Self {
+        Self {
+            name: name.to_owned(),
+            validator,
+            required: false,
+            default: None,
+        }
+    }
+
+    ///Sets the value `JsonValidator::normalize` fills in when the key is missing
+    pub fn with_default(mut self, default: JsonValue) -> Self {
+        self.default = Some(default);
+        self
+    }
+}
+
+///What a `JsonValidator::ObjectFields` validator does with keys it does not otherwise know about
+#[derive(Clone)]
+pub enum AdditionalKeys {
+    ///Unknown keys are allowed and not checked (the behaviour of `JsonValidator::Object`)
+    Allow,
+
+    ///Any unknown key fails validation
+    Deny,
+
+    ///Unknown keys are allowed if their values are validated by the internal JsonValidator
+    Validate(Box<JsonValidator>),
+
+    ///Unknown keys are allowed, but `JsonValidator::normalize` removes them
+    Strip,
+}
+
+///Selects the keys of a map-like object that a `JsonValidator::ObjectFields` pattern applies to
+#[derive(Clone)]
+pub enum KeyPattern {
+    ///Matches keys matched (anywhere, unless anchored) by a regular expression
+    Regex(Regex),
+
+    ///Matches keys for which the closure returns true
+    Predicate(Arc<dyn Fn(&str) -> bool + Send + Sync>),
+}
+
+impl KeyPattern {
+    ///Creates a `KeyPattern::Regex`, returning an error message if the pattern is malformed
+    pub fn regex(pattern: &str) -> Result<Self, String> {
+        Regex::new(pattern).map(KeyPattern::Regex)
+    }
+
+    ///Creates a `KeyPattern::Predicate` from a closure
+    pub fn predicate<F>(f: F) -> Self
+    where
+        F: Fn(&str) -> bool + Send + Sync + 'static,
+    {
+        KeyPattern::Predicate(Arc::new(f))
+    }
+
+    ///Returns TRUE if the pattern applies to the key
+    pub fn matches(&self, key: &str) -> bool {
+        match self {
+            KeyPattern::Regex(regex) => regex.is_match(key),
+            KeyPattern::Predicate(f) => f(key),
+        }
+    }
+}
+
+//JsonValidators are shared between threads, so every variant must stay Clone, Send and Sync
+const _: fn() = || {
+    fn assert_shareable<T: Clone + Send + Sync>() {}
+    assert_shareable::<JsonValidator>();
+};
+
+///A single validation failure reported by `JsonValidator::validate_detailed`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValidationError {
+    ///JSON Pointer (RFC 6901) to the offending value, where "" is the root value
+    pub path: String,
+
+    ///The name of the validator that failed (e.g. "Integer" or "Object")
+    pub validator: &'static str,
+
+    ///The type of value the failing validator expected (e.g. "integer")
+    pub expected: &'static str,
+
+    ///A human-readable description of the failure
+    pub message: String,
+}
+
+impl ValidationError {
+    fn new(path: &str, validator: &'static str, expected: &'static str, message: String) -> Self {
+        Self {
+            path: path.to_owned(),
+            validator,
+            expected,
+            message,
+        }
+    }
+
+    ///Appends a reference token to a JSON Pointer, escaping '~' and '/' as RFC 6901 requires
+    fn child_path(path: &str, token: &str) -> String {
+        format!("{}/{}", path, token.replace('~', "~0").replace('/', "~1"))
+    }
+}
+
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let path = if self.path.is_empty() {
+            "/"
+        } else {
+            &self.path
+        };
+        write!(
+            f,
+            "{}: {} (validator {}, expected {})",
+            path, self.message, self.validator, self.expected
+        )
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
 impl JsonValidator {
+    ///Creates a `JsonValidator::Custom` from a closure, e.g.
+    ///`JsonValidator::custom(move |x| x.get_string().map_or(false, |s| allowed.contains(&s)))`
+    pub fn custom<F>(f: F) -> Self
+    where
+        F: Fn(&JsonValue) -> bool + Send + Sync + 'static,
+    {
+        JsonValidator::Custom(Arc::new(f))
+    }
+
     ///Validates a JsonValue
     pub fn validate(&self, input: &JsonValue) -> bool {
+        self.validate_detailed(input).is_ok()
+    }
+
+    ///Validates a JsonValue, returning every failure found (rather than stopping at the first)
+    ///along with the JSON Pointer path of the value that caused it
+    pub fn validate_detailed(&self, input: &JsonValue) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+        self.collect_errors(input, "", &mut errors);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    ///Normalizes a JsonValue according to this validator and then validates the result.
+    ///Values are coerced to the types their validators expect, using the same coercions as
+    ///the `JsonValue::get_*` methods (so "42" becomes 42 under an Integer validator, and a
+    ///DateTime is rewritten in the standard format); missing `ObjectFields` fields with a
+    ///default are filled in; and unknown keys are removed where the additional keys policy is
+    ///`AdditionalKeys::Strip`. Values that cannot be coerced are left as they are, so they are
+    ///reported by the validation that follows
+    pub fn normalize(&self, input: JsonValue) -> Result<JsonValue, Vec<ValidationError>> {
+        let output = self.coerce(input);
+        self.validate_detailed(&output).map(|_| output)
+    }
+
+    fn coerce(&self, input: JsonValue) -> JsonValue {
         match self {
-            JsonValidator::RubberStamp => true,
-            JsonValidator::Null => input.is_null(),
+            JsonValidator::Boolean(_) => match input.get_boolean() {
+                Some(b) => JsonValue::Boolean(b),
+                None => input,
+            },
+            JsonValidator::Number(_) | JsonValidator::NumberRange { .. } => {
+                match input.get_number() {
+                    Some(n) => JsonValue::Number(n),
+                    None => input,
+                }
+            }
+            JsonValidator::String(_) | JsonValidator::StringOf(_) => match input.get_string() {
+                Some(s) => JsonValue::String(s),
+                None => input,
+            },
+            JsonValidator::Integer(_) | JsonValidator::IntegerRange { .. } => {
+                match input.get_integer() {
+                    Some(i) => JsonValue::Number(i as f64),
+                    None => input,
+                }
+            }
+            JsonValidator::DateTime(_) => match input.get_json_date() {
+                Some(d) => JsonValue::String(d.to_string()),
+                None => input,
+            },
+            JsonValidator::DateTimeWith(formats, _) => match formats
+                .iter()
+                .find_map(|format| input.get_json_date_with(*format))
+            {
+                Some(d) => d.to_json_with(formats[0]),
+                None => input,
+            },
+            JsonValidator::Date(_) => match input.get_json_date_only() {
+                Some(d) => JsonValue::String(d.to_string()),
+                None => input,
+            },
+            JsonValidator::Time(_) => match input.get_json_time() {
+                Some(t) => JsonValue::String(t.to_string()),
+                None => input,
+            },
+            JsonValidator::Duration(_) => match input.get_json_duration() {
+                Some(d) => JsonValue::String(d.to_string()),
+                None => input,
+            },
+            //Only arrays are rebuilt: turning an object into an array of its values would lose
+            //its keys, so any other value is left for validation to report
+            JsonValidator::Array(f) => match input {
+                JsonValue::Array(arr) => {
+                    JsonValue::Array(arr.into_iter().map(|x| f.coerce(x)).collect())
+                }
+                _ => input,
+            },
+            JsonValidator::ArrayOf(constraints) => match input {
+                JsonValue::Array(arr) => JsonValue::Array(
+                    arr.into_iter()
+                        .map(|x| constraints.items.coerce(x))
+                        .collect(),
+                ),
+                JsonValue::Object(_) => input,
+                _ if constraints.strict => input,
+                //A non-strict ArrayOf treats a scalar as a one-element array
+                scalar => JsonValue::Array(vec![constraints.items.coerce(scalar)]),
+            },
+            JsonValidator::Object(v) => match input {
+                JsonValue::Object(mut obj) => {
+                    for (key, validator) in v {
+                        if let Some(x) = obj.remove(key) {
+                            obj.insert(key.clone(), validator.coerce(x));
+                        }
+                    }
+                    JsonValue::Object(obj)
+                }
+                _ => input,
+            },
+            JsonValidator::ObjectFields {
+                fields,
+                patterns,
+                additional_keys,
+            } => match input {
+                JsonValue::Object(obj) => {
+                    let mut output = HashMap::with_capacity(obj.len());
+                    for (key, mut x) in obj {
+                        let mut known = false;
+                        for field in fields.iter().filter(|field| field.name == key) {
+                            known = true;
+                            x = field.validator.coerce(x);
+                        }
+                        for (pattern, validator) in patterns {
+                            if pattern.matches(&key) {
+                                known = true;
+                                x = validator.coerce(x);
+                            }
+                        }
+                        if !known {
+                            match additional_keys {
+                                AdditionalKeys::Strip => continue,
+                                AdditionalKeys::Validate(validator) => x = validator.coerce(x),
+                                AdditionalKeys::Allow | AdditionalKeys::Deny => {}
+                            }
+                        }
+                        output.insert(key, x);
+                    }
+                    for field in fields {
+                        if let (false, Some(default)) =
+                            (output.contains_key(&field.name), &field.default)
+                        {
+                            output.insert(
+                                field.name.clone(),
+                                field.validator.coerce(default.clone()),
+                            );
+                        }
+                    }
+                    JsonValue::Object(output)
+                }
+                _ => input,
+            },
+            JsonValidator::Or(v) | JsonValidator::ExactlyOne(v) => v
+                .iter()
+                .map(|x| x.coerce(input.clone()))
+                .find(|x| self.validate(x))
+                .unwrap_or(input),
+            JsonValidator::And(v) => v.iter().fold(input, |x, validator| validator.coerce(x)),
+            JsonValidator::Tuple(v) => match input {
+                JsonValue::Array(arr) if arr.len() == v.len() => JsonValue::Array(
+                    arr.into_iter()
+                        .zip(v)
+                        .map(|(x, validator)| validator.coerce(x))
+                        .collect(),
+                ),
+                _ => input,
+            },
+            JsonValidator::RubberStamp
+            | JsonValidator::Null
+            | JsonValidator::Not(_)
+            | JsonValidator::Const(_)
+            | JsonValidator::Enum(_)
+            | JsonValidator::Custom(_) => input,
+        }
+    }
+
+    ///Parses and validates a JSON document in a single pass. Each value is checked as soon as it
+    ///has been read (so, for example, a key denied by an `ObjectFields` validator is rejected
+    ///before its value is parsed), and parsing stops at the first violation, so that invalid
+    ///input is rejected without first being parsed in full. Unlike `JsonValue::parse`, the
+    ///input must be strictly RFC 8259-compliant. The default `ParseOptions` depth and size
+    ///limits apply
+    pub fn parse_and_validate(&self, input: &[u8]) -> Result<JsonValue, ParseValidateError> {
+        self.parse_and_validate_with(input, &ParseOptions::default())
+    }
+
+    ///Parses and validates a JSON document in a single pass, as `parse_and_validate` does,
+    ///within the options' depth and size limits. The options' dialect is ignored
+    pub fn parse_and_validate_with(
+        &self,
+        input: &[u8],
+        options: &ParseOptions,
+    ) -> Result<JsonValue, ParseValidateError> {
+        reader::JsonReader::parse_document(input, Some(self), options)
+    }
+
+    ///The elements `get_array` would return, each paired with its JSON Pointer path. A scalar
+    ///keeps its own path, since it is treated as an array containing only itself
+    fn array_elements<'a>(input: &'a JsonValue, path: &str) -> Vec<(String, &'a JsonValue)> {
+        match input {
+            JsonValue::Array(arr) => arr
+                .iter()
+                .enumerate()
+                .map(|(i, x)| (ValidationError::child_path(path, &i.to_string()), x))
+                .collect(),
+            JsonValue::Object(obj) => obj
+                .iter()
+                .map(|(key, x)| (ValidationError::child_path(path, key), x))
+                .collect(),
+            scalar => vec![(path.to_owned(), scalar)],
+        }
+    }
+
+    fn collect_errors(&self, input: &JsonValue, path: &str, errors: &mut Vec<ValidationError>) {
+        self.check(input, path, true, errors)
+    }
+
+    ///Collects the failures of this validator. When `deep` is false, the values inside arrays
+    ///and objects are not checked against their validators, leaving only the checks on the
+    ///container itself (its type, length, keys and so on)
+    fn check(&self, input: &JsonValue, path: &str, deep: bool, errors: &mut Vec<ValidationError>) {
+        match self {
+            JsonValidator::RubberStamp => {}
+            JsonValidator::Null => {
+                if !input.is_null() {
+                    errors.push(ValidationError::new(
+                        path,
+                        "Null",
+                        "null",
+                        format!("expected null, found {}", input.stringify()),
+                    ));
+                }
+            }
             JsonValidator::Boolean(f) => match input.get_boolean() {
-                None => false,
-                Some(b) => f(&b),
+                None => errors.push(ValidationError::new(
+                    path,
+                    "Boolean",
+                    "boolean",
+                    "value cannot be read as a boolean".to_owned(),
+                )),
+                Some(b) => {
+                    if !f(&b) {
+                        errors.push(ValidationError::new(
+                            path,
+                            "Boolean",
+                            "boolean",
+                            format!("boolean {} was rejected", b),
+                        ));
+                    }
+                }
             },
             JsonValidator::Number(f) => match input.get_number() {
-                None => false,
-                Some(n) => f(&n),
+                None => errors.push(ValidationError::new(
+                    path,
+                    "Number",
+                    "number",
+                    "value cannot be read as a number".to_owned(),
+                )),
+                Some(n) => {
+                    if !f(&n) {
+                        errors.push(ValidationError::new(
+                            path,
+                            "Number",
+                            "number",
+                            format!("number {} was rejected", n),
+                        ));
+                    }
+                }
             },
             JsonValidator::String(f) => match input.get_string() {
-                None => false,
-                Some(s) => f(&s),
+                None => errors.push(ValidationError::new(
+                    path,
+                    "String",
+                    "string",
+                    "value cannot be read as a string".to_owned(),
+                )),
+                Some(s) => {
+                    if !f(&s) {
+                        errors.push(ValidationError::new(
+                            path,
+                            "String",
+                            "string",
+                            format!(
+                                "string {} was rejected",
+                                JsonValue::stringify_actual_string(&s)
+                            ),
+                        ));
+                    }
+                }
             },
             JsonValidator::Integer(f) => match input.get_integer() {
-                None => false,
-                Some(i) => f(&i),
+                None => errors.push(ValidationError::new(
+                    path,
+                    "Integer",
+                    "integer",
+                    "value cannot be read as a non-negative integer".to_owned(),
+                )),
+                Some(i) => {
+                    if !f(&i) {
+                        errors.push(ValidationError::new(
+                            path,
+                            "Integer",
+                            "integer",
+                            format!("integer {} was rejected", i),
+                        ));
+                    }
+                }
+            },
+            JsonValidator::NumberRange { min, max } => match input.get_number() {
+                None => errors.push(ValidationError::new(
+                    path,
+                    "NumberRange",
+                    "number",
+                    "value cannot be read as a number".to_owned(),
+                )),
+                Some(n) => {
+                    if let Some(min) = min.filter(|min| n < *min) {
+                        errors.push(ValidationError::new(
+                            path,
+                            "NumberRange",
+                            "number",
+                            format!("number {} is less than the minimum {}", n, min),
+                        ));
+                    }
+                    if let Some(max) = max.filter(|max| n > *max) {
+                        errors.push(ValidationError::new(
+                            path,
+                            "NumberRange",
+                            "number",
+                            format!("number {} is greater than the maximum {}", n, max),
+                        ));
+                    }
+                }
+            },
+            JsonValidator::IntegerRange { min, max } => match input.get_integer() {
+                None => errors.push(ValidationError::new(
+                    path,
+                    "IntegerRange",
+                    "integer",
+                    "value cannot be read as a non-negative integer".to_owned(),
+                )),
+                Some(i) => {
+                    if let Some(min) = min.filter(|min| i < *min) {
+                        errors.push(ValidationError::new(
+                            path,
+                            "IntegerRange",
+                            "integer",
+                            format!("integer {} is less than the minimum {}", i, min),
+                        ));
+                    }
+                    if let Some(max) = max.filter(|max| i > *max) {
+                        errors.push(ValidationError::new(
+                            path,
+                            "IntegerRange",
+                            "integer",
+                            format!("integer {} is greater than the maximum {}", i, max),
+                        ));
+                    }
+                }
+            },
+            JsonValidator::StringOf(constraints) => match input.get_string() {
+                None => errors.push(ValidationError::new(
+                    path,
+                    "StringOf",
+                    "string",
+                    "value cannot be read as a string".to_owned(),
+                )),
+                Some(s) => {
+                    let len = s.chars().count();
+                    if let Some(min) = constraints.min_length.filter(|min| len < *min) {
+                        errors.push(ValidationError::new(
+                            path,
+                            "StringOf",
+                            "string",
+                            format!("expected at least {} characters, found {}", min, len),
+                        ));
+                    }
+                    if let Some(max) = constraints.max_length.filter(|max| len > *max) {
+                        errors.push(ValidationError::new(
+                            path,
+                            "StringOf",
+                            "string",
+                            format!("expected at most {} characters, found {}", max, len),
+                        ));
+                    }
+                    if let Some(regex) = constraints
+                        .pattern
+                        .as_ref()
+                        .filter(|regex| !regex.is_match(&s))
+                    {
+                        errors.push(ValidationError::new(
+                            path,
+                            "StringOf",
+                            "string",
+                            format!(
+                                "string {} does not match {:?}",
+                                JsonValue::stringify_actual_string(&s),
+                                regex.as_str()
+                            ),
+                        ));
+                    }
+                }
             },
             JsonValidator::DateTime(f) => match input.get_json_date() {
-                None => false,
-                Some(d) => f(&d),
+                None => errors.push(ValidationError::new(
+                    path,
+                    "DateTime",
+                    "datetime",
+                    "value cannot be read as a datetime".to_owned(),
+                )),
+                Some(d) => {
+                    if !f(&d) {
+                        errors.push(ValidationError::new(
+                            path,
+                            "DateTime",
+                            "datetime",
+                            format!("datetime {} was rejected", d),
+                        ));
+                    }
+                }
+            },
+            JsonValidator::DateTimeWith(formats, f) => match formats
+                .iter()
+                .find_map(|format| input.get_json_date_with(*format))
+            {
+                None => errors.push(ValidationError::new(
+                    path,
+                    "DateTimeWith",
+                    "datetime",
+                    format!("value cannot be read as a datetime in any of {:?}", formats),
+                )),
+                Some(d) => {
+                    if !f(&d) {
+                        errors.push(ValidationError::new(
+                            path,
+                            "DateTimeWith",
+                            "datetime",
+                            format!("datetime {} was rejected", d),
+                        ));
+                    }
+                }
+            },
+            JsonValidator::Date(f) => match input.get_json_date_only() {
+                None => errors.push(ValidationError::new(
+                    path,
+                    "Date",
+                    "date",
+                    "value cannot be read as a date".to_owned(),
+                )),
+                Some(d) => {
+                    if !f(&d) {
+                        errors.push(ValidationError::new(
+                            path,
+                            "Date",
+                            "date",
+                            format!("date {} was rejected", d),
+                        ));
+                    }
+                }
+            },
+            JsonValidator::Time(f) => match input.get_json_time() {
+                None => errors.push(ValidationError::new(
+                    path,
+                    "Time",
+                    "time",
+                    "value cannot be read as a time of day".to_owned(),
+                )),
+                Some(d) => {
+                    if !f(&d) {
+                        errors.push(ValidationError::new(
+                            path,
+                            "Time",
+                            "time",
+                            format!("time of day {} was rejected", d),
+                        ));
+                    }
+                }
+            },
+            JsonValidator::Duration(f) => match input.get_json_duration() {
+                None => errors.push(ValidationError::new(
+                    path,
+                    "Duration",
+                    "duration",
+                    "value cannot be read as a duration".to_owned(),
+                )),
+                Some(d) => {
+                    if !f(&d) {
+                        errors.push(ValidationError::new(
+                            path,
+                            "Duration",
+                            "duration",
+                            format!("duration {} was rejected", d),
+                        ));
+                    }
+                }
             },
             JsonValidator::Array(f) => {
-                input
-                    .get_array()
-                    .into_iter()
-                    .filter(|x| !f.validate(x))
-                    .count()
-                    == 0
+                if deep {
+                    for (child, x) in Self::array_elements(input, path) {
+                        f.collect_errors(x, &child, errors);
+                    }
+                }
+            }
+            JsonValidator::ArrayOf(constraints) => {
+                if constraints.strict && !matches!(input, JsonValue::Array(_)) {
+                    errors.push(ValidationError::new(
+                        path,
+                        "ArrayOf",
+                        "array",
+                        "value is not an array".to_owned(),
+                    ));
+                    return;
+                }
+                let elements = Self::array_elements(input, path);
+                if let Some(min) = constraints.min_items.filter(|min| elements.len() < *min) {
+                    errors.push(ValidationError::new(
+                        path,
+                        "ArrayOf",
+                        "array",
+                        format!("expected at least {} items, found {}", min, elements.len()),
+                    ));
+                }
+                if let Some(max) = constraints.max_items.filter(|max| elements.len() > *max) {
+                    errors.push(ValidationError::new(
+                        path,
+                        "ArrayOf",
+                        "array",
+                        format!("expected at most {} items, found {}", max, elements.len()),
+                    ));
+                }
+                if constraints.unique_items {
+                    for (i, (child, x)) in elements.iter().enumerate() {
+                        if elements[..i].iter().any(|(_, y)| x == y) {
+                            errors.push(ValidationError::new(
+                                child,
+                                "ArrayOf",
+                                "array",
+                                format!("duplicate item {}", x.stringify()),
+                            ));
+                        }
+                    }
+                }
+                if let Some(contains) = &constraints.contains {
+                    let found = elements
+                        .iter()
+                        .filter(|(_, x)| contains.validator.validate(x))
+                        .count();
+                    if found < contains.min || contains.max.is_some_and(|max| found > max) {
+                        let wanted = match contains.max {
+                            Some(max) => format!("between {} and {}", contains.min, max),
+                            None => format!("at least {}", contains.min),
+                        };
+                        errors.push(ValidationError::new(
+                            path,
+                            "ArrayOf",
+                            "array",
+                            format!("expected {} matching items, found {}", wanted, found),
+                        ));
+                    }
+                }
+                if deep {
+                    for (child, x) in &elements {
+                        constraints.items.collect_errors(x, child, errors);
+                    }
+                }
             }
             JsonValidator::Object(v) => {
                 let mut hm = HashMap::with_capacity(v.len());
-                for i in 0..v.len() {
-                    hm.insert(v[i].0.clone(), v[i].1.clone());
-                }
-                match input.get_object() {
-                    None => false,
-                    Some(tbt) => {
-                        if hm
-                            .keys()
-                            .into_iter()
-                            .filter(|x| !tbt.contains_key(x.to_owned()))
-                            .count()
-                            > 0
-                        {
-                            false
-                        } else {
-                            hm.into_iter()
-                                .map(|(x, y)| y.validate(tbt.get(&x).unwrap()))
-                                .filter(|z| !z)
-                                .count()
-                                == 0
+                for (key, validator) in v {
+                    hm.insert(key, validator);
+                }
+                match input {
+                    JsonValue::Object(tbt) => {
+                        let mut keys = hm.keys().collect::<Vec<_>>();
+                        keys.sort();
+                        for key in keys {
+                            let child = ValidationError::child_path(path, key);
+                            match tbt.get(key.as_str()) {
+                                None => errors.push(ValidationError::new(
+                                    &child,
+                                    "Object",
+                                    "object",
+                                    format!(
+                                        "missing required key {}",
+                                        JsonValue::stringify_actual_string(key)
+                                    ),
+                                )),
+                                Some(x) => {
+                                    if deep {
+                                        hm[key].collect_errors(x, &child, errors)
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    _ => errors.push(ValidationError::new(
+                        path,
+                        "Object",
+                        "object",
+                        "value is not an object".to_owned(),
+                    )),
+                }
+            }
+            JsonValidator::ObjectFields {
+                fields,
+                patterns,
+                additional_keys,
+            } => match input {
+                JsonValue::Object(tbt) => {
+                    for field in fields {
+                        let child = ValidationError::child_path(path, &field.name);
+                        match tbt.get(&field.name) {
+                            None => {
+                                if field.required {
+                                    errors.push(ValidationError::new(
+                                        &child,
+                                        "ObjectFields",
+                                        "object",
+                                        format!(
+                                            "missing required key {}",
+                                            JsonValue::stringify_actual_string(&field.name)
+                                        ),
+                                    ));
+                                }
+                            }
+                            Some(x) => {
+                                if deep {
+                                    field.validator.collect_errors(x, &child, errors)
+                                }
+                            }
                         }
                     }
+                    let mut keys = tbt.keys().collect::<Vec<_>>();
+                    keys.sort();
+                    for key in keys {
+                        let child = ValidationError::child_path(path, key);
+                        let mut known = fields.iter().any(|field| &field.name == key);
+                        for (pattern, validator) in patterns {
+                            if pattern.matches(key) {
+                                known = true;
+                                if deep {
+                                    validator.collect_errors(&tbt[key], &child, errors);
+                                }
+                            }
+                        }
+                        if !known {
+                            match additional_keys {
+                                AdditionalKeys::Allow | AdditionalKeys::Strip => {}
+                                AdditionalKeys::Deny => errors.push(ValidationError::new(
+                                    &child,
+                                    "ObjectFields",
+                                    "object",
+                                    format!(
+                                        "unexpected key {}",
+                                        JsonValue::stringify_actual_string(key)
+                                    ),
+                                )),
+                                AdditionalKeys::Validate(validator) => {
+                                    if deep {
+                                        validator.collect_errors(&tbt[key], &child, errors)
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+                _ => errors.push(ValidationError::new(
+                    path,
+                    "ObjectFields",
+                    "object",
+                    "value is not an object".to_owned(),
+                )),
+            },
+            JsonValidator::Or(v) => {
+                if !v.iter().any(|x| x.validate(input)) {
+                    errors.push(ValidationError::new(
+                        path,
+                        "Or",
+                        "any of the alternatives",
+                        format!("value matched none of the {} alternatives", v.len()),
+                    ));
+                }
+            }
+            JsonValidator::And(v) => {
+                for x in v {
+                    x.check(input, path, deep, errors);
+                }
+            }
+            JsonValidator::Not(f) => {
+                if f.validate(input) {
+                    errors.push(ValidationError::new(
+                        path,
+                        "Not",
+                        "anything but the negated validator",
+                        "value matched a validator it must not match".to_owned(),
+                    ));
+                }
+            }
+            JsonValidator::ExactlyOne(v) => {
+                let matched = v.iter().filter(|x| x.validate(input)).count();
+                if matched != 1 {
+                    errors.push(ValidationError::new(
+                        path,
+                        "ExactlyOne",
+                        "exactly one of the alternatives",
+                        format!("value matched {} of the {} alternatives", matched, v.len()),
+                    ));
+                }
+            }
+            JsonValidator::Const(value) => {
+                if input != value {
+                    errors.push(ValidationError::new(
+                        path,
+                        "Const",
+                        "constant",
+                        format!(
+                            "expected {}, found {}",
+                            value.stringify(),
+                            input.stringify()
+                        ),
+                    ));
+                }
+            }
+            JsonValidator::Enum(values) => {
+                if !values.contains(input) {
+                    errors.push(ValidationError::new(
+                        path,
+                        "Enum",
+                        "enumerated value",
+                        format!("{} is not one of the allowed values", input.stringify()),
+                    ));
+                }
+            }
+            JsonValidator::Tuple(v) => match input {
+                JsonValue::Array(arr) if arr.len() == v.len() => {
+                    if deep {
+                        for (i, (x, f)) in arr.iter().zip(v).enumerate() {
+                            f.collect_errors(
+                                x,
+                                &ValidationError::child_path(path, &i.to_string()),
+                                errors,
+                            );
+                        }
+                    }
+                }
+                JsonValue::Array(arr) => errors.push(ValidationError::new(
+                    path,
+                    "Tuple",
+                    "array",
+                    format!("expected {} elements, found {}", v.len(), arr.len()),
+                )),
+                _ => errors.push(ValidationError::new(
+                    path,
+                    "Tuple",
+                    "array",
+                    "value is not an array".to_owned(),
+                )),
+            },
+            JsonValidator::Custom(f) => {
+                if !f(input) {
+                    errors.push(ValidationError::new(
+                        path,
+                        "Custom",
+                        "custom",
+                        "value was rejected by a custom validator".to_owned(),
+                    ));
                 }
             }
-            JsonValidator::Or(v) => v.clone().into_iter().filter(|x| x.validate(input)).count() > 0,
         }
     }
 }
@@ -0,0 +1,194 @@
+use crate::reader::JsonReader;
+use crate::{JsonValue, ParseError, ParseOptions};
+use std::io::{BufRead, Read, Write};
+
+///An error found while reading a line of JSON Lines (NDJSON) input
+#[derive(Debug)]
+pub enum JsonLinesError {
+    ///The input could not be read. Reading always stops after this
+    Io(std::io::Error),
+
+    ///The line is not well-formed JSON. The offset is from the start of the line
+    Syntax(ParseError),
+}
+
+impl std::fmt::Display for JsonLinesError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JsonLinesError::Io(e) => write!(f, "{}", e),
+            JsonLinesError::Syntax(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for JsonLinesError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            JsonLinesError::Io(e) => Some(e),
+            JsonLinesError::Syntax(e) => Some(e),
+        }
+    }
+}
+
+///Reads newline-delimited JSON, yielding one value per line. Errors are paired with their line
+///number, counting from 1. Lines holding only whitespace are ignored.
+///
+///By default iteration ends after the first bad line has been reported; call `skip_errors` to
+///report each bad line and carry on with the next
+pub struct JsonLinesReader<R: BufRead> {
+    input: R,
+    options: ParseOptions,
+    line_no: usize,
+    skip_errors: bool,
+    done: bool,
+}
+
+impl<R: BufRead> JsonLinesReader<R> {
+    ///Reads with the default limits of `ParseOptions`
+    pub fn new(input: R) -> Self {
+        Self::with_options(input, &ParseOptions::default())
+    }
+
+    ///Reads with the given limits, which apply to each line. `max_size` does not count the line
+    ///break, and a longer line is reported without being held in memory. The dialect is ignored
+    pub fn with_options(input: R, options: &ParseOptions) -> Self {
+        Self {
+            input,
+            options: options.clone(),
+            line_no: 0,
+            skip_errors: false,
+            done: false,
+        }
+    }
+
+    ///Keeps reading after a line which is not well-formed JSON. I/O errors still end iteration
+    pub fn skip_errors(mut self) -> Self {
+        self.skip_errors = true;
+        self
+    }
+
+    ///The number of the last line read, counting from 1
+    pub fn line_no(&self) -> usize {
+        self.line_no
+    }
+
+    pub fn into_inner(self) -> R {
+        self.input
+    }
+
+    ///Discards the rest of the current line, up to and including its line break
+    fn skip_line(&mut self) -> std::io::Result<()> {
+        loop {
+            let buf = match self.input.fill_buf() {
+                Ok(buf) => buf,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            if buf.is_empty() {
+                return Ok(());
+            }
+            match buf.iter().position(|b| *b == b'\n') {
+                Some(i) => {
+                    self.input.consume(i + 1);
+                    return Ok(());
+                }
+                None => {
+                    let len = buf.len();
+                    self.input.consume(len);
+                }
+            }
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for JsonLinesReader<R> {
+    type Item = Result<JsonValue, (usize, JsonLinesError)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        //Room for the longest line allowed, a CR LF after it, and one byte more to tell that a
+        //line is too long
+        let limit = self
+            .options
+            .max_size
+            .map_or(u64::MAX, |max| (max as u64).saturating_add(3));
+        let mut line = vec![];
+        while !self.done {
+            line.clear();
+            match self.input.by_ref().take(limit).read_until(b'\n', &mut line) {
+                Ok(0) => self.done = true,
+                Ok(read) => {
+                    self.line_no += 1;
+                    if read as u64 == limit && line.last() != Some(&b'\n') {
+                        if let Err(e) = self.skip_line() {
+                            self.done = true;
+                            return Some(Err((self.line_no, JsonLinesError::Io(e))));
+                        }
+                        self.done = !self.skip_errors;
+                        let e = ParseError {
+                            offset: 0,
+                            message: "input is too large".to_owned(),
+                        };
+                        return Some(Err((self.line_no, JsonLinesError::Syntax(e))));
+                    }
+                    if line.iter().all(|b| matches!(b, 0x20 | 0x09 | 0x0A | 0x0D)) {
+                        continue;
+                    }
+                    let content = line.strip_suffix(b"\n").unwrap_or(&line);
+                    let content = content.strip_suffix(b"\r").unwrap_or(content);
+                    return match JsonReader::parse_unvalidated(content, &self.options) {
+                        Ok(value) => Some(Ok(value)),
+                        Err(e) => {
+                            self.done = !self.skip_errors;
+                            Some(Err((self.line_no, JsonLinesError::Syntax(e))))
+                        }
+                    };
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err((self.line_no + 1, JsonLinesError::Io(e))));
+                }
+            }
+        }
+        None
+    }
+}
+
+///Writes newline-delimited JSON: each value is written compactly with `stringify`, which escapes
+///any line breaks inside strings, followed by a single newline
+pub struct JsonLinesWriter<W: Write> {
+    output: W,
+}
+
+impl<W: Write> JsonLinesWriter<W> {
+    pub fn new(output: W) -> Self {
+        Self { output }
+    }
+
+    ///Writes one value as a line
+    pub fn write(&mut self, value: &JsonValue) -> std::io::Result<()> {
+        let mut line = value.stringify();
+        line.push('\n');
+        self.output.write_all(line.as_bytes())
+    }
+
+    ///Writes each value as a line
+    pub fn write_all<'v>(
+        &mut self,
+        values: impl IntoIterator<Item = &'v JsonValue>,
+    ) -> std::io::Result<()> {
+        values.into_iter().try_for_each(|value| self.write(value))
+    }
+
+    pub fn flush(&mut self) -> std::io::Result<()> {
+        self.output.flush()
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.output
+    }
+
+    pub fn into_inner(self) -> W {
+        self.output
+    }
+}
@@ -0,0 +1,313 @@
+use crate::base64;
+use crate::{DateFormat, JsonDate, JsonValue, ParseError, ParseOptions};
+use std::collections::HashMap;
+
+///The extension type of the MessagePack timestamp
+const EXT_TIMESTAMP: i8 = -1;
+
+impl JsonValue {
+    ///Encodes the value as MessagePack. Whole numbers are written as the smallest integer
+    ///format that holds them and other numbers as float 32 if that is exact, else float 64.
+    ///Object keys are sorted, so equal values always encode the same way
+    pub fn to_msgpack(&self) -> Vec<u8> {
+        let mut output = vec![];
+        write_value(self, &mut output);
+        output
+    }
+
+    ///Decodes a MessagePack value, with the default depth and size limits
+    pub fn from_msgpack(input: &[u8]) -> Result<JsonValue, ParseError> {
+        Self::from_msgpack_with(input, &ParseOptions::default())
+    }
+
+    ///Decodes a MessagePack value within the options' depth and size limits.
+    ///
+    ///Binary data becomes a base64 string and timestamps (extension type -1) become RFC 3339
+    ///strings, which `JsonValue::get_json_date` reads; other extension types are rejected.
+    ///Integer map keys become strings. Integers beyond 2^53 in magnitude may be rounded, since
+    ///a Number is an f64
+    pub fn from_msgpack_with(
+        input: &[u8],
+        options: &ParseOptions,
+    ) -> Result<JsonValue, ParseError> {
+        if !options.allows_size(input.len()) {
+            return Err(ParseError {
+                offset: 0,
+                message: "input is too large".to_owned(),
+            });
+        }
+        let mut reader = MsgpackReader {
+            input,
+            pos: 0,
+            options,
+        };
+        let value = reader.value(0)?;
+        if reader.pos < input.len() {
+            return Err(reader.error(reader.pos, "unexpected bytes after the value"));
+        }
+        Ok(value)
+    }
+}
+
+///Writes a length with the 8-bit (if `short` is Some), 16-bit or 32-bit format. Lengths beyond
+///u32 cannot be encoded; a JsonValue that large would not fit in memory twice anyway
+fn write_length(output: &mut Vec<u8>, len: usize, short: Option<u8>, medium: u8, long: u8) {
+    match (short, len) {
+        (Some(short), 0..=0xFF) => {
+            output.push(short);
+            output.push(len as u8);
+        }
+        (_, 0..=0xFFFF) => {
+            output.push(medium);
+            output.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        _ => {
+            output.push(long);
+            output.extend_from_slice(&(len as u32).to_be_bytes());
+        }
+    }
+}
+
+fn write_str(output: &mut Vec<u8>, str: &str) {
+    if str.len() < 32 {
+        output.push(0xA0 | str.len() as u8);
+    } else {
+        write_length(output, str.len(), Some(0xD9), 0xDA, 0xDB);
+    }
+    output.extend_from_slice(str.as_bytes());
+}
+
+fn write_value(value: &JsonValue, output: &mut Vec<u8>) {
+    match value {
+        JsonValue::Null => output.push(0xC0),
+        JsonValue::Boolean(false) => output.push(0xC2),
+        JsonValue::Boolean(true) => output.push(0xC3),
+        JsonValue::Number(n) => write_number(*n, output),
+        JsonValue::String(str) => write_str(output, str),
+        JsonValue::Array(arr) => {
+            if arr.len() < 16 {
+                output.push(0x90 | arr.len() as u8);
+            } else {
+                write_length(output, arr.len(), None, 0xDC, 0xDD);
+            }
+            for x in arr {
+                write_value(x, output);
+            }
+        }
+        JsonValue::Object(obj) => {
+            if obj.len() < 16 {
+                output.push(0x80 | obj.len() as u8);
+            } else {
+                write_length(output, obj.len(), None, 0xDE, 0xDF);
+            }
+            let mut entries = obj.iter().collect::<Vec<_>>();
+            entries.sort_by_key(|(key, _)| *key);
+            for (key, x) in entries {
+                write_str(output, key);
+                write_value(x, output);
+            }
+        }
+    }
+}
+
+fn write_number(n: f64, output: &mut Vec<u8>) {
+    let whole = n.fract() == 0.0 && !(n == 0.0 && n.is_sign_negative());
+    if whole && (0.0..18446744073709551616.0).contains(&n) {
+        match n as u64 {
+            u @ 0..=0x7F => output.push(u as u8),
+            u @ 0..=0xFF => output.extend_from_slice(&[0xCC, u as u8]),
+            u @ 0..=0xFFFF => {
+                output.push(0xCD);
+                output.extend_from_slice(&(u as u16).to_be_bytes());
+            }
+            u @ 0..=0xFFFF_FFFF => {
+                output.push(0xCE);
+                output.extend_from_slice(&(u as u32).to_be_bytes());
+            }
+            u => {
+                output.push(0xCF);
+                output.extend_from_slice(&u.to_be_bytes());
+            }
+        }
+    } else if whole && (-9223372036854775808.0..0.0).contains(&n) {
+        match n as i64 {
+            i @ -32..=-1 => output.push(i as u8),
+            i @ -0x80..=-1 => output.extend_from_slice(&[0xD0, i as u8]),
+            i @ -0x8000..=-1 => {
+                output.push(0xD1);
+                output.extend_from_slice(&(i as i16).to_be_bytes());
+            }
+            i @ -0x8000_0000..=-1 => {
+                output.push(0xD2);
+                output.extend_from_slice(&(i as i32).to_be_bytes());
+            }
+            i => {
+                output.push(0xD3);
+                output.extend_from_slice(&i.to_be_bytes());
+            }
+        }
+    } else if n.is_nan() || (n as f32) as f64 == n {
+        output.push(0xCA);
+        output.extend_from_slice(&(n as f32).to_bits().to_be_bytes());
+    } else {
+        output.push(0xCB);
+        output.extend_from_slice(&n.to_bits().to_be_bytes());
+    }
+}
+
+struct MsgpackReader<'a> {
+    input: &'a [u8],
+    pos: usize,
+    options: &'a ParseOptions,
+}
+
+impl<'a> MsgpackReader<'a> {
+    fn error(&self, offset: usize, message: &str) -> ParseError {
+        ParseError {
+            offset,
+            message: message.to_owned(),
+        }
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], ParseError> {
+        if n > self.input.len() - self.pos {
+            return Err(self.error(self.input.len(), "unexpected end of input"));
+        }
+        self.pos += n;
+        Ok(&self.input[self.pos - n..self.pos])
+    }
+
+    ///Reads a big-endian unsigned integer of `n` bytes
+    fn uint(&mut self, n: usize) -> Result<u64, ParseError> {
+        Ok(self.take(n)?.iter().fold(0, |x, b| x << 8 | *b as u64))
+    }
+
+    ///Reads a big-endian signed integer of `n` bytes
+    fn int(&mut self, n: usize) -> Result<i64, ParseError> {
+        let shift = 64 - 8 * n as u32;
+        Ok(((self.uint(n)? << shift) as i64) >> shift)
+    }
+
+    ///Reads a length of `n` bytes, which cannot exceed the bytes left since each byte or item
+    ///takes at least one
+    fn length(&mut self, offset: usize, n: usize) -> Result<usize, ParseError> {
+        match usize::try_from(self.uint(n)?) {
+            Ok(len) if len <= self.input.len() - self.pos => Ok(len),
+            _ => Err(self.error(offset, "length exceeds the input")),
+        }
+    }
+
+    fn str(&mut self, offset: usize, len: usize) -> Result<JsonValue, ParseError> {
+        let bytes = self.take(len)?;
+        std::str::from_utf8(bytes)
+            .map(|str| JsonValue::String(str.to_owned()))
+            .map_err(|_| self.error(offset, "string is not valid UTF-8"))
+    }
+
+    fn bin(&mut self, len: usize) -> Result<JsonValue, ParseError> {
+        let bytes = self.take(len)?;
+        Ok(JsonValue::String(base64::encode(
+            bytes,
+            base64::STANDARD,
+            true,
+        )))
+    }
+
+    fn array(&mut self, offset: usize, len: usize, depth: usize) -> Result<JsonValue, ParseError> {
+        if !self.options.allows_depth(depth) {
+            return Err(self.error(offset, "nesting is too deep"));
+        }
+        //The length comes from the input, so nothing is allocated for it up front
+        let mut arr = vec![];
+        for _ in 0..len {
+            arr.push(self.value(depth + 1)?);
+        }
+        Ok(JsonValue::Array(arr))
+    }
+
+    fn map(&mut self, offset: usize, len: usize, depth: usize) -> Result<JsonValue, ParseError> {
+        if !self.options.allows_depth(depth) {
+            return Err(self.error(offset, "nesting is too deep"));
+        }
+        let mut obj = HashMap::new();
+        for _ in 0..len {
+            let key_offset = self.pos;
+            let key = match self.value(depth + 1)? {
+                JsonValue::String(key) => key,
+                JsonValue::Number(n) if n.fract() == 0.0 => n.to_string(),
+                _ => return Err(self.error(key_offset, "map keys must be strings or integers")),
+            };
+            let value = self.value(depth + 1)?;
+            if obj.insert(key, value).is_some() {
+                return Err(self.error(key_offset, "duplicate map key"));
+            }
+        }
+        Ok(JsonValue::Object(obj))
+    }
+
+    ///Reads an extension value. Only the timestamp type is supported
+    fn ext(&mut self, offset: usize, len: usize) -> Result<JsonValue, ParseError> {
+        let ext_type = self.take(1)?[0] as i8;
+        let data = self.take(len)?;
+        if ext_type != EXT_TIMESTAMP {
+            return Err(self.error(offset, &format!("unsupported extension type {}", ext_type)));
+        }
+        let be = |bytes: &[u8]| bytes.iter().fold(0_u64, |x, b| x << 8 | *b as u64);
+        let (seconds, nanos) = match data.len() {
+            4 => (be(data) as i128, 0),
+            8 => {
+                let n = be(data);
+                ((n & 0x3_FFFF_FFFF) as i128, (n >> 34) as i128)
+            }
+            12 => (be(&data[4..]) as i64 as i128, be(&data[..4]) as i128),
+            _ => return Err(self.error(offset, "timestamps must be 4, 8 or 12 bytes")),
+        };
+        if nanos >= 1_000_000_000 {
+            return Err(self.error(offset, "timestamp nanoseconds are out of range"));
+        }
+        JsonDate::from_unix_timestamp_nanos(seconds * 1_000_000_000 + nanos)
+            .map(|date| date.to_json_with(DateFormat::Rfc3339))
+            .map_err(|_| self.error(offset, "timestamp is out of range"))
+    }
+
+    fn value(&mut self, depth: usize) -> Result<JsonValue, ParseError> {
+        let offset = self.pos;
+        let format = self.take(1)?[0];
+        Ok(match format {
+            0x00..=0x7F => JsonValue::Number(format as f64),
+            0x80..=0x8F => return self.map(offset, (format & 0x0F) as usize, depth),
+            0x90..=0x9F => return self.array(offset, (format & 0x0F) as usize, depth),
+            0xA0..=0xBF => return self.str(offset, (format & 0x1F) as usize),
+            0xC0 => JsonValue::Null,
+            0xC2 => JsonValue::Boolean(false),
+            0xC3 => JsonValue::Boolean(true),
+            0xC4..=0xC6 => {
+                let len = self.length(offset, 1 << (format - 0xC4))?;
+                return self.bin(len);
+            }
+            0xC7..=0xC9 => {
+                let len = self.length(offset, 1 << (format - 0xC7))?;
+                return self.ext(offset, len);
+            }
+            0xCA => JsonValue::Number(f32::from_bits(self.uint(4)? as u32) as f64),
+            0xCB => JsonValue::Number(f64::from_bits(self.uint(8)?)),
+            0xCC..=0xCF => JsonValue::Number(self.uint(1 << (format - 0xCC))? as f64),
+            0xD0..=0xD3 => JsonValue::Number(self.int(1 << (format - 0xD0))? as f64),
+            0xD4..=0xD8 => return self.ext(offset, 1 << (format - 0xD4)),
+            0xD9..=0xDB => {
+                let len = self.length(offset, 1 << (format - 0xD9))?;
+                return self.str(offset, len);
+            }
+            0xDC | 0xDD => {
+                let len = self.length(offset, 2 << (format - 0xDC))?;
+                return self.array(offset, len, depth);
+            }
+            0xDE | 0xDF => {
+                let len = self.length(offset, 2 << (format - 0xDE))?;
+                return self.map(offset, len, depth);
+            }
+            0xE0..=0xFF => JsonValue::Number(format as i8 as f64),
+            0xC1 => return Err(self.error(offset, "0xC1 is never used")),
+        })
+    }
+}
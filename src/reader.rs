@@ -0,0 +1,697 @@
+use crate::{AdditionalKeys, JsonValidator, JsonValue, ParseOptions, ValidationError};
+use std::collections::HashMap;
+
+///An error found while parsing malformed JSON, or a malformed CBOR or MessagePack encoding
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    ///The byte offset within the input at which the problem was found
+    pub offset: usize,
+
+    ///A human-readable description of the problem
+    pub message: String,
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at byte {}", self.message, self.offset)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+///An error returned by `JsonValidator::parse_and_validate`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseValidateError {
+    ///The input is not well-formed JSON
+    Syntax(ParseError),
+
+    ///The input violates the validator. Since parsing stops at the first violation, only that
+    ///violation is reported
+    Invalid {
+        ///The byte offset within the input of the value, key or array element that failed
+        offset: usize,
+
+        ///The failure itself, as `JsonValidator::validate_detailed` would have reported it
+        error: ValidationError,
+    },
+}
+
+impl std::fmt::Display for ParseValidateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseValidateError::Syntax(e) => write!(f, "{}", e),
+            ParseValidateError::Invalid { offset, error } => {
+                write!(f, "{} at byte {}", error, offset)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseValidateError {}
+
+impl From<ParseError> for ParseValidateError {
+    fn from(e: ParseError) -> Self {
+        ParseValidateError::Syntax(e)
+    }
+}
+
+///How the values in the input to `JsonValue::parse_stream` are delimited
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StreamFormat {
+    ///Values separated by at least one whitespace character, e.g. `1 [2] {"a": 3}`
+    WhitespaceSeparated,
+
+    ///Values written back to back, optionally with whitespace between them, e.g. `{"a":1}{"b":2}`.
+    ///Adjacent numbers must still be separated by whitespace, since `12` is a single number
+    Concatenated,
+
+    ///An RFC 7464 JSON text sequence, in which each value is preceded by a record separator
+    ///(0x1E) and usually followed by a newline
+    RecordSeparated,
+}
+
+///The iterator returned by `JsonValue::parse_stream`
+pub(crate) struct JsonStream<'a> {
+    reader: JsonReader<'a>,
+    format: StreamFormat,
+    done: bool,
+}
+
+impl<'a> JsonStream<'a> {
+    pub(crate) fn new(input: &'a [u8], format: StreamFormat, options: &ParseOptions) -> Self {
+        Self {
+            reader: JsonReader::new(input, options),
+            format,
+            done: false,
+        }
+    }
+
+    ///Reads the next value of a whitespace-separated or concatenated stream. Since there is no
+    ///way to tell where the next value starts after malformed input, the stream ends at the
+    ///first error
+    fn next_value(&mut self) -> Option<Result<JsonValue, ParseError>> {
+        let reader = &mut self.reader;
+        let separated = reader.pos == 0 || matches!(reader.peek(), Some(0x20 | 0x09 | 0x0A | 0x0D));
+        reader.skip_whitespace();
+        reader.peek()?;
+        if self.format == StreamFormat::WhitespaceSeparated && !separated {
+            self.done = true;
+            return Some(Err(ParseError {
+                offset: reader.pos,
+                message: "expected whitespace between values".to_owned(),
+            }));
+        }
+        let result = match reader.parse_value(&[]) {
+            Ok(value) => Ok(value),
+            Err(ParseValidateError::Syntax(e)) => Err(e),
+            Err(ParseValidateError::Invalid { .. }) => unreachable!("no validator was given"),
+        };
+        self.done = result.is_err();
+        Some(result)
+    }
+
+    ///Reads the next record of an RFC 7464 sequence. A malformed record is reported and then
+    ///skipped, as the RFC recommends
+    fn next_record(&mut self) -> Option<Result<JsonValue, ParseError>> {
+        let input = self.reader.input;
+        loop {
+            let start = self.reader.pos;
+            if start >= input.len() {
+                return None;
+            }
+            let end = input[start + 1..]
+                .iter()
+                .position(|b| *b == 0x1E)
+                .map_or(input.len(), |i| start + 1 + i);
+            self.reader.pos = end;
+            if input[start] != 0x1E {
+                return Some(Err(ParseError {
+                    offset: start,
+                    message: "expected a record separator".to_owned(),
+                }));
+            }
+            let record = &input[start + 1..end];
+            if record.is_empty() {
+                continue;
+            }
+            let result = JsonReader::parse_unvalidated(record, &self.reader.options).map_err(|e| {
+                ParseError {
+                    offset: e.offset + start + 1,
+                    message: e.message,
+                }
+            });
+            //A number or literal which is not followed by whitespace may have been cut short
+            let truncated = matches!(
+                result,
+                Ok(JsonValue::Null | JsonValue::Boolean(_) | JsonValue::Number(_))
+            ) && !matches!(record.last(), Some(0x20 | 0x09 | 0x0A | 0x0D));
+            if truncated {
+                return Some(Err(ParseError {
+                    offset: end,
+                    message: "value may have been truncated".to_owned(),
+                }));
+            }
+            return Some(result);
+        }
+    }
+}
+
+impl Iterator for JsonStream<'_> {
+    type Item = Result<JsonValue, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let reader = &self.reader;
+        if reader.pos == 0 && !reader.options.allows_size(reader.input.len()) {
+            self.done = true;
+            return Some(Err(ParseError {
+                offset: 0,
+                message: "input is too large".to_owned(),
+            }));
+        }
+        match self.format {
+            StreamFormat::WhitespaceSeparated | StreamFormat::Concatenated => self.next_value(),
+            StreamFormat::RecordSeparated => self.next_record(),
+        }
+    }
+}
+
+///A strict RFC 8259 parser that works directly on the input bytes, so that it always knows
+///the offset it has reached. While parsing it checks each value against the validators that
+///apply to it, descending into arrays and objects so that a violation is found as soon as the
+///offending value has been read. Only the depth and size limits of its options apply
+pub(crate) struct JsonReader<'a> {
+    input: &'a [u8],
+    pos: usize,
+    path: Vec<String>,
+    options: ParseOptions,
+    depth: usize,
+}
+
+impl<'a> JsonReader<'a> {
+    pub(crate) fn new(input: &'a [u8], options: &ParseOptions) -> Self {
+        Self {
+            input,
+            pos: 0,
+            path: vec![],
+            options: options.clone(),
+            depth: 0,
+        }
+    }
+
+    ///Parses a complete document, which must hold exactly one value
+    pub(crate) fn parse_document(
+        input: &'a [u8],
+        validator: Option<&JsonValidator>,
+        options: &ParseOptions,
+    ) -> Result<JsonValue, ParseValidateError> {
+        let mut reader = Self::new(input, options);
+        if !options.allows_size(input.len()) {
+            return Err(reader.syntax(0, "input is too large"));
+        }
+        let validators = validator.into_iter().collect::<Vec<_>>();
+        let value = reader.parse_value(&validators)?;
+        reader.skip_whitespace();
+        if reader.pos < input.len() {
+            return Err(reader.syntax(reader.pos, "unexpected characters after the value"));
+        }
+        Ok(value)
+    }
+
+    ///Parses a complete document without validating it
+    pub(crate) fn parse_unvalidated(
+        input: &'a [u8],
+        options: &ParseOptions,
+    ) -> Result<JsonValue, ParseError> {
+        match Self::parse_document(input, None, options) {
+            Ok(value) => Ok(value),
+            Err(ParseValidateError::Syntax(e)) => Err(e),
+            Err(ParseValidateError::Invalid { .. }) => unreachable!("no validator was given"),
+        }
+    }
+
+    fn syntax(&self, offset: usize, message: &str) -> ParseValidateError {
+        ParseValidateError::Syntax(ParseError {
+            offset,
+            message: message.to_owned(),
+        })
+    }
+
+    fn pointer(&self) -> String {
+        self.path.iter().fold(String::new(), |path, token| {
+            ValidationError::child_path(&path, token)
+        })
+    }
+
+    fn invalid(
+        &self,
+        offset: usize,
+        path: &str,
+        validator: &'static str,
+        expected: &'static str,
+        message: String,
+    ) -> ParseValidateError {
+        ParseValidateError::Invalid {
+            offset,
+            error: ValidationError::new(path, validator, expected, message),
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.input.get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(0x20 | 0x09 | 0x0A | 0x0D)) {
+            self.pos += 1;
+        }
+    }
+
+    ///Expands `And` validators into their members, which all apply to the same value, and
+    ///drops `RubberStamp` validators, which never fail
+    fn flatten<'v>(validators: &[&'v JsonValidator], output: &mut Vec<&'v JsonValidator>) {
+        for validator in validators {
+            match validator {
+                JsonValidator::RubberStamp => {}
+                JsonValidator::And(members) => {
+                    Self::flatten(&members.iter().collect::<Vec<_>>(), output)
+                }
+                other => output.push(other),
+            }
+        }
+    }
+
+    ///Returns TRUE if the validator's checks on the values inside this container are made as
+    ///each of those values is parsed, rather than once the whole container has been read
+    fn descends(validator: &JsonValidator, value: &JsonValue) -> bool {
+        matches!(
+            (validator, value),
+            (
+                JsonValidator::Object(_) | JsonValidator::ObjectFields { .. },
+                JsonValue::Object(_)
+            ) | (
+                JsonValidator::Array(_) | JsonValidator::ArrayOf(_),
+                JsonValue::Array(_) | JsonValue::Object(_)
+            ) | (JsonValidator::Tuple(_), JsonValue::Array(_))
+        )
+    }
+
+    pub(crate) fn parse_value(
+        &mut self,
+        validators: &[&JsonValidator],
+    ) -> Result<JsonValue, ParseValidateError> {
+        self.skip_whitespace();
+        let start = self.pos;
+        let mut flat = vec![];
+        Self::flatten(validators, &mut flat);
+        let value = match self.peek() {
+            Some(b'{' | b'[') if !self.options.allows_depth(self.depth) => {
+                return Err(self.syntax(start, "nesting is too deep"));
+            }
+            Some(b'{') => {
+                self.check_container(&flat, start, true)?;
+                self.depth += 1;
+                let value = self.parse_object(&flat)?;
+                self.depth -= 1;
+                value
+            }
+            Some(b'[') => {
+                self.check_container(&flat, start, false)?;
+                self.depth += 1;
+                let value = self.parse_array(&flat)?;
+                self.depth -= 1;
+                value
+            }
+            Some(b'"') => JsonValue::String(self.parse_string()?),
+            Some(b't') => self.parse_literal("true", JsonValue::Boolean(true))?,
+            Some(b'f') => self.parse_literal("false", JsonValue::Boolean(false))?,
+            Some(b'n') => self.parse_literal("null", JsonValue::Null)?,
+            Some(b'-' | b'0'..=b'9') => self.parse_number()?,
+            Some(_) => return Err(self.syntax(start, "unexpected character")),
+            None => return Err(self.syntax(start, "unexpected end of input")),
+        };
+        if !flat.is_empty() {
+            let path = self.pointer();
+            for validator in flat {
+                let mut errors = vec![];
+                validator.check(
+                    &value,
+                    &path,
+                    !Self::descends(validator, &value),
+                    &mut errors,
+                );
+                if let Some(error) = errors.into_iter().next() {
+                    return Err(ParseValidateError::Invalid {
+                        offset: start,
+                        error,
+                    });
+                }
+            }
+        }
+        Ok(value)
+    }
+
+    ///Fails as soon as an array or object is opened where a validator can only accept the other
+    fn check_container(
+        &self,
+        validators: &[&JsonValidator],
+        start: usize,
+        is_object: bool,
+    ) -> Result<(), ParseValidateError> {
+        for validator in validators {
+            let (name, expected) = match (validator, is_object) {
+                (JsonValidator::Object(_), false) => ("Object", "object"),
+                (JsonValidator::ObjectFields { .. }, false) => ("ObjectFields", "object"),
+                (JsonValidator::ArrayOf(c), true) if c.strict => ("ArrayOf", "array"),
+                (JsonValidator::Tuple(_), true) => ("Tuple", "array"),
+                _ => continue,
+            };
+            return Err(self.invalid(
+                start,
+                &self.pointer(),
+                name,
+                expected,
+                format!("value is not an {}", expected),
+            ));
+        }
+        Ok(())
+    }
+
+    fn key_validators<'v>(
+        &self,
+        validators: &[&'v JsonValidator],
+        key: &str,
+        key_start: usize,
+    ) -> Result<Vec<&'v JsonValidator>, ParseValidateError> {
+        let mut output = vec![];
+        for validator in validators {
+            match validator {
+                JsonValidator::Object(v) => {
+                    //JsonValidator::Object lets a later entry for the same key win
+                    if let Some((_, x)) = v.iter().rev().find(|(name, _)| name == key) {
+                        output.push(x);
+                    }
+                }
+                JsonValidator::ObjectFields {
+                    fields,
+                    patterns,
+                    additional_keys,
+                } => {
+                    let mut known = false;
+                    for field in fields.iter().filter(|field| field.name == key) {
+                        known = true;
+                        output.push(&field.validator);
+                    }
+                    for (pattern, x) in patterns {
+                        if pattern.matches(key) {
+                            known = true;
+                            output.push(x);
+                        }
+                    }
+                    if !known {
+                        match additional_keys {
+                            AdditionalKeys::Allow | AdditionalKeys::Strip => {}
+                            AdditionalKeys::Deny => {
+                                return Err(self.invalid(
+                                    key_start,
+                                    &ValidationError::child_path(&self.pointer(), key),
+                                    "ObjectFields",
+                                    "object",
+                                    format!(
+                                        "unexpected key {}",
+                                        JsonValue::stringify_actual_string(key)
+                                    ),
+                                ));
+                            }
+                            AdditionalKeys::Validate(x) => output.push(x),
+                        }
+                    }
+                }
+                JsonValidator::Array(f) => output.push(f),
+                JsonValidator::ArrayOf(c) => output.push(&c.items),
+                _ => {}
+            }
+        }
+        Ok(output)
+    }
+
+    fn element_validators<'v>(
+        &self,
+        validators: &[&'v JsonValidator],
+        index: usize,
+        element_start: usize,
+    ) -> Result<Vec<&'v JsonValidator>, ParseValidateError> {
+        let mut output = vec![];
+        for validator in validators {
+            match validator {
+                JsonValidator::Array(f) => output.push(&**f),
+                JsonValidator::ArrayOf(c) => match c.max_items {
+                    Some(max) if index >= max => {
+                        return Err(self.invalid(
+                            element_start,
+                            &self.pointer(),
+                            "ArrayOf",
+                            "array",
+                            format!("expected at most {} items, found more", max),
+                        ));
+                    }
+                    _ => output.push(&c.items),
+                },
+                JsonValidator::Tuple(v) => match v.get(index) {
+                    Some(x) => output.push(x),
+                    None => {
+                        return Err(self.invalid(
+                            element_start,
+                            &self.pointer(),
+                            "Tuple",
+                            "array",
+                            format!("expected {} elements, found more", v.len()),
+                        ));
+                    }
+                },
+                _ => {}
+            }
+        }
+        Ok(output)
+    }
+
+    fn parse_object(
+        &mut self,
+        validators: &[&JsonValidator],
+    ) -> Result<JsonValue, ParseValidateError> {
+        self.pos += 1;
+        let mut obj = HashMap::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+            return Ok(JsonValue::Object(obj));
+        }
+        loop {
+            self.skip_whitespace();
+            let key_start = self.pos;
+            if self.peek() != Some(b'"') {
+                return Err(self.syntax(key_start, "expected a string key"));
+            }
+            let key = self.parse_string()?;
+            if obj.contains_key(&key) {
+                return Err(self.syntax(key_start, "duplicate key"));
+            }
+            self.skip_whitespace();
+            if self.peek() != Some(b':') {
+                return Err(self.syntax(self.pos, "expected ':'"));
+            }
+            self.pos += 1;
+            let children = self.key_validators(validators, &key, key_start)?;
+            self.path.push(key);
+            let value = self.parse_value(&children)?;
+            let key = self.path.pop().unwrap();
+            obj.insert(key, value);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b'}') => {
+                    self.pos += 1;
+                    return Ok(JsonValue::Object(obj));
+                }
+                _ => return Err(self.syntax(self.pos, "expected ',' or '}'")),
+            }
+        }
+    }
+
+    fn parse_array(
+        &mut self,
+        validators: &[&JsonValidator],
+    ) -> Result<JsonValue, ParseValidateError> {
+        self.pos += 1;
+        let mut arr: Vec<JsonValue> = vec![];
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.pos += 1;
+            return Ok(JsonValue::Array(arr));
+        }
+        loop {
+            self.skip_whitespace();
+            let element_start = self.pos;
+            let children = self.element_validators(validators, arr.len(), element_start)?;
+            self.path.push(arr.len().to_string());
+            let value = self.parse_value(&children)?;
+            self.path.pop();
+            let unique = validators
+                .iter()
+                .any(|v| matches!(v, JsonValidator::ArrayOf(c) if c.unique_items));
+            if unique && arr.contains(&value) {
+                return Err(self.invalid(
+                    element_start,
+                    &ValidationError::child_path(&self.pointer(), &arr.len().to_string()),
+                    "ArrayOf",
+                    "array",
+                    format!("duplicate item {}", value.stringify()),
+                ));
+            }
+            arr.push(value);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b']') => {
+                    self.pos += 1;
+                    return Ok(JsonValue::Array(arr));
+                }
+                _ => return Err(self.syntax(self.pos, "expected ',' or ']'")),
+            }
+        }
+    }
+
+    fn parse_literal(
+        &mut self,
+        literal: &str,
+        value: JsonValue,
+    ) -> Result<JsonValue, ParseValidateError> {
+        if self.input[self.pos..].starts_with(literal.as_bytes()) {
+            self.pos += literal.len();
+            Ok(value)
+        } else {
+            Err(self.syntax(self.pos, "invalid literal"))
+        }
+    }
+
+    fn skip_digits(&mut self) -> usize {
+        let start = self.pos;
+        while matches!(self.peek(), Some(b'0'..=b'9')) {
+            self.pos += 1;
+        }
+        self.pos - start
+    }
+
+    fn parse_number(&mut self) -> Result<JsonValue, ParseValidateError> {
+        let start = self.pos;
+        if self.peek() == Some(b'-') {
+            self.pos += 1;
+        }
+        match self.peek() {
+            Some(b'0') => self.pos += 1,
+            Some(b'1'..=b'9') => {
+                self.skip_digits();
+            }
+            _ => return Err(self.syntax(self.pos, "expected a digit")),
+        }
+        if self.peek() == Some(b'.') {
+            self.pos += 1;
+            if self.skip_digits() == 0 {
+                return Err(self.syntax(self.pos, "expected a digit after the decimal point"));
+            }
+        }
+        if matches!(self.peek(), Some(b'e' | b'E')) {
+            self.pos += 1;
+            if matches!(self.peek(), Some(b'+' | b'-')) {
+                self.pos += 1;
+            }
+            if self.skip_digits() == 0 {
+                return Err(self.syntax(self.pos, "expected a digit in the exponent"));
+            }
+        }
+        //The slice holds only ASCII digits, signs, '.' and 'e', so both conversions succeed
+        let text = std::str::from_utf8(&self.input[start..self.pos]).unwrap();
+        Ok(JsonValue::Number(text.parse::<f64>().unwrap()))
+    }
+
+    fn parse_hex4(&mut self) -> Result<u32, ParseError> {
+        let mut val = 0_u32;
+        for _ in 0..4 {
+            match self.peek().and_then(|b| (b as char).to_digit(16)) {
+                Some(d) => {
+                    val = (val << 4) | d;
+                    self.pos += 1;
+                }
+                None => {
+                    return Err(ParseError {
+                        offset: self.pos,
+                        message: "expected a hexadecimal digit".to_owned(),
+                    });
+                }
+            }
+        }
+        Ok(val)
+    }
+
+    pub(crate) fn parse_string(&mut self) -> Result<String, ParseError> {
+        let start = self.pos;
+        let error = |offset: usize, message: &str| ParseError {
+            offset,
+            message: message.to_owned(),
+        };
+        self.pos += 1;
+        let mut bytes = vec![];
+        loop {
+            let b = match self.peek() {
+                None => return Err(error(start, "unterminated string")),
+                Some(b) => b,
+            };
+            self.pos += 1;
+            match b {
+                b'"' => break,
+                b'\\' => {
+                    let escape_start = self.pos - 1;
+                    let c = match self.peek() {
+                        Some(b'"') => '"',
+                        Some(b'\\') => '\\',
+                        Some(b'/') => '/',
+                        Some(b'b') => '\x08',
+                        Some(b'f') => '\x0C',
+                        Some(b'n') => '\n',
+                        Some(b'r') => '\r',
+                        Some(b't') => '\t',
+                        Some(b'u') => {
+                            self.pos += 1;
+                            let mut code = self.parse_hex4()?;
+                            if (0xD800..0xDC00).contains(&code) {
+                                if !self.input[self.pos..].starts_with(b"\\u") {
+                                    return Err(error(escape_start, "unpaired surrogate"));
+                                }
+                                self.pos += 2;
+                                let low = self.parse_hex4()?;
+                                if !(0xDC00..0xE000).contains(&low) {
+                                    return Err(error(escape_start, "unpaired surrogate"));
+                                }
+                                code = 0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00);
+                            }
+                            self.pos -= 1;
+                            match char::from_u32(code) {
+                                Some(c) => c,
+                                None => return Err(error(escape_start, "unpaired surrogate")),
+                            }
+                        }
+                        _ => return Err(error(escape_start, "invalid escape")),
+                    };
+                    self.pos += 1;
+                    let mut buffer = [0_u8; 4];
+                    bytes.extend_from_slice(c.encode_utf8(&mut buffer).as_bytes());
+                }
+                0x00..=0x1F => return Err(error(self.pos - 1, "unescaped control character")),
+                b => bytes.push(b),
+            }
+        }
+        String::from_utf8(bytes).map_err(|_| error(start, "string is not valid UTF-8"))
+    }
+}
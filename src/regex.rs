@@ -0,0 +1,629 @@
+//!A small regular expression engine covering the ECMA-262 subset that JSON Schema `pattern`
+//!keywords use in practice, so that Jasper can stay dependency-free.
+//!
+//!Patterns compile to a Thompson NFA that is simulated one input character at a time, so
+//!matching takes time proportional to the input length times the pattern size and never
+//!recurses on the input. Patterns and instance data may therefore both be untrusted
+
+use std::collections::HashMap;
+
+#[derive(Clone, Debug)]
+enum ClassItem {
+    Range(char, char),
+    Digit(bool),
+    Word(bool),
+    Space(bool),
+}
+
+impl ClassItem {
+    fn matches(&self, c: char) -> bool {
+        match self {
+            ClassItem::Range(lo, hi) => *lo <= c && c <= *hi,
+            ClassItem::Digit(negated) => c.is_ascii_digit() != *negated,
+            ClassItem::Word(negated) => Regex::is_word(c) != *negated,
+            ClassItem::Space(negated) => c.is_whitespace() != *negated,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Node {
+    Empty,
+    Char(char),
+    Any,
+    Class(Vec<ClassItem>, bool),
+    Start,
+    End,
+    WordBoundary(bool),
+    Concat(Vec<Node>),
+    Alternate(Vec<Node>),
+    Repeat {
+        node: Box<Node>,
+        min: u32,
+        max: Option<u32>,
+    },
+    LookAhead(Box<Node>, bool),
+}
+
+///The deepest group nesting a pattern may use
+const MAX_NESTING: usize = 128;
+
+///The most instructions a compiled pattern may have, which bounds counted repetitions such
+///as `(a{1000}){1000}`
+const MAX_INSTRUCTIONS: usize = 100_000;
+
+///An instruction of the compiled NFA
+#[derive(Clone, Debug)]
+enum Inst {
+    Char(char),
+    Any,
+    Class(Vec<ClassItem>, bool),
+    Start,
+    End,
+    WordBoundary(bool),
+    ///Succeeds if the program at this index matches (or, when negated, does not match) here
+    LookAhead(usize, bool),
+    Split(usize, usize),
+    Jump(usize),
+    Match,
+}
+
+///A compiled regular expression. Matching is unanchored (as with JavaScript's `RegExp.test`),
+///so use `^` and `$` to match a whole string
+#[derive(Clone, Debug)]
+pub struct Regex {
+    source: String,
+    ///The main program comes first, followed by one program per lookahead
+    programs: Vec<Vec<Inst>>,
+}
+
+struct RegexParser<'a> {
+    chars: Vec<char>,
+    pos: usize,
+    depth: usize,
+    source: &'a str,
+}
+
+impl<'a> RegexParser<'a> {
+    fn error<T>(&self, message: &str) -> Result<T, String> {
+        Err(format!(
+            "invalid pattern {:?} at offset {}: {}",
+            self.source, self.pos, message
+        ))
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn parse_alternation(&mut self) -> Result<Node, String> {
+        let mut alternatives = vec![self.parse_concat()?];
+        while self.eat('|') {
+            alternatives.push(self.parse_concat()?);
+        }
+        if alternatives.len() == 1 {
+            Ok(alternatives.pop().unwrap())
+        } else {
+            Ok(Node::Alternate(alternatives))
+        }
+    }
+
+    fn parse_concat(&mut self) -> Result<Node, String> {
+        let mut nodes = vec![];
+        while let Some(c) = self.peek() {
+            if c == '|' || c == ')' {
+                break;
+            }
+            let atom = self.parse_atom()?;
+            nodes.push(self.parse_quantifier(atom)?);
+        }
+        if nodes.len() == 1 {
+            Ok(nodes.pop().unwrap())
+        } else if nodes.is_empty() {
+            Ok(Node::Empty)
+        } else {
+            Ok(Node::Concat(nodes))
+        }
+    }
+
+    fn parse_number(&mut self) -> Option<u32> {
+        let start = self.pos;
+        while matches!(self.peek(), Some(c) if c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos]
+            .iter()
+            .collect::<String>()
+            .parse::<u32>()
+            .ok()
+    }
+
+    fn parse_quantifier(&mut self, atom: Node) -> Result<Node, String> {
+        let (min, max) = match self.peek() {
+            Some('*') => (0, None),
+            Some('+') => (1, None),
+            Some('?') => (0, Some(1)),
+            Some('{') => {
+                let start = self.pos;
+                self.pos += 1;
+                match self.parse_number() {
+                    None => {
+                        //A brace that does not start a quantifier is a literal, as in JavaScript
+                        self.pos = start;
+                        return Ok(atom);
+                    }
+                    Some(min) => {
+                        let max = if self.eat(',') {
+                            if self.peek() == Some('}') {
+                                None
+                            } else {
+                                match self.parse_number() {
+                                    Some(max) => Some(max),
+                                    None => return self.error("malformed quantifier"),
+                                }
+                            }
+                        } else {
+                            Some(min)
+                        };
+                        if self.peek() != Some('}') {
+                            return self.error("malformed quantifier");
+                        }
+                        if max.is_some_and(|max| max < min) {
+                            return self.error("quantifier range out of order");
+                        }
+                        (min, max)
+                    }
+                }
+            }
+            _ => return Ok(atom),
+        };
+        self.pos += 1;
+        if matches!(atom, Node::Start | Node::End | Node::WordBoundary(_)) {
+            return self.error("nothing to repeat");
+        }
+        //Whether a quantifier is greedy or lazy changes which match is found, but not whether
+        //there is one, so a lazy '?' suffix is accepted and ignored
+        self.eat('?');
+        Ok(Node::Repeat {
+            node: Box::new(atom),
+            min,
+            max,
+        })
+    }
+
+    fn parse_hex(&mut self, digits: usize) -> Result<char, String> {
+        let mut val = 0_u32;
+        for _ in 0..digits {
+            match self.peek().and_then(|c| c.to_digit(16)) {
+                Some(d) => {
+                    val = (val << 4) | d;
+                    self.pos += 1;
+                }
+                None => return self.error("malformed hexadecimal escape"),
+            }
+        }
+        match char::from_u32(val) {
+            Some(c) => Ok(c),
+            None => self.error("escape is not a valid character"),
+        }
+    }
+
+    ///Parses the character after a backslash, returning either a single character or a class
+    fn parse_escape(&mut self) -> Result<Result<char, ClassItem>, String> {
+        let c = match self.peek() {
+            Some(c) => c,
+            None => return self.error("trailing backslash"),
+        };
+        self.pos += 1;
+        Ok(match c {
+            'd' => Err(ClassItem::Digit(false)),
+            'D' => Err(ClassItem::Digit(true)),
+            'w' => Err(ClassItem::Word(false)),
+            'W' => Err(ClassItem::Word(true)),
+            's' => Err(ClassItem::Space(false)),
+            'S' => Err(ClassItem::Space(true)),
+            'n' => Ok('\n'),
+            'r' => Ok('\r'),
+            't' => Ok('\t'),
+            'f' => Ok('\x0C'),
+            'v' => Ok('\x0B'),
+            '0' => Ok('\0'),
+            'x' => Ok(self.parse_hex(2)?),
+            'u' => Ok(self.parse_hex(4)?),
+            c if c.is_ascii_alphanumeric() => {
+                self.pos -= 1;
+                return self.error("unsupported escape");
+            }
+            c => Ok(c),
+        })
+    }
+
+    fn parse_class(&mut self) -> Result<Node, String> {
+        let negated = self.eat('^');
+        let mut items = vec![];
+        loop {
+            let c = match self.peek() {
+                None => return self.error("unterminated character class"),
+                Some(']') => {
+                    self.pos += 1;
+                    break;
+                }
+                Some(c) => c,
+            };
+            self.pos += 1;
+            let lo = if c == '\\' {
+                match self.parse_escape()? {
+                    Ok(c) => c,
+                    Err(item) => {
+                        items.push(item);
+                        continue;
+                    }
+                }
+            } else {
+                c
+            };
+            if self.peek() == Some('-') && !matches!(self.chars.get(self.pos + 1), None | Some(']'))
+            {
+                self.pos += 1;
+                let hi = match self.peek() {
+                    Some('\\') => {
+                        self.pos += 1;
+                        match self.parse_escape()? {
+                            Ok(c) => c,
+                            Err(_) => return self.error("class escape used as range bound"),
+                        }
+                    }
+                    Some(c) => {
+                        self.pos += 1;
+                        c
+                    }
+                    None => return self.error("unterminated character class"),
+                };
+                if hi < lo {
+                    return self.error("character class range out of order");
+                }
+                items.push(ClassItem::Range(lo, hi));
+            } else {
+                items.push(ClassItem::Range(lo, lo));
+            }
+        }
+        Ok(Node::Class(items, negated))
+    }
+
+    fn parse_atom(&mut self) -> Result<Node, String> {
+        let c = self.peek().unwrap();
+        self.pos += 1;
+        match c {
+            '^' => Ok(Node::Start),
+            '$' => Ok(Node::End),
+            '.' => Ok(Node::Any),
+            '[' => self.parse_class(),
+            '(' => {
+                if self.depth == MAX_NESTING {
+                    return self.error("groups are nested too deeply");
+                }
+                self.depth += 1;
+                let node = if self.eat('?') {
+                    if self.eat(':') {
+                        self.parse_alternation()?
+                    } else if self.eat('=') {
+                        Node::LookAhead(Box::new(self.parse_alternation()?), false)
+                    } else if self.eat('!') {
+                        Node::LookAhead(Box::new(self.parse_alternation()?), true)
+                    } else {
+                        return self.error("unsupported group type");
+                    }
+                } else {
+                    self.parse_alternation()?
+                };
+                if !self.eat(')') {
+                    return self.error("unbalanced parenthesis");
+                }
+                self.depth -= 1;
+                Ok(node)
+            }
+            ')' => self.error("unbalanced parenthesis"),
+            '*' | '+' | '?' => {
+                self.pos -= 1;
+                self.error("nothing to repeat")
+            }
+            '\\' => match self.peek() {
+                Some('b') => {
+                    self.pos += 1;
+                    Ok(Node::WordBoundary(false))
+                }
+                Some('B') => {
+                    self.pos += 1;
+                    Ok(Node::WordBoundary(true))
+                }
+                _ => Ok(match self.parse_escape()? {
+                    Ok(c) => Node::Char(c),
+                    Err(item) => Node::Class(vec![item], false),
+                }),
+            },
+            c => Ok(Node::Char(c)),
+        }
+    }
+}
+
+///Compiles a parsed pattern into NFA programs
+struct Compiler<'a> {
+    programs: Vec<Vec<Inst>>,
+    source: &'a str,
+}
+
+impl<'a> Compiler<'a> {
+    fn emit(&mut self, program: usize, inst: Inst) -> Result<usize, String> {
+        if self.size() >= MAX_INSTRUCTIONS {
+            return Err(format!(
+                "invalid pattern {:?}: pattern is too large",
+                self.source
+            ));
+        }
+        self.programs[program].push(inst);
+        Ok(self.programs[program].len() - 1)
+    }
+
+    ///The number of instructions emitted so far, across all programs
+    fn size(&self) -> usize {
+        self.programs.iter().map(Vec::len).sum()
+    }
+
+    fn next(&self, program: usize) -> usize {
+        self.programs[program].len()
+    }
+
+    ///Compiles a node followed by a Match instruction into a new program, returning its index
+    fn program(&mut self, node: &Node) -> Result<usize, String> {
+        self.programs.push(vec![]);
+        let program = self.programs.len() - 1;
+        self.compile(node, program)?;
+        self.emit(program, Inst::Match)?;
+        Ok(program)
+    }
+
+    fn compile(&mut self, node: &Node, program: usize) -> Result<(), String> {
+        match node {
+            Node::Empty => {}
+            Node::Char(c) => {
+                self.emit(program, Inst::Char(*c))?;
+            }
+            Node::Any => {
+                self.emit(program, Inst::Any)?;
+            }
+            Node::Class(items, negated) => {
+                self.emit(program, Inst::Class(items.clone(), *negated))?;
+            }
+            Node::Start => {
+                self.emit(program, Inst::Start)?;
+            }
+            Node::End => {
+                self.emit(program, Inst::End)?;
+            }
+            Node::WordBoundary(negated) => {
+                self.emit(program, Inst::WordBoundary(*negated))?;
+            }
+            Node::Concat(nodes) => {
+                for node in nodes {
+                    self.compile(node, program)?;
+                }
+            }
+            Node::Alternate(alternatives) => {
+                let mut jumps = vec![];
+                for (i, alternative) in alternatives.iter().enumerate() {
+                    if i + 1 < alternatives.len() {
+                        let split = self.emit(program, Inst::Split(0, 0))?;
+                        self.compile(alternative, program)?;
+                        jumps.push(self.emit(program, Inst::Jump(0))?);
+                        self.programs[program][split] = Inst::Split(split + 1, self.next(program));
+                    } else {
+                        self.compile(alternative, program)?;
+                    }
+                }
+                let end = self.next(program);
+                for jump in jumps {
+                    self.programs[program][jump] = Inst::Jump(end);
+                }
+            }
+            Node::Repeat { node, min, max } => {
+                for _ in 0..*min {
+                    let size = self.size();
+                    self.compile(node, program)?;
+                    //A node which emits nothing, such as an empty group, matches the same however
+                    //often it is repeated, and repeating it would never reach the budget
+                    if self.size() == size {
+                        break;
+                    }
+                }
+                match max {
+                    None => {
+                        let split = self.emit(program, Inst::Split(0, 0))?;
+                        self.compile(node, program)?;
+                        self.emit(program, Inst::Jump(split))?;
+                        self.programs[program][split] = Inst::Split(split + 1, self.next(program));
+                    }
+                    Some(max) => {
+                        let mut splits = vec![];
+                        for _ in *min..*max {
+                            splits.push(self.emit(program, Inst::Split(0, 0))?);
+                            self.compile(node, program)?;
+                        }
+                        let end = self.next(program);
+                        for split in splits {
+                            self.programs[program][split] = Inst::Split(split + 1, end);
+                        }
+                    }
+                }
+            }
+            Node::LookAhead(node, negated) => {
+                let lookahead = self.program(node)?;
+                self.emit(program, Inst::LookAhead(lookahead, *negated))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Regex {
+    ///Compiles a pattern, returning a description of the problem if it is malformed or uses
+    ///syntax this engine does not support (such as backreferences)
+    pub fn new(pattern: &str) -> Result<Regex, String> {
+        let mut parser = RegexParser {
+            chars: pattern.chars().collect(),
+            pos: 0,
+            depth: 0,
+            source: pattern,
+        };
+        let root = parser.parse_alternation()?;
+        if parser.pos < parser.chars.len() {
+            return parser.error("unbalanced parenthesis");
+        }
+        let mut compiler = Compiler {
+            programs: vec![],
+            source: pattern,
+        };
+        compiler.program(&root)?;
+        Ok(Regex {
+            source: pattern.to_owned(),
+            programs: compiler.programs,
+        })
+    }
+
+    ///The pattern this regex was compiled from
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    ///Returns TRUE if the pattern matches anywhere within the input
+    pub fn is_match(&self, input: &str) -> bool {
+        let chars = input.chars().collect::<Vec<char>>();
+        let mut lookaheads = HashMap::new();
+        self.run(0, &chars, 0, false, &mut lookaheads)
+    }
+
+    fn is_word(c: char) -> bool {
+        c.is_ascii_alphanumeric() || c == '_'
+    }
+
+    ///Simulates a program on the input from `start`, returning TRUE as soon as any thread
+    ///reaches Match. If `anchored` is FALSE a new thread also starts at every later position.
+    ///Lookahead results are cached by program and position, so each is computed only once
+    fn run(
+        &self,
+        program: usize,
+        input: &[char],
+        start: usize,
+        anchored: bool,
+        lookaheads: &mut HashMap<(usize, usize), bool>,
+    ) -> bool {
+        let insts = &self.programs[program];
+        let mut current = vec![];
+        let mut next = vec![];
+        //The position at which each instruction was last added, so that a thread list holds
+        //every instruction at most once
+        let mut added = vec![usize::MAX; insts.len()];
+        for pos in start..=input.len() {
+            if pos == start || !anchored {
+                self.add_thread(program, 0, input, pos, &mut current, &mut added, lookaheads);
+            }
+            if current.is_empty() && anchored {
+                return false;
+            }
+            for &pc in &current {
+                let accepts = match (&insts[pc], input.get(pos)) {
+                    (Inst::Match, _) => return true,
+                    (Inst::Char(c), Some(x)) => c == x,
+                    (Inst::Any, Some(x)) => !matches!(x, '\n' | '\r'),
+                    (Inst::Class(items, negated), Some(x)) => {
+                        items.iter().any(|item| item.matches(*x)) != *negated
+                    }
+                    _ => false,
+                };
+                if accepts {
+                    self.add_thread(
+                        program,
+                        pc + 1,
+                        input,
+                        pos + 1,
+                        &mut next,
+                        &mut added,
+                        lookaheads,
+                    );
+                }
+            }
+            std::mem::swap(&mut current, &mut next);
+            next.clear();
+        }
+        false
+    }
+
+    ///Adds the instructions reachable from `pc` without consuming input to a thread list,
+    ///using an explicit stack rather than recursion
+    #[allow(clippy::too_many_arguments)]
+    fn add_thread(
+        &self,
+        program: usize,
+        pc: usize,
+        input: &[char],
+        pos: usize,
+        list: &mut Vec<usize>,
+        added: &mut [usize],
+        lookaheads: &mut HashMap<(usize, usize), bool>,
+    ) {
+        let insts = &self.programs[program];
+        let mut stack = vec![pc];
+        while let Some(pc) = stack.pop() {
+            if added[pc] == pos {
+                continue;
+            }
+            added[pc] = pos;
+            match &insts[pc] {
+                Inst::Jump(target) => stack.push(*target),
+                Inst::Split(first, second) => {
+                    stack.push(*second);
+                    stack.push(*first);
+                }
+                Inst::Start => {
+                    if pos == 0 {
+                        stack.push(pc + 1);
+                    }
+                }
+                Inst::End => {
+                    if pos == input.len() {
+                        stack.push(pc + 1);
+                    }
+                }
+                Inst::WordBoundary(negated) => {
+                    let before = pos > 0 && Self::is_word(input[pos - 1]);
+                    let after = pos < input.len() && Self::is_word(input[pos]);
+                    if (before != after) != *negated {
+                        stack.push(pc + 1);
+                    }
+                }
+                Inst::LookAhead(lookahead, negated) => {
+                    let matched = match lookaheads.get(&(*lookahead, pos)) {
+                        Some(matched) => *matched,
+                        None => {
+                            let matched = self.run(*lookahead, input, pos, true, lookaheads);
+                            lookaheads.insert((*lookahead, pos), matched);
+                            matched
+                        }
+                    };
+                    if matched != *negated {
+                        stack.push(pc + 1);
+                    }
+                }
+                Inst::Char(_) | Inst::Any | Inst::Class(..) | Inst::Match => list.push(pc),
+            }
+        }
+    }
+}
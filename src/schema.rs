@@ -0,0 +1,837 @@
+use crate::regex::Regex;
+use crate::{JsonValue, ValidationError};
+use std::collections::HashMap;
+
+///An error found while compiling a JSON Schema document with `JsonSchema::compile`, or while
+///reading a validator spec with `ValidatorSpec::from_json`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SchemaError {
+    ///JSON Pointer to the part of the document that could not be compiled
+    pub path: String,
+
+    ///A human-readable description of the problem
+    pub message: String,
+}
+
+impl std::fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let path = if self.path.is_empty() {
+            "/"
+        } else {
+            &self.path
+        };
+        write!(f, "{}: {}", path, self.message)
+    }
+}
+
+impl std::error::Error for SchemaError {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SchemaType {
+    Null,
+    Boolean,
+    Object,
+    Array,
+    Number,
+    String,
+    Integer,
+}
+
+impl SchemaType {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "null" => Some(SchemaType::Null),
+            "boolean" => Some(SchemaType::Boolean),
+            "object" => Some(SchemaType::Object),
+            "array" => Some(SchemaType::Array),
+            "number" => Some(SchemaType::Number),
+            "string" => Some(SchemaType::String),
+            "integer" => Some(SchemaType::Integer),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            SchemaType::Null => "null",
+            SchemaType::Boolean => "boolean",
+            SchemaType::Object => "object",
+            SchemaType::Array => "array",
+            SchemaType::Number => "number",
+            SchemaType::String => "string",
+            SchemaType::Integer => "integer",
+        }
+    }
+
+    ///Unlike JsonValidator, JSON Schema types are strict: "42" is a string, never a number
+    fn matches(self, input: &JsonValue) -> bool {
+        match (self, input) {
+            (SchemaType::Null, JsonValue::Null) => true,
+            (SchemaType::Boolean, JsonValue::Boolean(_)) => true,
+            (SchemaType::Object, JsonValue::Object(_)) => true,
+            (SchemaType::Array, JsonValue::Array(_)) => true,
+            (SchemaType::Number, JsonValue::Number(_)) => true,
+            (SchemaType::String, JsonValue::String(_)) => true,
+            (SchemaType::Integer, JsonValue::Number(n)) => n.is_finite() && n.fract() == 0_f64,
+            _ => false,
+        }
+    }
+
+    fn of(input: &JsonValue) -> &'static str {
+        match input {
+            JsonValue::Null => "null",
+            JsonValue::Boolean(_) => "boolean",
+            JsonValue::Number(_) => "number",
+            JsonValue::String(_) => "string",
+            JsonValue::Array(_) => "array",
+            JsonValue::Object(_) => "object",
+        }
+    }
+}
+
+///A single compiled (sub)schema. Child schemas are referenced by their index in
+///`JsonSchema::nodes`, which lets `$ref` point back up the tree without reference cycles
+#[derive(Clone, Debug, Default)]
+struct SchemaNode {
+    always: Option<bool>,
+    types: Option<Vec<SchemaType>>,
+    const_value: Option<JsonValue>,
+    enum_values: Option<Vec<JsonValue>>,
+    minimum: Option<f64>,
+    maximum: Option<f64>,
+    exclusive_minimum: Option<f64>,
+    exclusive_maximum: Option<f64>,
+    multiple_of: Option<f64>,
+    min_length: Option<usize>,
+    max_length: Option<usize>,
+    pattern: Option<Regex>,
+    prefix_items: Vec<usize>,
+    items: Option<usize>,
+    contains: Option<usize>,
+    min_contains: Option<usize>,
+    max_contains: Option<usize>,
+    min_items: Option<usize>,
+    max_items: Option<usize>,
+    unique_items: bool,
+    properties: Vec<(String, usize)>,
+    pattern_properties: Vec<(Regex, usize)>,
+    additional_properties: Option<usize>,
+    property_names: Option<usize>,
+    required: Vec<String>,
+    min_properties: Option<usize>,
+    max_properties: Option<usize>,
+    all_of: Vec<usize>,
+    any_of: Vec<usize>,
+    one_of: Vec<usize>,
+    not: Option<usize>,
+    reference: Option<usize>,
+}
+
+impl SchemaNode {
+    ///The subschemas applied to the same value as this one, rather than to a part of it
+    fn in_place(&self) -> Vec<usize> {
+        let mut children = self
+            .reference
+            .into_iter()
+            .chain(self.not)
+            .collect::<Vec<_>>();
+        children.extend(&self.all_of);
+        children.extend(&self.any_of);
+        children.extend(&self.one_of);
+        children
+    }
+}
+
+///A compiled JSON Schema (draft 2020-12) document. Supports the type, const, enum, numeric
+///range, multipleOf, string length, pattern, prefixItems, items, contains, array length,
+///uniqueItems, properties, patternProperties, additionalProperties, propertyNames, required,
+///property count, allOf, anyOf, oneOf, not, $defs and local $ref keywords; other keywords
+///(such as format) are treated as annotations and ignored
+#[derive(Clone, Debug)]
+pub struct JsonSchema {
+    nodes: Vec<SchemaNode>,
+    root: usize,
+}
+
+struct SchemaCompiler<'a> {
+    document: &'a JsonValue,
+    nodes: Vec<SchemaNode>,
+    compiled: HashMap<String, usize>,
+}
+
+impl<'a> SchemaCompiler<'a> {
+    fn error<T>(path: &str, message: String) -> Result<T, SchemaError> {
+        Err(SchemaError {
+            path: path.to_owned(),
+            message,
+        })
+    }
+
+    fn compile(&mut self, schema: &JsonValue, path: &str) -> Result<usize, SchemaError> {
+        if let Some(index) = self.compiled.get(path) {
+            return Ok(*index);
+        }
+        let index = self.nodes.len();
+        self.nodes.push(SchemaNode::default());
+        self.compiled.insert(path.to_owned(), index);
+        let node = match schema {
+            JsonValue::Boolean(b) => SchemaNode {
+                always: Some(*b),
+                ..SchemaNode::default()
+            },
+            JsonValue::Object(obj) => self.compile_object(obj, path)?,
+            _ => {
+                return Self::error(path, "a schema must be an object or a boolean".to_owned());
+            }
+        };
+        self.nodes[index] = node;
+        Ok(index)
+    }
+
+    fn compile_child(
+        &mut self,
+        schema: &JsonValue,
+        path: &str,
+        token: &str,
+    ) -> Result<usize, SchemaError> {
+        self.compile(schema, &ValidationError::child_path(path, token))
+    }
+
+    fn compile_list(
+        &mut self,
+        schema: &JsonValue,
+        path: &str,
+        keyword: &str,
+    ) -> Result<Vec<usize>, SchemaError> {
+        let path = ValidationError::child_path(path, keyword);
+        match schema {
+            JsonValue::Array(arr) if !arr.is_empty() => arr
+                .iter()
+                .enumerate()
+                .map(|(i, x)| self.compile_child(x, &path, &i.to_string()))
+                .collect(),
+            _ => Self::error(
+                &path,
+                format!("{} must be a non-empty array of schemas", keyword),
+            ),
+        }
+    }
+
+    fn number(value: &JsonValue, path: &str, keyword: &str) -> Result<f64, SchemaError> {
+        match value {
+            JsonValue::Number(n) => Ok(*n),
+            _ => Self::error(
+                &ValidationError::child_path(path, keyword),
+                format!("{} must be a number", keyword),
+            ),
+        }
+    }
+
+    fn count(value: &JsonValue, path: &str, keyword: &str) -> Result<usize, SchemaError> {
+        match value {
+            JsonValue::Number(n) if *n >= 0_f64 && n.fract() == 0_f64 => Ok(*n as usize),
+            _ => Self::error(
+                &ValidationError::child_path(path, keyword),
+                format!("{} must be a non-negative integer", keyword),
+            ),
+        }
+    }
+
+    fn regex(value: &JsonValue, path: &str) -> Result<Regex, SchemaError> {
+        match value {
+            JsonValue::String(s) => match Regex::new(s) {
+                Ok(regex) => Ok(regex),
+                Err(message) => Self::error(path, message),
+            },
+            _ => Self::error(path, "a pattern must be a string".to_owned()),
+        }
+    }
+
+    fn sorted_entries(obj: &HashMap<String, JsonValue>) -> Vec<(&String, &JsonValue)> {
+        let mut entries = obj.iter().collect::<Vec<_>>();
+        entries.sort_by(|a, b| a.0.cmp(b.0));
+        entries
+    }
+
+    fn compile_object(
+        &mut self,
+        obj: &HashMap<String, JsonValue>,
+        path: &str,
+    ) -> Result<SchemaNode, SchemaError> {
+        let mut node = SchemaNode::default();
+        for defs in ["$defs", "definitions"] {
+            if let Some(value) = obj.get(defs) {
+                let defs_path = ValidationError::child_path(path, defs);
+                match value {
+                    JsonValue::Object(entries) => {
+                        for (name, schema) in Self::sorted_entries(entries) {
+                            self.compile_child(schema, &defs_path, name)?;
+                        }
+                    }
+                    _ => return Self::error(&defs_path, format!("{} must be an object", defs)),
+                }
+            }
+        }
+        for (keyword, value) in Self::sorted_entries(obj) {
+            let keyword_path = ValidationError::child_path(path, keyword);
+            match keyword.as_str() {
+                "type" => {
+                    let names = match value {
+                        JsonValue::String(s) => vec![s.as_str()],
+                        JsonValue::Array(arr) => {
+                            let mut names = vec![];
+                            for x in arr {
+                                match x {
+                                    JsonValue::String(s) => names.push(s.as_str()),
+                                    _ => {
+                                        return Self::error(
+                                            &keyword_path,
+                                            "type names must be strings".to_owned(),
+                                        );
+                                    }
+                                }
+                            }
+                            names
+                        }
+                        _ => {
+                            return Self::error(
+                                &keyword_path,
+                                "type must be a string or an array of strings".to_owned(),
+                            );
+                        }
+                    };
+                    let mut types = vec![];
+                    for name in names {
+                        match SchemaType::from_name(name) {
+                            Some(t) => types.push(t),
+                            None => {
+                                return Self::error(
+                                    &keyword_path,
+                                    format!("unknown type {:?}", name),
+                                );
+                            }
+                        }
+                    }
+                    node.types = Some(types);
+                }
+                "const" => node.const_value = Some(value.clone()),
+                "enum" => match value {
+                    JsonValue::Array(arr) => node.enum_values = Some(arr.clone()),
+                    _ => return Self::error(&keyword_path, "enum must be an array".to_owned()),
+                },
+                "minimum" => node.minimum = Some(Self::number(value, path, keyword)?),
+                "maximum" => node.maximum = Some(Self::number(value, path, keyword)?),
+                "exclusiveMinimum" => {
+                    node.exclusive_minimum = Some(Self::number(value, path, keyword)?)
+                }
+                "exclusiveMaximum" => {
+                    node.exclusive_maximum = Some(Self::number(value, path, keyword)?)
+                }
+                "multipleOf" => {
+                    let n = Self::number(value, path, keyword)?;
+                    if n <= 0_f64 {
+                        return Self::error(
+                            &keyword_path,
+                            "multipleOf must be positive".to_owned(),
+                        );
+                    }
+                    node.multiple_of = Some(n);
+                }
+                "minLength" => node.min_length = Some(Self::count(value, path, keyword)?),
+                "maxLength" => node.max_length = Some(Self::count(value, path, keyword)?),
+                "pattern" => node.pattern = Some(Self::regex(value, &keyword_path)?),
+                "prefixItems" => node.prefix_items = self.compile_list(value, path, keyword)?,
+                "items" => node.items = Some(self.compile(value, &keyword_path)?),
+                "contains" => node.contains = Some(self.compile(value, &keyword_path)?),
+                "minContains" => node.min_contains = Some(Self::count(value, path, keyword)?),
+                "maxContains" => node.max_contains = Some(Self::count(value, path, keyword)?),
+                "minItems" => node.min_items = Some(Self::count(value, path, keyword)?),
+                "maxItems" => node.max_items = Some(Self::count(value, path, keyword)?),
+                "uniqueItems" => match value {
+                    JsonValue::Boolean(b) => node.unique_items = *b,
+                    _ => {
+                        return Self::error(
+                            &keyword_path,
+                            "uniqueItems must be a boolean".to_owned(),
+                        );
+                    }
+                },
+                "properties" | "patternProperties" => match value {
+                    JsonValue::Object(entries) => {
+                        for (name, schema) in Self::sorted_entries(entries) {
+                            let index = self.compile_child(schema, &keyword_path, name)?;
+                            if keyword == "properties" {
+                                node.properties.push((name.clone(), index));
+                            } else {
+                                let regex = Self::regex(
+                                    &JsonValue::String(name.clone()),
+                                    &ValidationError::child_path(&keyword_path, name),
+                                )?;
+                                node.pattern_properties.push((regex, index));
+                            }
+                        }
+                    }
+                    _ => {
+                        return Self::error(&keyword_path, format!("{} must be an object", keyword))
+                    }
+                },
+                "additionalProperties" => {
+                    node.additional_properties = Some(self.compile(value, &keyword_path)?)
+                }
+                "propertyNames" => node.property_names = Some(self.compile(value, &keyword_path)?),
+                "required" => match value {
+                    JsonValue::Array(arr) => {
+                        for x in arr {
+                            match x {
+                                JsonValue::String(s) => node.required.push(s.clone()),
+                                _ => {
+                                    return Self::error(
+                                        &keyword_path,
+                                        "required must be an array of strings".to_owned(),
+                                    );
+                                }
+                            }
+                        }
+                    }
+                    _ => {
+                        return Self::error(
+                            &keyword_path,
+                            "required must be an array of strings".to_owned(),
+                        );
+                    }
+                },
+                "minProperties" => node.min_properties = Some(Self::count(value, path, keyword)?),
+                "maxProperties" => node.max_properties = Some(Self::count(value, path, keyword)?),
+                "allOf" => node.all_of = self.compile_list(value, path, keyword)?,
+                "anyOf" => node.any_of = self.compile_list(value, path, keyword)?,
+                "oneOf" => node.one_of = self.compile_list(value, path, keyword)?,
+                "not" => node.not = Some(self.compile(value, &keyword_path)?),
+                "$ref" => match value {
+                    JsonValue::String(reference) => {
+                        node.reference = Some(self.resolve(reference, &keyword_path)?)
+                    }
+                    _ => return Self::error(&keyword_path, "$ref must be a string".to_owned()),
+                },
+                _ => {}
+            }
+        }
+        Ok(node)
+    }
+
+    fn percent_decode(input: &str) -> Option<String> {
+        let bytes = input.as_bytes();
+        let mut out = Vec::with_capacity(bytes.len());
+        let mut i = 0;
+        while i < bytes.len() {
+            if bytes[i] == b'%' {
+                let hex = std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok()?;
+                out.push(u8::from_str_radix(hex, 16).ok()?);
+                i += 3;
+            } else {
+                out.push(bytes[i]);
+                i += 1;
+            }
+        }
+        String::from_utf8(out).ok()
+    }
+
+    ///Resolves a `$ref` against the document being compiled. Only same-document references
+    ///("#" followed by a JSON Pointer) are supported, since Jasper never fetches remote schemas
+    fn resolve(&mut self, reference: &str, path: &str) -> Result<usize, SchemaError> {
+        let pointer = match reference.strip_prefix('#').and_then(Self::percent_decode) {
+            Some(pointer) => pointer,
+            None => {
+                return Self::error(
+                    path,
+                    format!(
+                        "unsupported $ref {:?}; only local references are supported",
+                        reference
+                    ),
+                );
+            }
+        };
+        if !pointer.is_empty() && !pointer.starts_with('/') {
+            return Self::error(
+                path,
+                format!(
+                    "unsupported $ref {:?}; anchors are not supported",
+                    reference
+                ),
+            );
+        }
+        let mut target = self.document;
+        for token in pointer.split('/').skip(1) {
+            let token = token.replace("~1", "/").replace("~0", "~");
+            let next = match target {
+                JsonValue::Object(obj) => obj.get(&token),
+                JsonValue::Array(arr) => token.parse::<usize>().ok().and_then(|i| arr.get(i)),
+                _ => None,
+            };
+            match next {
+                Some(next) => target = next,
+                None => {
+                    return Self::error(path, format!("$ref {:?} does not resolve", reference));
+                }
+            }
+        }
+        self.compile(target, &pointer)
+    }
+}
+
+impl JsonSchema {
+    ///Compiles a JSON Schema document (itself parsed into a JsonValue), returning an error
+    ///describing the first malformed or unsupported part of the document
+    pub fn compile(schema: &JsonValue) -> Result<JsonSchema, SchemaError> {
+        let mut compiler = SchemaCompiler {
+            document: schema,
+            nodes: vec![],
+            compiled: HashMap::new(),
+        };
+        let root = compiler.compile(schema, "")?;
+        let nodes = compiler.nodes;
+        let paths = compiler
+            .compiled
+            .into_iter()
+            .map(|(path, index)| (index, path))
+            .collect::<HashMap<usize, String>>();
+        //A cycle of in-place applicators would validate the same value forever, so reject it.
+        //Depth-first search, with an explicit stack, marks each node 1 while it is on the
+        //current path and 2 once everything it reaches is known to be acyclic
+        let mut state = vec![0_u8; nodes.len()];
+        for start in 0..nodes.len() {
+            if state[start] != 0 {
+                continue;
+            }
+            state[start] = 1;
+            let mut stack = vec![(start, nodes[start].in_place())];
+            while let Some((index, children)) = stack.last_mut() {
+                match children.pop() {
+                    Some(child) if state[child] == 1 => {
+                        return Err(SchemaError {
+                            path: paths[&child].clone(),
+                            message:
+                                "schema refers back to itself without moving into the instance"
+                                    .to_owned(),
+                        });
+                    }
+                    Some(child) if state[child] == 0 => {
+                        state[child] = 1;
+                        stack.push((child, nodes[child].in_place()));
+                    }
+                    Some(_) => {}
+                    None => {
+                        state[*index] = 2;
+                        stack.pop();
+                    }
+                }
+            }
+        }
+        Ok(JsonSchema { nodes, root })
+    }
+
+    ///Validates a JsonValue against the schema, returning every failure found along with the
+    ///JSON Pointer path of the value that caused it. The `validator` of each error is the
+    ///name of the schema keyword that failed (e.g. "minLength")
+    pub fn validate(&self, input: &JsonValue) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+        self.collect_errors(self.root, input, "", &mut errors);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    ///Returns TRUE if and only if the JsonValue is valid against the schema
+    pub fn is_valid(&self, input: &JsonValue) -> bool {
+        self.validate(input).is_ok()
+    }
+
+    fn is_valid_at(&self, index: usize, input: &JsonValue) -> bool {
+        let mut errors = Vec::new();
+        self.collect_errors(index, input, "", &mut errors);
+        errors.is_empty()
+    }
+
+    fn collect_errors(
+        &self,
+        index: usize,
+        input: &JsonValue,
+        path: &str,
+        errors: &mut Vec<ValidationError>,
+    ) {
+        let node = &self.nodes[index];
+        let mut fail = |keyword: &'static str, expected: &'static str, message: String| {
+            errors.push(ValidationError::new(path, keyword, expected, message))
+        };
+        match node.always {
+            Some(true) => return,
+            Some(false) => {
+                fail("false", "nothing", "no value is allowed here".to_owned());
+                return;
+            }
+            None => {}
+        }
+        if let Some(types) = &node.types {
+            if !types.iter().any(|t| t.matches(input)) {
+                let names = types.iter().map(|t| t.name()).collect::<Vec<&str>>();
+                let expected = if types.len() == 1 {
+                    types[0].name()
+                } else {
+                    "one of several types"
+                };
+                fail(
+                    "type",
+                    expected,
+                    format!(
+                        "expected {}, found {}",
+                        names.join(" or "),
+                        SchemaType::of(input)
+                    ),
+                );
+            }
+        }
+        if let Some(value) = &node.const_value {
+            if value != input {
+                fail(
+                    "const",
+                    "constant",
+                    format!("expected {}", value.stringify()),
+                );
+            }
+        }
+        if let Some(values) = &node.enum_values {
+            if !values.contains(input) {
+                fail(
+                    "enum",
+                    "enumerated value",
+                    format!("{} is not one of the allowed values", input.stringify()),
+                );
+            }
+        }
+        match input {
+            JsonValue::Number(n) => {
+                let n = *n;
+                if let Some(min) = node.minimum.filter(|min| n < *min) {
+                    fail("minimum", "number", format!("{} is less than {}", n, min));
+                }
+                if let Some(max) = node.maximum.filter(|max| n > *max) {
+                    fail(
+                        "maximum",
+                        "number",
+                        format!("{} is greater than {}", n, max),
+                    );
+                }
+                if let Some(min) = node.exclusive_minimum.filter(|min| n <= *min) {
+                    fail(
+                        "exclusiveMinimum",
+                        "number",
+                        format!("{} is not greater than {}", n, min),
+                    );
+                }
+                if let Some(max) = node.exclusive_maximum.filter(|max| n >= *max) {
+                    fail(
+                        "exclusiveMaximum",
+                        "number",
+                        format!("{} is not less than {}", n, max),
+                    );
+                }
+                if let Some(m) = node.multiple_of {
+                    let quotient = n / m;
+                    if (quotient - quotient.round()).abs()
+                        > f64::EPSILON * quotient.abs().max(1_f64) * 4_f64
+                    {
+                        fail(
+                            "multipleOf",
+                            "number",
+                            format!("{} is not a multiple of {}", n, m),
+                        );
+                    }
+                }
+            }
+            JsonValue::String(s) => {
+                let len = s.chars().count();
+                if let Some(min) = node.min_length.filter(|min| len < *min) {
+                    fail(
+                        "minLength",
+                        "string",
+                        format!("string is shorter than {} characters", min),
+                    );
+                }
+                if let Some(max) = node.max_length.filter(|max| len > *max) {
+                    fail(
+                        "maxLength",
+                        "string",
+                        format!("string is longer than {} characters", max),
+                    );
+                }
+                if let Some(regex) = node.pattern.as_ref().filter(|regex| !regex.is_match(s)) {
+                    fail(
+                        "pattern",
+                        "string",
+                        format!("string does not match {:?}", regex.as_str()),
+                    );
+                }
+            }
+            JsonValue::Array(arr) => {
+                if let Some(min) = node.min_items.filter(|min| arr.len() < *min) {
+                    fail(
+                        "minItems",
+                        "array",
+                        format!("array has fewer than {} items", min),
+                    );
+                }
+                if let Some(max) = node.max_items.filter(|max| arr.len() > *max) {
+                    fail(
+                        "maxItems",
+                        "array",
+                        format!("array has more than {} items", max),
+                    );
+                }
+                if node.unique_items {
+                    let duplicate = (0..arr.len()).any(|i| arr[i + 1..].contains(&arr[i]));
+                    if duplicate {
+                        fail(
+                            "uniqueItems",
+                            "array",
+                            "array items are not unique".to_owned(),
+                        );
+                    }
+                }
+                if let Some(contains) = node.contains {
+                    let found = arr.iter().filter(|x| self.is_valid_at(contains, x)).count();
+                    let min = node.min_contains.unwrap_or(1);
+                    if found < min {
+                        fail(
+                            "contains",
+                            "array",
+                            format!(
+                                "array contains {} matching items, fewer than {}",
+                                found, min
+                            ),
+                        );
+                    }
+                    if let Some(max) = node.max_contains.filter(|max| found > *max) {
+                        fail(
+                            "maxContains",
+                            "array",
+                            format!("array contains {} matching items, more than {}", found, max),
+                        );
+                    }
+                }
+            }
+            JsonValue::Object(obj) => {
+                for key in &node.required {
+                    if !obj.contains_key(key) {
+                        errors.push(ValidationError::new(
+                            &ValidationError::child_path(path, key),
+                            "required",
+                            "object",
+                            format!("missing required key {:?}", key),
+                        ));
+                    }
+                }
+                if let Some(min) = node.min_properties.filter(|min| obj.len() < *min) {
+                    errors.push(ValidationError::new(
+                        path,
+                        "minProperties",
+                        "object",
+                        format!("object has fewer than {} properties", min),
+                    ));
+                }
+                if let Some(max) = node.max_properties.filter(|max| obj.len() > *max) {
+                    errors.push(ValidationError::new(
+                        path,
+                        "maxProperties",
+                        "object",
+                        format!("object has more than {} properties", max),
+                    ));
+                }
+            }
+            _ => {}
+        }
+        match input {
+            JsonValue::Array(arr) => {
+                for (i, x) in arr.iter().enumerate() {
+                    let child = ValidationError::child_path(path, &i.to_string());
+                    match node.prefix_items.get(i) {
+                        Some(prefix) => self.collect_errors(*prefix, x, &child, errors),
+                        None => {
+                            if let Some(items) = node.items {
+                                self.collect_errors(items, x, &child, errors);
+                            }
+                        }
+                    }
+                }
+            }
+            JsonValue::Object(obj) => {
+                let mut entries = obj.iter().collect::<Vec<_>>();
+                entries.sort_by(|a, b| a.0.cmp(b.0));
+                for (key, x) in entries {
+                    let child = ValidationError::child_path(path, key);
+                    let mut matched = false;
+                    if let Some((_, index)) = node.properties.iter().find(|(name, _)| name == key) {
+                        matched = true;
+                        self.collect_errors(*index, x, &child, errors);
+                    }
+                    for (regex, index) in &node.pattern_properties {
+                        if regex.is_match(key) {
+                            matched = true;
+                            self.collect_errors(*index, x, &child, errors);
+                        }
+                    }
+                    if let (false, Some(index)) = (matched, node.additional_properties) {
+                        self.collect_errors(index, x, &child, errors);
+                    }
+                    if let Some(index) = node.property_names {
+                        self.collect_errors(index, &JsonValue::String(key.clone()), &child, errors);
+                    }
+                }
+            }
+            _ => {}
+        }
+        for index in &node.all_of {
+            self.collect_errors(*index, input, path, errors);
+        }
+        if !node.any_of.is_empty() && !node.any_of.iter().any(|i| self.is_valid_at(*i, input)) {
+            errors.push(ValidationError::new(
+                path,
+                "anyOf",
+                "any of the subschemas",
+                format!("value matched none of the {} subschemas", node.any_of.len()),
+            ));
+        }
+        if !node.one_of.is_empty() {
+            let matched = node
+                .one_of
+                .iter()
+                .filter(|i| self.is_valid_at(**i, input))
+                .count();
+            if matched != 1 {
+                errors.push(ValidationError::new(
+                    path,
+                    "oneOf",
+                    "exactly one of the subschemas",
+                    format!(
+                        "value matched {} of the {} subschemas",
+                        matched,
+                        node.one_of.len()
+                    ),
+                ));
+            }
+        }
+        if let Some(index) = node.not {
+            if self.is_valid_at(index, input) {
+                errors.push(ValidationError::new(
+                    path,
+                    "not",
+                    "anything but the subschema",
+                    "value matched a schema it must not match".to_owned(),
+                ));
+            }
+        }
+        if let Some(index) = node.reference {
+            self.collect_errors(index, input, path, errors);
+        }
+    }
+}
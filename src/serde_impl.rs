@@ -0,0 +1,842 @@
+use crate::{FromJsonError, JsonDate, JsonDateOnly, JsonDuration, JsonTime, JsonValue};
+use serde::de::{
+    self, DeserializeOwned, DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess,
+    VariantAccess, Visitor,
+};
+use serde::ser::{self, Serialize, Serializer};
+use serde::{Deserialize, Deserializer};
+use std::collections::HashMap;
+
+///Converts any `Serialize` value into a JsonValue
+pub fn to_value<T: Serialize + ?Sized>(value: &T) -> Result<JsonValue, FromJsonError> {
+    value.serialize(ValueSerializer)
+}
+
+///Converts a JsonValue into any `Deserialize` type. Errors carry the JSON Pointer of the value
+///that could not be converted, as with `FromJson`
+pub fn from_value<T: DeserializeOwned>(value: JsonValue) -> Result<T, FromJsonError> {
+    T::deserialize(value)
+}
+
+impl ser::Error for FromJsonError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        FromJsonError::new(msg.to_string())
+    }
+}
+
+impl de::Error for FromJsonError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        FromJsonError::new(msg.to_string())
+    }
+}
+
+///Whole numbers which fit are serialized as integers, so that formats with an integer type do not
+///write them as floats
+impl Serialize for JsonValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            JsonValue::Null => serializer.serialize_unit(),
+            JsonValue::Boolean(b) => serializer.serialize_bool(*b),
+            JsonValue::Number(n) => match whole_number(*n) {
+                Some(Whole::Unsigned(u)) => serializer.serialize_u64(u),
+                Some(Whole::Signed(i)) => serializer.serialize_i64(i),
+                None => serializer.serialize_f64(*n),
+            },
+            JsonValue::String(s) => serializer.serialize_str(s),
+            JsonValue::Array(arr) => serializer.collect_seq(arr),
+            JsonValue::Object(obj) => serializer.collect_map(obj),
+        }
+    }
+}
+
+enum Whole {
+    Unsigned(u64),
+    Signed(i64),
+}
+
+fn whole_number(n: f64) -> Option<Whole> {
+    //-0.0 is kept a float, since neither integer type has a negative zero
+    if n.fract() != 0.0 || (n == 0.0 && n.is_sign_negative()) {
+        None
+    } else if (0.0..18446744073709551616.0).contains(&n) {
+        Some(Whole::Unsigned(n as u64))
+    } else if (-9223372036854775808.0..0.0).contains(&n) {
+        Some(Whole::Signed(n as i64))
+    } else {
+        None
+    }
+}
+
+impl<'de> Deserialize<'de> for JsonValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+struct ValueVisitor;
+
+impl<'de> Visitor<'de> for ValueVisitor {
+    type Value = JsonValue;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("any JSON value")
+    }
+
+    fn visit_unit<E>(self) -> Result<JsonValue, E> {
+        Ok(JsonValue::Null)
+    }
+
+    fn visit_none<E>(self) -> Result<JsonValue, E> {
+        Ok(JsonValue::Null)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<JsonValue, D::Error> {
+        JsonValue::deserialize(deserializer)
+    }
+
+    fn visit_bool<E>(self, b: bool) -> Result<JsonValue, E> {
+        Ok(JsonValue::Boolean(b))
+    }
+
+    fn visit_i64<E>(self, n: i64) -> Result<JsonValue, E> {
+        Ok(JsonValue::Number(n as f64))
+    }
+
+    fn visit_u64<E>(self, n: u64) -> Result<JsonValue, E> {
+        Ok(JsonValue::Number(n as f64))
+    }
+
+    fn visit_f64<E>(self, n: f64) -> Result<JsonValue, E> {
+        Ok(JsonValue::Number(n))
+    }
+
+    fn visit_str<E>(self, s: &str) -> Result<JsonValue, E> {
+        Ok(JsonValue::String(s.to_owned()))
+    }
+
+    fn visit_string<E>(self, s: String) -> Result<JsonValue, E> {
+        Ok(JsonValue::String(s))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<JsonValue, A::Error> {
+        let mut arr = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(value) = seq.next_element()? {
+            arr.push(value);
+        }
+        Ok(JsonValue::Array(arr))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<JsonValue, A::Error> {
+        let mut obj = HashMap::with_capacity(map.size_hint().unwrap_or(0));
+        while let Some((key, value)) = map.next_entry()? {
+            obj.insert(key, value);
+        }
+        Ok(JsonValue::Object(obj))
+    }
+}
+
+macro_rules! date_serde {
+    ($($t:ty => $expected:literal),*) => {$(
+        ///Serialized as an ISO 8601 string
+        impl Serialize for $t {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_str(self)
+            }
+        }
+
+        ///Deserialized from an ISO 8601 string
+        impl<'de> Deserialize<'de> for $t {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                struct DateVisitor;
+
+                impl Visitor<'_> for DateVisitor {
+                    type Value = $t;
+
+                    fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        f.write_str(concat!("a ", $expected, " string"))
+                    }
+
+                    fn visit_str<E: de::Error>(self, s: &str) -> Result<$t, E> {
+                        <$t>::from_iso8601(s)
+                            .map_err(|e| E::custom(format!("invalid {}: {}", $expected, e)))
+                    }
+                }
+
+                deserializer.deserialize_str(DateVisitor)
+            }
+        }
+    )*};
+}
+
+date_serde!(
+    JsonDate => "datetime",
+    JsonDateOnly => "date",
+    JsonTime => "time",
+    JsonDuration => "duration"
+);
+
+struct ValueSerializer;
+
+impl Serializer for ValueSerializer {
+    type Ok = JsonValue;
+    type Error = FromJsonError;
+    type SerializeSeq = ArraySerializer;
+    type SerializeTuple = ArraySerializer;
+    type SerializeTupleStruct = ArraySerializer;
+    type SerializeTupleVariant = VariantSerializer<ArraySerializer>;
+    type SerializeMap = ObjectSerializer;
+    type SerializeStruct = ObjectSerializer;
+    type SerializeStructVariant = VariantSerializer<ObjectSerializer>;
+
+    fn serialize_bool(self, v: bool) -> Result<JsonValue, FromJsonError> {
+        Ok(JsonValue::Boolean(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<JsonValue, FromJsonError> {
+        Ok(JsonValue::from(v))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<JsonValue, FromJsonError> {
+        Ok(JsonValue::from(v))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<JsonValue, FromJsonError> {
+        Ok(JsonValue::from(v))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<JsonValue, FromJsonError> {
+        Ok(JsonValue::from(v))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<JsonValue, FromJsonError> {
+        Ok(JsonValue::from(v))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<JsonValue, FromJsonError> {
+        Ok(JsonValue::from(v))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<JsonValue, FromJsonError> {
+        Ok(JsonValue::from(v))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<JsonValue, FromJsonError> {
+        Ok(JsonValue::from(v))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<JsonValue, FromJsonError> {
+        self.serialize_f64(v as f64)
+    }
+
+    ///Fails on NaN and the infinities, which JSON cannot represent
+    fn serialize_f64(self, v: f64) -> Result<JsonValue, FromJsonError> {
+        if v.is_finite() {
+            Ok(JsonValue::from(v))
+        } else {
+            Err(ser::Error::custom(format!(
+                "{} cannot be represented as a JSON number",
+                v
+            )))
+        }
+    }
+
+    fn serialize_char(self, v: char) -> Result<JsonValue, FromJsonError> {
+        Ok(JsonValue::from(v))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<JsonValue, FromJsonError> {
+        Ok(JsonValue::from(v))
+    }
+
+    ///Bytes become an array of numbers
+    fn serialize_bytes(self, v: &[u8]) -> Result<JsonValue, FromJsonError> {
+        Ok(v.iter().map(|b| JsonValue::from(*b)).collect())
+    }
+
+    fn serialize_none(self) -> Result<JsonValue, FromJsonError> {
+        Ok(JsonValue::Null)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<JsonValue, FromJsonError> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<JsonValue, FromJsonError> {
+        Ok(JsonValue::Null)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<JsonValue, FromJsonError> {
+        Ok(JsonValue::Null)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<JsonValue, FromJsonError> {
+        Ok(JsonValue::from(variant))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<JsonValue, FromJsonError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<JsonValue, FromJsonError> {
+        let value = to_value(value).map_err(|e| e.within(variant))?;
+        Ok(JsonValue::Object(HashMap::from([(
+            variant.to_owned(),
+            value,
+        )])))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<ArraySerializer, FromJsonError> {
+        Ok(ArraySerializer(Vec::with_capacity(len.unwrap_or(0))))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<ArraySerializer, FromJsonError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<ArraySerializer, FromJsonError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<VariantSerializer<ArraySerializer>, FromJsonError> {
+        Ok(VariantSerializer {
+            variant,
+            content: ArraySerializer(Vec::with_capacity(len)),
+        })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<ObjectSerializer, FromJsonError> {
+        Ok(ObjectSerializer {
+            obj: HashMap::with_capacity(len.unwrap_or(0)),
+            key: None,
+        })
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<ObjectSerializer, FromJsonError> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<VariantSerializer<ObjectSerializer>, FromJsonError> {
+        Ok(VariantSerializer {
+            variant,
+            content: self.serialize_map(Some(len))?,
+        })
+    }
+}
+
+struct ArraySerializer(Vec<JsonValue>);
+
+impl ArraySerializer {
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), FromJsonError> {
+        let value = to_value(value).map_err(|e| e.within(&self.0.len().to_string()))?;
+        self.0.push(value);
+        Ok(())
+    }
+}
+
+impl ser::SerializeSeq for ArraySerializer {
+    type Ok = JsonValue;
+    type Error = FromJsonError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), FromJsonError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<JsonValue, FromJsonError> {
+        Ok(JsonValue::Array(self.0))
+    }
+}
+
+impl ser::SerializeTuple for ArraySerializer {
+    type Ok = JsonValue;
+    type Error = FromJsonError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), FromJsonError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<JsonValue, FromJsonError> {
+        Ok(JsonValue::Array(self.0))
+    }
+}
+
+impl ser::SerializeTupleStruct for ArraySerializer {
+    type Ok = JsonValue;
+    type Error = FromJsonError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), FromJsonError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<JsonValue, FromJsonError> {
+        Ok(JsonValue::Array(self.0))
+    }
+}
+
+struct ObjectSerializer {
+    obj: HashMap<String, JsonValue>,
+
+    ///The key passed to `serialize_key`, waiting for its value
+    key: Option<String>,
+}
+
+impl ObjectSerializer {
+    fn insert<T: Serialize + ?Sized>(
+        &mut self,
+        key: String,
+        value: &T,
+    ) -> Result<(), FromJsonError> {
+        let value = to_value(value).map_err(|e| e.within(&key))?;
+        self.obj.insert(key, value);
+        Ok(())
+    }
+}
+
+impl ser::SerializeMap for ObjectSerializer {
+    type Ok = JsonValue;
+    type Error = FromJsonError;
+
+    ///Keys must serialize to strings, numbers or booleans; the latter two are written as strings
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), FromJsonError> {
+        self.key = Some(match to_value(key)? {
+            JsonValue::String(s) => s,
+            JsonValue::Number(n) => n.to_string(),
+            JsonValue::Boolean(b) => b.to_string(),
+            other => return Err(FromJsonError::expected("a string key", &other)),
+        });
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), FromJsonError> {
+        let key = self
+            .key
+            .take()
+            .ok_or_else(|| FromJsonError::new("serialize_value called before serialize_key"))?;
+        self.insert(key, value)
+    }
+
+    fn end(self) -> Result<JsonValue, FromJsonError> {
+        Ok(JsonValue::Object(self.obj))
+    }
+}
+
+impl ser::SerializeStruct for ObjectSerializer {
+    type Ok = JsonValue;
+    type Error = FromJsonError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), FromJsonError> {
+        self.insert(key.to_owned(), value)
+    }
+
+    fn end(self) -> Result<JsonValue, FromJsonError> {
+        Ok(JsonValue::Object(self.obj))
+    }
+}
+
+///The content of a tuple or struct variant, which is written as `{"Variant": content}`
+struct VariantSerializer<C> {
+    variant: &'static str,
+    content: C,
+}
+
+impl<C> VariantSerializer<C> {
+    fn wrap(variant: &str, content: JsonValue) -> JsonValue {
+        JsonValue::Object(HashMap::from([(variant.to_owned(), content)]))
+    }
+}
+
+impl ser::SerializeTupleVariant for VariantSerializer<ArraySerializer> {
+    type Ok = JsonValue;
+    type Error = FromJsonError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), FromJsonError> {
+        self.content.push(value).map_err(|e| e.within(self.variant))
+    }
+
+    fn end(self) -> Result<JsonValue, FromJsonError> {
+        Ok(Self::wrap(self.variant, JsonValue::Array(self.content.0)))
+    }
+}
+
+impl ser::SerializeStructVariant for VariantSerializer<ObjectSerializer> {
+    type Ok = JsonValue;
+    type Error = FromJsonError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), FromJsonError> {
+        self.content
+            .insert(key.to_owned(), value)
+            .map_err(|e| e.within(self.variant))
+    }
+
+    fn end(self) -> Result<JsonValue, FromJsonError> {
+        Ok(Self::wrap(
+            self.variant,
+            JsonValue::Object(self.content.obj),
+        ))
+    }
+}
+
+///Lets a JsonValue be read by any `Deserialize` type, as `from_value` does
+impl<'de> Deserializer<'de> for JsonValue {
+    type Error = FromJsonError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, FromJsonError> {
+        match self {
+            JsonValue::Null => visitor.visit_unit(),
+            JsonValue::Boolean(b) => visitor.visit_bool(b),
+            JsonValue::Number(n) => match whole_number(n) {
+                Some(Whole::Unsigned(u)) => visitor.visit_u64(u),
+                Some(Whole::Signed(i)) => visitor.visit_i64(i),
+                None => visitor.visit_f64(n),
+            },
+            JsonValue::String(s) => visitor.visit_string(s),
+            JsonValue::Array(arr) => visit_array(arr, visitor),
+            JsonValue::Object(obj) => visit_object(obj, visitor),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, FromJsonError> {
+        match self {
+            JsonValue::Null => visitor.visit_none(),
+            other => visitor.visit_some(other),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, FromJsonError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    ///Enums are read as `"Variant"` or `{"Variant": content}`
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, FromJsonError> {
+        match self {
+            JsonValue::String(variant) => visitor.visit_enum(EnumDeserializer {
+                variant,
+                content: None,
+            }),
+            JsonValue::Object(obj) if obj.len() == 1 => {
+                let (variant, content) = obj.into_iter().next().unwrap();
+                visitor.visit_enum(EnumDeserializer {
+                    variant,
+                    content: Some(content),
+                })
+            }
+            other => Err(FromJsonError::expected(
+                "a variant name or an object with a single key",
+                &other,
+            )),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+        unit unit_struct seq tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+impl IntoDeserializer<'_, FromJsonError> for JsonValue {
+    type Deserializer = JsonValue;
+
+    fn into_deserializer(self) -> JsonValue {
+        self
+    }
+}
+
+fn visit_array<'de, V: Visitor<'de>>(
+    arr: Vec<JsonValue>,
+    visitor: V,
+) -> Result<V::Value, FromJsonError> {
+    let len = arr.len();
+    let mut seq = ArrayDeserializer {
+        iter: arr.into_iter(),
+        index: 0,
+    };
+    let output = visitor.visit_seq(&mut seq)?;
+    if seq.iter.len() == 0 {
+        Ok(output)
+    } else {
+        Err(de::Error::invalid_length(len, &"fewer elements in array"))
+    }
+}
+
+fn visit_object<'de, V: Visitor<'de>>(
+    obj: HashMap<String, JsonValue>,
+    visitor: V,
+) -> Result<V::Value, FromJsonError> {
+    let len = obj.len();
+    let mut map = ObjectDeserializer {
+        iter: obj.into_iter(),
+        value: None,
+    };
+    let output = visitor.visit_map(&mut map)?;
+    if map.iter.len() == 0 {
+        Ok(output)
+    } else {
+        Err(de::Error::invalid_length(len, &"fewer elements in object"))
+    }
+}
+
+struct ArrayDeserializer {
+    iter: std::vec::IntoIter<JsonValue>,
+    index: usize,
+}
+
+impl<'de> SeqAccess<'de> for ArrayDeserializer {
+    type Error = FromJsonError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, FromJsonError> {
+        match self.iter.next() {
+            Some(value) => {
+                let index = self.index;
+                self.index += 1;
+                seed.deserialize(value)
+                    .map(Some)
+                    .map_err(|e| e.within(&index.to_string()))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
+
+struct ObjectDeserializer {
+    iter: std::collections::hash_map::IntoIter<String, JsonValue>,
+
+    ///The entry whose key was just read, waiting for its value to be read
+    value: Option<(String, JsonValue)>,
+}
+
+impl<'de> MapAccess<'de> for ObjectDeserializer {
+    type Error = FromJsonError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, FromJsonError> {
+        match self.iter.next() {
+            Some((key, value)) => {
+                let output = seed
+                    .deserialize(KeyDeserializer(key.clone()))
+                    .map_err(|e| e.within(&key));
+                self.value = Some((key, value));
+                output.map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, FromJsonError> {
+        let (key, value) = self
+            .value
+            .take()
+            .ok_or_else(|| FromJsonError::new("next_value called before next_key"))?;
+        seed.deserialize(value).map_err(|e| e.within(&key))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
+
+///Reads an object key, which may stand for a number or boolean as written by `to_value`
+struct KeyDeserializer(String);
+
+macro_rules! parse_key {
+    ($($method:ident => $visit:ident),*) => {$(
+        fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, FromJsonError> {
+            match self.0.parse() {
+                Ok(parsed) => visitor.$visit(parsed),
+                Err(_) => visitor.visit_string(self.0),
+            }
+        }
+    )*};
+}
+
+impl<'de> Deserializer<'de> for KeyDeserializer {
+    type Error = FromJsonError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, FromJsonError> {
+        visitor.visit_string(self.0)
+    }
+
+    parse_key!(
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64
+    );
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, FromJsonError> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, FromJsonError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, FromJsonError> {
+        JsonValue::String(self.0).deserialize_enum(name, variants, visitor)
+    }
+
+    serde::forward_to_deserialize_any! {
+        char str string bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+struct EnumDeserializer {
+    variant: String,
+
+    ///None for `"Variant"`, or the content of `{"Variant": content}`
+    content: Option<JsonValue>,
+}
+
+impl<'de> EnumAccess<'de> for EnumDeserializer {
+    type Error = FromJsonError;
+    type Variant = EnumDeserializer;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, EnumDeserializer), FromJsonError> {
+        let variant = seed.deserialize(JsonValue::String(self.variant.clone()))?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de> VariantAccess<'de> for EnumDeserializer {
+    type Error = FromJsonError;
+
+    fn unit_variant(self) -> Result<(), FromJsonError> {
+        match self.content {
+            None | Some(JsonValue::Null) => Ok(()),
+            Some(other) => Err(FromJsonError::expected("null", &other).within(&self.variant)),
+        }
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> Result<T::Value, FromJsonError> {
+        match self.content {
+            Some(content) => seed
+                .deserialize(content)
+                .map_err(|e| e.within(&self.variant)),
+            None => Err(FromJsonError::new(format!(
+                "expected an object for variant {:?}, found a string",
+                self.variant
+            ))),
+        }
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, FromJsonError> {
+        match self.content {
+            Some(JsonValue::Array(arr)) => {
+                visit_array(arr, visitor).map_err(|e| e.within(&self.variant))
+            }
+            Some(other) => Err(FromJsonError::expected("an array", &other).within(&self.variant)),
+            None => Err(FromJsonError::new(format!(
+                "expected an object for variant {:?}, found a string",
+                self.variant
+            ))),
+        }
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, FromJsonError> {
+        match self.content {
+            Some(JsonValue::Object(obj)) => {
+                visit_object(obj, visitor).map_err(|e| e.within(&self.variant))
+            }
+            Some(other) => Err(FromJsonError::expected("an object", &other).within(&self.variant)),
+            None => Err(FromJsonError::new(format!(
+                "expected an object for variant {:?}, found a string",
+                self.variant
+            ))),
+        }
+    }
+}
@@ -0,0 +1,682 @@
+use crate::{
+    AdditionalKeys, ArrayConstraints, DateFormat, Field, JsonValidator, JsonValue, Regex,
+    SchemaError, StringConstraints, ValidationError,
+};
+use std::collections::HashMap;
+
+///A declarative description of a JsonValidator. Unlike a JsonValidator, which holds function
+///pointers and closures, a ValidatorSpec is plain data: it can be converted to and from a
+///JsonValue (and so stored in config or sent between services) and then compiled into a
+///JsonValidator with `ValidatorSpec::compile`.
+///
+///As JSON, every spec is an object with a "type" key naming the variant in snake_case, e.g.
+///`{"type": "string", "max_length": 10, "pattern": "^[a-z]+$"}` or
+///`{"type": "object", "fields": [{"name": "id", "required": true, "validator": {"type": "integer"}}]}`
+#[derive(Clone, Debug, PartialEq)]
+pub enum ValidatorSpec {
+    ///Validates any value (compiles to `JsonValidator::RubberStamp`)
+    Any,
+
+    ///Validates only null values
+    Null,
+
+    ///Validates only values that can be read as a boolean
+    Boolean,
+
+    ///Validates only values that can be read as a number within the (inclusive) bounds
+    Number {
+        ///The minimum, if any
+        min: Option<f64>,
+
+        ///The maximum, if any
+        max: Option<f64>,
+    },
+
+    ///Validates only values that can be read as an integer within the (inclusive) bounds. As
+    ///JSON, a bound above 2^53 is written as a string, since a number would lose precision
+    Integer {
+        ///The minimum, if any
+        min: Option<u64>,
+
+        ///The maximum, if any
+        max: Option<u64>,
+    },
+
+    ///Validates only values that can be read as a string of the given length (in characters)
+    ///that matches the given regular expression
+    String {
+        ///The minimum length, if any
+        min_length: Option<usize>,
+
+        ///The maximum length, if any
+        max_length: Option<usize>,
+
+        ///A regular expression the string must match (anywhere, unless anchored), if any
+        pattern: Option<String>,
+    },
+
+    ///Validates only values that can be read as a JsonDate
+    DateTime,
+
+    ///Validates only values that can be read as a JsonDate in any of the given formats. As
+    ///JSON, this is a "datetime" spec with a "formats" list of snake_case format names, e.g.
+    ///`{"type": "datetime", "formats": ["rfc3339", "unix_millis"]}`
+    DateTimeWith(Vec<DateFormat>),
+
+    ///Validates only values that can be read as a JsonDateOnly
+    Date,
+
+    ///Validates only values that can be read as a JsonTime
+    Time,
+
+    ///Validates only values that can be read as a JsonDuration
+    Duration,
+
+    ///Validates only values equal to one of the given values
+    Enum(Vec<JsonValue>),
+
+    ///Validates only values equal to the given value
+    Const(JsonValue),
+
+    ///Validates arrays (see `ArrayConstraints`)
+    Array {
+        ///The spec for every element
+        items: Box<ValidatorSpec>,
+
+        ///The minimum number of elements, if any
+        min_items: Option<usize>,
+
+        ///The maximum number of elements, if any
+        max_items: Option<usize>,
+
+        ///Whether duplicate elements fail validation
+        unique_items: bool,
+
+        ///Whether values other than arrays fail validation
+        strict: bool,
+    },
+
+    ///Validates objects field by field (see `JsonValidator::ObjectFields`)
+    Object {
+        ///The named keys of the object
+        fields: Vec<FieldSpec>,
+
+        ///The policy for keys not listed in `fields`
+        additional_keys: AdditionalKeysSpec,
+    },
+
+    ///Validates values validated by any of the specs
+    AnyOf(Vec<ValidatorSpec>),
+
+    ///Validates values validated by all of the specs
+    AllOf(Vec<ValidatorSpec>),
+
+    ///Validates values validated by exactly one of the specs
+    ExactlyOne(Vec<ValidatorSpec>),
+
+    ///Validates values not validated by the spec
+    Not(Box<ValidatorSpec>),
+
+    ///Validates fixed-shape arrays, each element by the spec in the same position
+    Tuple(Vec<ValidatorSpec>),
+}
+
+///A named key within a `ValidatorSpec::Object`
+#[derive(Clone, Debug, PartialEq)]
+pub struct FieldSpec {
+    ///The key
+    pub name: String,
+
+    ///The spec for the key's value
+    pub spec: ValidatorSpec,
+
+    ///Whether validation fails when the key is missing
+    pub required: bool,
+
+    ///The value `JsonValidator::normalize` fills in when the key is missing, if any
+    pub default: Option<JsonValue>,
+}
+
+///The declarative counterpart of `AdditionalKeys`
+#[derive(Clone, Debug, PartialEq)]
+pub enum AdditionalKeysSpec {
+    ///Unknown keys are allowed and not checked
+    Allow,
+
+    ///Any unknown key fails validation
+    Deny,
+
+    ///Unknown keys are allowed if their values are validated by the spec
+    Validate(Box<ValidatorSpec>),
+
+    ///Unknown keys are allowed, but removed by `JsonValidator::normalize`
+    Strip,
+}
+
+impl ValidatorSpec {
+    ///Converts the spec to its JSON representation
+    pub fn to_json(&self) -> JsonValue {
+        let tagged = |name: &str, mut entries: Vec<(&str, JsonValue)>| {
+            entries.insert(0, ("type", JsonValue::String(name.to_owned())));
+            JsonValue::build_object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key.to_owned(), value))
+                    .collect(),
+            )
+        };
+        let optional = |entries: &mut Vec<(&str, JsonValue)>, key, value: Option<f64>| {
+            if let Some(value) = value {
+                entries.push((key, JsonValue::Number(value)));
+            }
+        };
+        let list = |specs: &[ValidatorSpec]| {
+            JsonValue::Array(specs.iter().map(|spec| spec.to_json()).collect())
+        };
+        match self {
+            ValidatorSpec::Any => tagged("any", vec![]),
+            ValidatorSpec::Null => tagged("null", vec![]),
+            ValidatorSpec::Boolean => tagged("boolean", vec![]),
+            ValidatorSpec::Number { min, max } => {
+                let mut entries = vec![];
+                optional(&mut entries, "min", *min);
+                optional(&mut entries, "max", *max);
+                tagged("number", entries)
+            }
+            ValidatorSpec::Integer { min, max } => {
+                //A bound that an f64 cannot hold exactly is written as a string instead
+                let mut entries = vec![];
+                for (key, bound) in [("min", min), ("max", max)] {
+                    match bound {
+                        Some(x) if *x > MAX_EXACT_INTEGER => {
+                            entries.push((key, JsonValue::String(x.to_string())))
+                        }
+                        Some(x) => entries.push((key, JsonValue::Number(*x as f64))),
+                        None => {}
+                    }
+                }
+                tagged("integer", entries)
+            }
+            ValidatorSpec::String {
+                min_length,
+                max_length,
+                pattern,
+            } => {
+                let mut entries = vec![];
+                optional(&mut entries, "min_length", min_length.map(|x| x as f64));
+                optional(&mut entries, "max_length", max_length.map(|x| x as f64));
+                if let Some(pattern) = pattern {
+                    entries.push(("pattern", JsonValue::String(pattern.clone())));
+                }
+                tagged("string", entries)
+            }
+            ValidatorSpec::DateTime => tagged("datetime", vec![]),
+            ValidatorSpec::DateTimeWith(formats) => tagged(
+                "datetime",
+                vec![(
+                    "formats",
+                    JsonValue::Array(
+                        formats
+                            .iter()
+                            .map(|format| JsonValue::String(format_name(*format).to_owned()))
+                            .collect(),
+                    ),
+                )],
+            ),
+            ValidatorSpec::Date => tagged("date", vec![]),
+            ValidatorSpec::Time => tagged("time", vec![]),
+            ValidatorSpec::Duration => tagged("duration", vec![]),
+            ValidatorSpec::Enum(values) => {
+                tagged("enum", vec![("values", JsonValue::Array(values.clone()))])
+            }
+            ValidatorSpec::Const(value) => tagged("const", vec![("value", value.clone())]),
+            ValidatorSpec::Array {
+                items,
+                min_items,
+                max_items,
+                unique_items,
+                strict,
+            } => {
+                let mut entries = vec![("items", items.to_json())];
+                optional(&mut entries, "min_items", min_items.map(|x| x as f64));
+                optional(&mut entries, "max_items", max_items.map(|x| x as f64));
+                entries.push(("unique_items", JsonValue::Boolean(*unique_items)));
+                entries.push(("strict", JsonValue::Boolean(*strict)));
+                tagged("array", entries)
+            }
+            ValidatorSpec::Object {
+                fields,
+                additional_keys,
+            } => {
+                let fields = fields
+                    .iter()
+                    .map(|field| {
+                        let mut entries = vec![
+                            ("name".to_owned(), JsonValue::String(field.name.clone())),
+                            ("required".to_owned(), JsonValue::Boolean(field.required)),
+                            ("validator".to_owned(), field.spec.to_json()),
+                        ];
+                        if let Some(default) = &field.default {
+                            entries.push(("default".to_owned(), default.clone()));
+                        }
+                        JsonValue::build_object(entries)
+                    })
+                    .collect();
+                let additional_keys = match additional_keys {
+                    AdditionalKeysSpec::Allow => JsonValue::String("allow".to_owned()),
+                    AdditionalKeysSpec::Deny => JsonValue::String("deny".to_owned()),
+                    AdditionalKeysSpec::Strip => JsonValue::String("strip".to_owned()),
+                    AdditionalKeysSpec::Validate(spec) => spec.to_json(),
+                };
+                tagged(
+                    "object",
+                    vec![
+                        ("fields", JsonValue::Array(fields)),
+                        ("additional_keys", additional_keys),
+                    ],
+                )
+            }
+            ValidatorSpec::AnyOf(specs) => tagged("any_of", vec![("validators", list(specs))]),
+            ValidatorSpec::AllOf(specs) => tagged("all_of", vec![("validators", list(specs))]),
+            ValidatorSpec::ExactlyOne(specs) => {
+                tagged("exactly_one", vec![("validators", list(specs))])
+            }
+            ValidatorSpec::Not(spec) => tagged("not", vec![("validator", spec.to_json())]),
+            ValidatorSpec::Tuple(specs) => tagged("tuple", vec![("validators", list(specs))]),
+        }
+    }
+
+    ///Reads a spec from its JSON representation, returning an error locating the first part of
+    ///the input that is not a valid spec. Patterns are checked here, so a spec read this way
+    ///always compiles
+    pub fn from_json(input: &JsonValue) -> Result<ValidatorSpec, SchemaError> {
+        SpecReader::read(input, "")
+    }
+
+    ///Compiles the spec into a JsonValidator, returning an error message if a pattern is not
+    ///a valid regular expression
+    pub fn compile(&self) -> Result<JsonValidator, String> {
+        let compile_all = |specs: &[ValidatorSpec]| {
+            specs
+                .iter()
+                .map(|spec| spec.compile())
+                .collect::<Result<Vec<JsonValidator>, String>>()
+        };
+        Ok(match self {
+            ValidatorSpec::Any => JsonValidator::RubberStamp,
+            ValidatorSpec::Null => JsonValidator::Null,
+            ValidatorSpec::Boolean => JsonValidator::Boolean(|_| true),
+            ValidatorSpec::Number { min, max } => JsonValidator::NumberRange {
+                min: *min,
+                max: *max,
+            },
+            ValidatorSpec::Integer { min, max } => JsonValidator::IntegerRange {
+                min: *min,
+                max: *max,
+            },
+            ValidatorSpec::String {
+                min_length,
+                max_length,
+                pattern,
+            } => JsonValidator::StringOf(StringConstraints {
+                min_length: *min_length,
+                max_length: *max_length,
+                pattern: match pattern {
+                    Some(pattern) => Some(Regex::new(pattern)?),
+                    None => None,
+                },
+            }),
+            ValidatorSpec::DateTime => JsonValidator::DateTime(|_| true),
+            ValidatorSpec::DateTimeWith(formats) => {
+                JsonValidator::DateTimeWith(formats.clone(), |_| true)
+            }
+            ValidatorSpec::Date => JsonValidator::Date(|_| true),
+            ValidatorSpec::Time => JsonValidator::Time(|_| true),
+            ValidatorSpec::Duration => JsonValidator::Duration(|_| true),
+            ValidatorSpec::Enum(values) => JsonValidator::Enum(values.clone()),
+            ValidatorSpec::Const(value) => JsonValidator::Const(value.clone()),
+            ValidatorSpec::Array {
+                items,
+                min_items,
+                max_items,
+                unique_items,
+                strict,
+            } => JsonValidator::ArrayOf(ArrayConstraints {
+                min_items: *min_items,
+                max_items: *max_items,
+                unique_items: *unique_items,
+                strict: *strict,
+                ..ArrayConstraints::new(items.compile()?)
+            }),
+            ValidatorSpec::Object {
+                fields,
+                additional_keys,
+            } => JsonValidator::ObjectFields {
+                fields: fields
+                    .iter()
+                    .map(|field| {
+                        Ok(Field {
+                            name: field.name.clone(),
+                            validator: field.spec.compile()?,
+                            required: field.required,
+                            default: field.default.clone(),
+                        })
+                    })
+                    .collect::<Result<Vec<Field>, String>>()?,
+                patterns: vec![],
+                additional_keys: match additional_keys {
+                    AdditionalKeysSpec::Allow => AdditionalKeys::Allow,
+                    AdditionalKeysSpec::Deny => AdditionalKeys::Deny,
+                    AdditionalKeysSpec::Strip => AdditionalKeys::Strip,
+                    AdditionalKeysSpec::Validate(spec) => {
+                        AdditionalKeys::Validate(Box::new(spec.compile()?))
+                    }
+                },
+            },
+            ValidatorSpec::AnyOf(specs) => JsonValidator::Or(compile_all(specs)?),
+            ValidatorSpec::AllOf(specs) => JsonValidator::And(compile_all(specs)?),
+            ValidatorSpec::ExactlyOne(specs) => JsonValidator::ExactlyOne(compile_all(specs)?),
+            ValidatorSpec::Not(spec) => JsonValidator::Not(Box::new(spec.compile()?)),
+            ValidatorSpec::Tuple(specs) => JsonValidator::Tuple(compile_all(specs)?),
+        })
+    }
+}
+
+///Reads ValidatorSpecs from JSON, keeping track of which keys of each object have been used so
+///that misspelt keys are reported rather than silently ignored
+struct SpecReader<'a> {
+    obj: &'a HashMap<String, JsonValue>,
+    path: &'a str,
+    used: Vec<&'static str>,
+}
+
+impl<'a> SpecReader<'a> {
+    fn error<T>(path: &str, message: String) -> Result<T, SchemaError> {
+        Err(SchemaError {
+            path: path.to_owned(),
+            message,
+        })
+    }
+
+    fn get(&mut self, key: &'static str) -> Option<&'a JsonValue> {
+        self.used.push(key);
+        self.obj.get(key)
+    }
+
+    fn key_path(&self, key: &str) -> String {
+        ValidationError::child_path(self.path, key)
+    }
+
+    fn count(&mut self, key: &'static str) -> Result<Option<u64>, SchemaError> {
+        match self.get(key) {
+            None => Ok(None),
+            Some(JsonValue::Number(n)) if *n >= 0_f64 && n.fract() == 0_f64 => Ok(Some(*n as u64)),
+            Some(_) => Self::error(
+                &self.key_path(key),
+                format!("{} must be a non-negative integer", key),
+            ),
+        }
+    }
+
+    ///Reads an integer bound, which may be written as a string so that it keeps its precision
+    fn integer(&mut self, key: &'static str) -> Result<Option<u64>, SchemaError> {
+        match self.get(key) {
+            None => Ok(None),
+            Some(JsonValue::Number(n)) if *n >= 0_f64 && n.fract() == 0_f64 => Ok(Some(*n as u64)),
+            Some(JsonValue::String(s)) if s.parse::<u64>().is_ok() => Ok(s.parse::<u64>().ok()),
+            Some(_) => Self::error(
+                &self.key_path(key),
+                format!("{} must be a non-negative integer", key),
+            ),
+        }
+    }
+
+    fn number(&mut self, key: &'static str) -> Result<Option<f64>, SchemaError> {
+        match self.get(key) {
+            None => Ok(None),
+            Some(JsonValue::Number(n)) => Ok(Some(*n)),
+            Some(_) => Self::error(&self.key_path(key), format!("{} must be a number", key)),
+        }
+    }
+
+    fn flag(&mut self, key: &'static str) -> Result<bool, SchemaError> {
+        match self.get(key) {
+            None => Ok(false),
+            Some(JsonValue::Boolean(b)) => Ok(*b),
+            Some(_) => Self::error(&self.key_path(key), format!("{} must be a boolean", key)),
+        }
+    }
+
+    fn spec(&mut self, key: &'static str) -> Result<ValidatorSpec, SchemaError> {
+        match self.get(key) {
+            None => Self::error(self.path, format!("missing {}", key)),
+            Some(value) => Self::read(value, &self.key_path(key)),
+        }
+    }
+
+    fn specs(&mut self, key: &'static str) -> Result<Vec<ValidatorSpec>, SchemaError> {
+        let path = self.key_path(key);
+        match self.get(key) {
+            Some(JsonValue::Array(arr)) => arr
+                .iter()
+                .enumerate()
+                .map(|(i, x)| Self::read(x, &ValidationError::child_path(&path, &i.to_string())))
+                .collect(),
+            Some(_) => Self::error(&path, format!("{} must be an array", key)),
+            None => Self::error(self.path, format!("missing {}", key)),
+        }
+    }
+
+    fn values(&mut self, key: &'static str) -> Result<Vec<JsonValue>, SchemaError> {
+        match self.get(key) {
+            Some(JsonValue::Array(arr)) => Ok(arr.clone()),
+            Some(_) => Self::error(&self.key_path(key), format!("{} must be an array", key)),
+            None => Self::error(self.path, format!("missing {}", key)),
+        }
+    }
+
+    fn fields(&mut self) -> Result<Vec<FieldSpec>, SchemaError> {
+        let path = self.key_path("fields");
+        let arr = match self.get("fields") {
+            None => return Ok(vec![]),
+            Some(JsonValue::Array(arr)) => arr,
+            Some(_) => return Self::error(&path, "fields must be an array".to_owned()),
+        };
+        let mut fields = vec![];
+        for (i, x) in arr.iter().enumerate() {
+            let path = ValidationError::child_path(&path, &i.to_string());
+            let obj = match x {
+                JsonValue::Object(obj) => obj,
+                _ => return Self::error(&path, "a field must be an object".to_owned()),
+            };
+            let mut field = SpecReader {
+                obj,
+                path: &path,
+                used: vec![],
+            };
+            let name = match field.get("name") {
+                Some(JsonValue::String(name)) => name.clone(),
+                _ => return Self::error(&path, "a field must have a string name".to_owned()),
+            };
+            let required = field.flag("required")?;
+            let spec = field.spec("validator")?;
+            let default = field.get("default").cloned();
+            field.finish()?;
+            fields.push(FieldSpec {
+                name,
+                spec,
+                required,
+                default,
+            });
+        }
+        Ok(fields)
+    }
+
+    fn finish(self) -> Result<(), SchemaError> {
+        let mut keys = self.obj.keys().collect::<Vec<_>>();
+        keys.sort();
+        match keys
+            .into_iter()
+            .find(|key| !self.used.contains(&key.as_str()))
+        {
+            Some(key) => Self::error(&self.key_path(key), format!("unexpected key {:?}", key)),
+            None => Ok(()),
+        }
+    }
+
+    fn read(input: &JsonValue, path: &str) -> Result<ValidatorSpec, SchemaError> {
+        let obj = match input {
+            JsonValue::Object(obj) => obj,
+            _ => return Self::error(path, "a validator spec must be an object".to_owned()),
+        };
+        let mut reader = SpecReader {
+            obj,
+            path,
+            used: vec![],
+        };
+        let name = match reader.get("type") {
+            Some(JsonValue::String(name)) => name.as_str(),
+            _ => return Self::error(path, "a validator spec must have a string type".to_owned()),
+        };
+        let spec = match name {
+            "any" => ValidatorSpec::Any,
+            "null" => ValidatorSpec::Null,
+            "boolean" => ValidatorSpec::Boolean,
+            "number" => ValidatorSpec::Number {
+                min: reader.number("min")?,
+                max: reader.number("max")?,
+            },
+            "integer" => ValidatorSpec::Integer {
+                min: reader.integer("min")?,
+                max: reader.integer("max")?,
+            },
+            "string" => {
+                let min_length = reader.count("min_length")?.map(|x| x as usize);
+                let max_length = reader.count("max_length")?.map(|x| x as usize);
+                let pattern = match reader.get("pattern") {
+                    None => None,
+                    Some(JsonValue::String(pattern)) => {
+                        if let Err(message) = Regex::new(pattern) {
+                            return Self::error(&reader.key_path("pattern"), message);
+                        }
+                        Some(pattern.clone())
+                    }
+                    Some(_) => {
+                        return Self::error(
+                            &reader.key_path("pattern"),
+                            "pattern must be a string".to_owned(),
+                        );
+                    }
+                };
+                ValidatorSpec::String {
+                    min_length,
+                    max_length,
+                    pattern,
+                }
+            }
+            "datetime" => match reader.get("formats") {
+                None => ValidatorSpec::DateTime,
+                Some(JsonValue::Array(arr)) if !arr.is_empty() => {
+                    let path = reader.key_path("formats");
+                    let mut formats = vec![];
+                    for (i, x) in arr.iter().enumerate() {
+                        match x.get_string().as_deref().and_then(format_from_name) {
+                            Some(format) => formats.push(format),
+                            None => {
+                                return Self::error(
+                                    &ValidationError::child_path(&path, &i.to_string()),
+                                    format!("unknown date format {}", x.stringify()),
+                                );
+                            }
+                        }
+                    }
+                    ValidatorSpec::DateTimeWith(formats)
+                }
+                Some(_) => {
+                    return Self::error(
+                        &reader.key_path("formats"),
+                        "formats must be a non-empty array".to_owned(),
+                    );
+                }
+            },
+            "date" => ValidatorSpec::Date,
+            "time" => ValidatorSpec::Time,
+            "duration" => ValidatorSpec::Duration,
+            "enum" => ValidatorSpec::Enum(reader.values("values")?),
+            "const" => match reader.get("value") {
+                Some(value) => ValidatorSpec::Const(value.clone()),
+                None => return Self::error(path, "missing value".to_owned()),
+            },
+            "array" => ValidatorSpec::Array {
+                items: Box::new(reader.spec("items")?),
+                min_items: reader.count("min_items")?.map(|x| x as usize),
+                max_items: reader.count("max_items")?.map(|x| x as usize),
+                unique_items: reader.flag("unique_items")?,
+                strict: reader.flag("strict")?,
+            },
+            "object" => {
+                let fields = reader.fields()?;
+                let additional_keys = match reader.get("additional_keys") {
+                    None => AdditionalKeysSpec::Allow,
+                    Some(JsonValue::String(s)) if s == "allow" => AdditionalKeysSpec::Allow,
+                    Some(JsonValue::String(s)) if s == "deny" => AdditionalKeysSpec::Deny,
+                    Some(JsonValue::String(s)) if s == "strip" => AdditionalKeysSpec::Strip,
+                    Some(value @ JsonValue::Object(_)) => AdditionalKeysSpec::Validate(Box::new(
+                        Self::read(value, &reader.key_path("additional_keys"))?,
+                    )),
+                    Some(_) => {
+                        return Self::error(
+                            &reader.key_path("additional_keys"),
+                            "additional_keys must be \"allow\", \"deny\", \"strip\" or a validator spec"
+                                .to_owned(),
+                        );
+                    }
+                };
+                ValidatorSpec::Object {
+                    fields,
+                    additional_keys,
+                }
+            }
+            "any_of" => ValidatorSpec::AnyOf(reader.specs("validators")?),
+            "all_of" => ValidatorSpec::AllOf(reader.specs("validators")?),
+            "exactly_one" => ValidatorSpec::ExactlyOne(reader.specs("validators")?),
+            "not" => ValidatorSpec::Not(Box::new(reader.spec("validator")?)),
+            "tuple" => ValidatorSpec::Tuple(reader.specs("validators")?),
+            other => {
+                return Self::error(
+                    &ValidationError::child_path(path, "type"),
+                    format!("unknown validator type {:?}", other),
+                );
+            }
+        };
+        reader.finish()?;
+        Ok(spec)
+    }
+}
+
+///Every non-negative integer up to 2^53 can be held exactly by an f64
+const MAX_EXACT_INTEGER: u64 = 1 << 53;
+
+fn format_name(format: DateFormat) -> &'static str {
+    match format {
+        DateFormat::Iso8601 => "iso8601",
+        DateFormat::Rfc3339 => "rfc3339",
+        DateFormat::UnixSeconds => "unix_seconds",
+        DateFormat::UnixMillis => "unix_millis",
+        DateFormat::Rfc2822 => "rfc2822",
+        DateFormat::AspNet => "asp_net",
+    }
+}
+
+fn format_from_name(name: &str) -> Option<DateFormat> {
+    [
+        DateFormat::Iso8601,
+        DateFormat::Rfc3339,
+        DateFormat::UnixSeconds,
+        DateFormat::UnixMillis,
+        DateFormat::Rfc2822,
+        DateFormat::AspNet,
+    ]
+    .into_iter()
+    .find(|format| format_name(*format) == name)
+}
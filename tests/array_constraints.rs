@@ -0,0 +1,94 @@
+use jasper::{ArrayConstraints, Contains, JsonValidator, JsonValue};
+
+fn parse(input: &str) -> JsonValue {
+    JsonValue::parse(input.as_bytes().to_vec()).unwrap()
+}
+
+fn messages(validator: &JsonValidator, input: &JsonValue) -> Vec<(String, String)> {
+    match validator.validate_detailed(input) {
+        Ok(()) => vec![],
+        Err(errors) => errors.into_iter().map(|e| (e.path, e.message)).collect(),
+    }
+}
+
+#[test]
+fn checks_lengths_and_items() {
+    let validator = JsonValidator::ArrayOf(ArrayConstraints {
+        min_items: Some(1),
+        max_items: Some(3),
+        ..ArrayConstraints::new(JsonValidator::Number(|n| *n >= 0.0))
+    });
+    assert!(validator.validate(&parse("[0, 1, 2]")));
+    assert_eq!(
+        messages(&validator, &parse("[]")),
+        [(
+            String::new(),
+            "expected at least 1 items, found 0".to_owned()
+        )]
+    );
+    assert_eq!(
+        messages(&validator, &parse("[1, 2, 3, -4]")),
+        [
+            (
+                String::new(),
+                "expected at most 3 items, found 4".to_owned()
+            ),
+            ("/3".to_owned(), "number -4 was rejected".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn finds_duplicates() {
+    let validator = JsonValidator::ArrayOf(ArrayConstraints {
+        unique_items: true,
+        ..ArrayConstraints::new(JsonValidator::RubberStamp)
+    });
+    assert!(validator.validate(&parse(r#"[1, "1", [1], {"a": 1}]"#)));
+    assert_eq!(
+        messages(&validator, &parse(r#"[{"a": 1}, 2, {"a": 1}, 2]"#)),
+        [
+            ("/2".to_owned(), "duplicate item {\"a\": 1}".to_owned()),
+            ("/3".to_owned(), "duplicate item 2".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn counts_matching_items() {
+    let validator = JsonValidator::ArrayOf(ArrayConstraints {
+        contains: Some(Contains {
+            validator: Box::new(JsonValidator::Const(JsonValue::String("admin".to_owned()))),
+            min: 1,
+            max: Some(1),
+        }),
+        ..ArrayConstraints::new(JsonValidator::String(|_| true))
+    });
+    assert!(validator.validate(&parse(r#"["user", "admin"]"#)));
+    assert_eq!(
+        messages(&validator, &parse(r#"["user"]"#)),
+        [(
+            String::new(),
+            "expected between 1 and 1 matching items, found 0".to_owned()
+        )]
+    );
+    assert!(!validator.validate(&parse(r#"["admin", "admin"]"#)));
+}
+
+#[test]
+fn strict_mode_rejects_scalars() {
+    let loose = ArrayConstraints {
+        max_items: Some(1),
+        ..ArrayConstraints::new(JsonValidator::Number(|_| true))
+    };
+    assert!(JsonValidator::ArrayOf(loose.clone()).validate(&JsonValue::Number(1.0)));
+    let strict = JsonValidator::ArrayOf(ArrayConstraints {
+        strict: true,
+        ..loose
+    });
+    assert!(strict.validate(&parse("[1]")));
+    assert_eq!(
+        messages(&strict, &JsonValue::Number(1.0)),
+        [(String::new(), "value is not an array".to_owned())]
+    );
+}
@@ -0,0 +1,138 @@
+use jasper::{json, JsonDate, JsonValue, ParseOptions};
+
+#[test]
+fn round_trips_values() {
+    for value in [
+        json!(null),
+        json!(true),
+        json!(0),
+        json!(-1),
+        json!(1.5),
+        json!(0.1),
+        json!(18446744073709551615_u64),
+        json!("text"),
+        json!([1, [2, [3]], {"a": null}]),
+        json!({"b": 1, "aa": 2, "a": [true, "x"]}),
+    ] {
+        assert_eq!(JsonValue::from_cbor(&value.to_cbor()).unwrap(), value);
+    }
+    let zero = JsonValue::from_cbor(&JsonValue::Number(-0.0).to_cbor()).unwrap();
+    assert!(matches!(zero, JsonValue::Number(n) if n == 0.0 && n.is_sign_negative()));
+}
+
+#[test]
+fn encodes_deterministically() {
+    assert_eq!(json!(10).to_cbor(), [0x0A]);
+    assert_eq!(json!(-500).to_cbor(), [0x39, 0x01, 0xF3]);
+    assert_eq!(json!(1.5).to_cbor(), [0xF9, 0x3E, 0x00]);
+    assert_eq!(json!(100000.5).to_cbor(), [0xFA, 0x47, 0xC3, 0x50, 0x40]);
+    assert_eq!(
+        json!({"b": 1, "aa": 2, "a": 3}).to_cbor(),
+        [0xA3, 0x61, b'a', 0x03, 0x61, b'b', 0x01, 0x62, b'a', b'a', 0x02]
+    );
+}
+
+#[test]
+fn converts_tags_and_byte_strings() {
+    let epoch = [0xC1, 0x1A, 0x66, 0x32, 0x47, 0x5C];
+    assert_eq!(
+        JsonValue::from_cbor(&epoch).unwrap(),
+        json!("2024-05-01T13:45:00Z")
+    );
+    let date = JsonDate::from_rfc3339("2024-05-01T13:45:00+02:00").unwrap();
+    assert_eq!(JsonDate::from_cbor(&date.to_cbor()).unwrap(), date);
+    assert_eq!(
+        JsonDate::from_cbor(&epoch).unwrap().to_string(),
+        "2024-05-01T13:45:00Z"
+    );
+    assert_eq!(
+        JsonValue::from_cbor(&[0x43, 0xFB, 0xFF, 0x00]).unwrap(),
+        json!("-_8A")
+    );
+    assert_eq!(
+        JsonValue::from_cbor(&[0xD6, 0x43, 0xFB, 0xFF, 0x00]).unwrap(),
+        json!("+/8A")
+    );
+    assert_eq!(
+        JsonValue::from_cbor(&[0xD7, 0x42, 0xAB, 0x01]).unwrap(),
+        json!("ab01")
+    );
+    assert_eq!(
+        JsonValue::from_cbor(&[0xA1, 0x01, 0xF7]).unwrap(),
+        json!({"1": null})
+    );
+}
+
+#[test]
+fn rejects_datetimes_hidden_behind_other_tags() {
+    for input in [
+        &[0xC0, 0xD8, 0x20, 0x80][..],
+        &[0xC0, 0xD6, 0x41, 0x00],
+        &[0xC1, 0xF9, 0x7E, 0x00],
+        &[0xC1, 0xF9, 0x7C, 0x00],
+        &[0xC0, 0x61, b'x'],
+        &[0xC1, 0x61, b'1'],
+        &[0xD8, 0x20, 0xC0, 0x80],
+    ] {
+        let e = JsonValue::from_cbor(input).unwrap_err();
+        assert!(e.message.starts_with("tag "), "{:?}: {}", input, e);
+        assert!(JsonDate::from_cbor(input).is_err());
+    }
+    assert!(JsonDate::from_cbor(&[0x61, b'x']).is_err());
+    assert!(JsonDate::from_cbor(&[]).is_err());
+}
+
+#[test]
+fn reports_malformed_input_with_offsets() {
+    for (input, offset, message) in [
+        (&[][..], 0, "unexpected end of input"),
+        (&[0x82, 0x01], 0, "length exceeds the input"),
+        (&[0x9F, 0x01], 2, "unexpected end of input"),
+        (&[0x1C], 0, "reserved additional information"),
+        (&[0x62, 0xFF, 0xFE], 0, "text string is not valid UTF-8"),
+        (
+            &[0x9B, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF],
+            0,
+            "length exceeds the input",
+        ),
+        (
+            &[0xA1, 0x80, 0x01],
+            1,
+            "map keys must be strings or integers",
+        ),
+        (&[0xA2, 0x01, 0x02, 0x01, 0x03], 3, "duplicate map key"),
+        (&[0x01, 0x02], 1, "unexpected bytes after the value"),
+        (&[0xFF], 0, "unexpected break"),
+    ] {
+        let e = JsonValue::from_cbor(input).unwrap_err();
+        assert_eq!(
+            (e.offset, e.message.as_str()),
+            (offset, message),
+            "{:?}",
+            input
+        );
+    }
+}
+
+#[test]
+fn limits_depth_and_size() {
+    let deep = vec![0x81; 100_000];
+    assert_eq!(
+        JsonValue::from_cbor(&deep).unwrap_err().message,
+        "nesting is too deep"
+    );
+    let mut tags = [0xD8, 0x20].repeat(50_000);
+    tags.push(0x01);
+    assert_eq!(JsonValue::from_cbor(&tags).unwrap(), json!(1));
+    let options = ParseOptions {
+        max_size: Some(2),
+        ..ParseOptions::default()
+    };
+    assert!(JsonValue::from_cbor_with(&[0x81, 0x01], &options).is_ok());
+    assert_eq!(
+        JsonValue::from_cbor_with(&[0x82, 0x01, 0x02], &options)
+            .unwrap_err()
+            .message,
+        "input is too large"
+    );
+}
@@ -0,0 +1,100 @@
+use jasper::{JsonValidator, JsonValue};
+
+fn parse(input: &str) -> JsonValue {
+    JsonValue::parse(input.as_bytes().to_vec()).unwrap()
+}
+
+fn first_error(validator: &JsonValidator, input: &JsonValue) -> (String, &'static str, String) {
+    let e = validator.validate_detailed(input).unwrap_err().remove(0);
+    (e.path, e.validator, e.message)
+}
+
+#[test]
+fn and_reports_every_failing_member() {
+    let validator = JsonValidator::And(vec![
+        JsonValidator::Number(|n| *n > 0.0),
+        JsonValidator::Number(|n| *n < 10.0),
+        JsonValidator::Not(Box::new(JsonValidator::Const(JsonValue::Number(5.0)))),
+    ]);
+    assert!(validator.validate(&JsonValue::Number(3.0)));
+    assert!(!validator.validate(&JsonValue::Number(5.0)));
+    assert_eq!(
+        validator
+            .validate_detailed(&JsonValue::Number(-20.0))
+            .unwrap_err()
+            .len(),
+        1
+    );
+    assert!(JsonValidator::And(vec![]).validate(&JsonValue::Null));
+}
+
+#[test]
+fn not_and_exactly_one() {
+    let not_null = JsonValidator::Not(Box::new(JsonValidator::Null));
+    assert!(not_null.validate(&JsonValue::Number(0.0)));
+    assert_eq!(
+        first_error(&not_null, &JsonValue::Null),
+        (
+            String::new(),
+            "Not",
+            "value matched a validator it must not match".to_owned()
+        )
+    );
+
+    let one = JsonValidator::ExactlyOne(vec![
+        JsonValidator::Number(|n| *n > 0.0),
+        JsonValidator::Number(|n| *n > 10.0),
+    ]);
+    assert!(one.validate(&JsonValue::Number(5.0)));
+    assert_eq!(
+        first_error(&one, &JsonValue::Number(50.0)).2,
+        "value matched 2 of the 2 alternatives"
+    );
+    assert_eq!(
+        first_error(&one, &JsonValue::Number(-1.0)).2,
+        "value matched 0 of the 2 alternatives"
+    );
+}
+
+#[test]
+fn const_and_enum_compare_without_coercion() {
+    let version = JsonValidator::Const(parse(r#"{"major": 1}"#));
+    assert!(version.validate(&parse(r#"{"major": 1}"#)));
+    assert_eq!(
+        first_error(&version, &parse(r#"{"major": "1"}"#)).2,
+        "expected {\"major\": 1}, found {\"major\": \"1\"}"
+    );
+
+    let colour = JsonValidator::Enum(vec![
+        JsonValue::String("red".to_owned()),
+        JsonValue::String("green".to_owned()),
+        JsonValue::Number(1.0),
+    ]);
+    assert!(colour.validate(&JsonValue::String("green".to_owned())));
+    assert!(colour.validate(&JsonValue::Number(1.0)));
+    assert!(!colour.validate(&JsonValue::String("1".to_owned())));
+    assert_eq!(
+        first_error(&colour, &JsonValue::String("blue".to_owned())),
+        (
+            String::new(),
+            "Enum",
+            "\"blue\" is not one of the allowed values".to_owned()
+        )
+    );
+}
+
+#[test]
+fn tuples_check_length_and_position() {
+    let point = JsonValidator::Tuple(vec![
+        JsonValidator::String(|_| true),
+        JsonValidator::Number(|_| true),
+        JsonValidator::Number(|n| *n >= 0.0),
+    ]);
+    assert!(point.validate(&parse(r#"["a", 1, 2]"#)));
+    assert_eq!(
+        first_error(&point, &parse(r#"["a", 1]"#)).2,
+        "expected 3 elements, found 2"
+    );
+    assert_eq!(first_error(&point, &parse(r#"["a", 1, -2]"#)).0, "/2");
+    assert_eq!(first_error(&point, &parse(r#"{"0": "a"}"#)).1, "Tuple");
+}
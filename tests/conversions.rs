@@ -0,0 +1,93 @@
+use std::collections::{BTreeMap, HashMap};
+
+use jasper::{FromJson, JsonDate, JsonValue, ToJson};
+
+fn parse(input: &str) -> JsonValue {
+    JsonValue::parse(input.as_bytes().to_vec()).unwrap()
+}
+
+#[test]
+fn round_trips_std_types() {
+    let v = vec![Some(1_u8), None, Some(3)];
+    assert_eq!(v.to_json(), parse("[1, null, 3]"));
+    assert_eq!(Vec::<Option<u8>>::from_json(&v.to_json()).unwrap(), v);
+
+    let map: BTreeMap<String, Vec<bool>> = [("a".to_owned(), vec![true, false])].into();
+    assert_eq!(map.to_json(), parse(r#"{"a": [true, false]}"#));
+    assert_eq!(
+        BTreeMap::<String, Vec<bool>>::from_json(&map.to_json()).unwrap(),
+        map
+    );
+    let map: HashMap<String, String> = [("k".to_owned(), "v".to_owned())].into();
+    assert_eq!(
+        HashMap::<String, String>::from_json(&map.to_json()).unwrap(),
+        map
+    );
+
+    assert_eq!(<[i32; 2]>::from_json(&parse("[-1, 2]")).unwrap(), [-1, 2]);
+    assert!(<[i32; 2]>::from_json(&parse("[1]")).is_err());
+    assert_eq!(
+        char::from_json(&JsonValue::String("x".to_owned())).unwrap(),
+        'x'
+    );
+    assert!(char::from_json(&JsonValue::String("xy".to_owned())).is_err());
+    assert_eq!(f64::NAN.to_json(), JsonValue::Null);
+}
+
+#[test]
+fn rejects_integers_outside_the_range_of_the_type() {
+    assert_eq!(u8::from_json(&JsonValue::Number(255.0)).unwrap(), 255);
+    assert!(u8::from_json(&JsonValue::Number(256.0)).is_err());
+    assert_eq!(i8::from_json(&JsonValue::Number(-128.0)).unwrap(), -128);
+    assert!(i8::from_json(&JsonValue::Number(-129.0)).is_err());
+    assert!(u32::from_json(&JsonValue::Number(1.5)).is_err());
+    assert!(u32::from_json(&JsonValue::Number(-1.0)).is_err());
+
+    let two_63 = JsonValue::Number(2_f64.powi(63));
+    let two_64 = JsonValue::Number(2_f64.powi(64));
+    assert!(i64::from_json(&two_63).is_err());
+    assert!(isize::from_json(&two_63).is_err());
+    assert_eq!(
+        i64::from_json(&JsonValue::Number(-(2_f64.powi(63)))).unwrap(),
+        i64::MIN
+    );
+    assert_eq!(u64::from_json(&two_63).unwrap(), 1 << 63);
+    assert!(u64::from_json(&two_64).is_err());
+    assert!(usize::from_json(&two_64).is_err());
+    assert_eq!(
+        u64::from_json(&JsonValue::Number(2_f64.powi(64) - 2048_f64)).unwrap(),
+        u64::MAX - 2047
+    );
+    assert!(u128::from_json(&two_64).is_ok());
+}
+
+#[test]
+fn rejects_floats_too_large_for_f32() {
+    assert_eq!(f32::from_json(&JsonValue::Number(1.5)).unwrap(), 1.5);
+    assert_eq!(
+        f32::from_json(&JsonValue::Number(f32::MAX as f64)).unwrap(),
+        f32::MAX
+    );
+    assert!(f32::from_json(&parse("1e39")).is_err());
+    assert!(f32::from_json(&parse("-1e39")).is_err());
+    assert_eq!(f64::from_json(&parse("1e300")).unwrap(), 1e300);
+}
+
+#[test]
+fn reports_the_path_of_nested_errors() {
+    let e = Vec::<HashMap<String, u8>>::from_json(&parse(r#"[{"a": 1}, {"b": 300}]"#)).unwrap_err();
+    assert_eq!(e.path, "/1/b");
+    assert_eq!(e.message, "expected an integer from 0 to 255, found 300");
+    let e = Option::<String>::from_json(&JsonValue::Number(1.0)).unwrap_err();
+    assert_eq!(e.message, "expected a string, found a number");
+}
+
+#[test]
+fn converts_dates_to_and_from_rfc3339_strings() {
+    let date = JsonDate::from_rfc3339("2024-05-01T13:45:00.5+02:00").unwrap();
+    assert_eq!(
+        date.to_json(),
+        JsonValue::String("2024-05-01T13:45:00.5+02:00".to_owned())
+    );
+    assert_eq!(JsonDate::from_json(&date.to_json()).unwrap(), date);
+}
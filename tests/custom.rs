@@ -0,0 +1,48 @@
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use jasper::{JsonValidator, JsonValue};
+
+fn parse(input: &str) -> JsonValue {
+    JsonValue::parse(input.as_bytes().to_vec()).unwrap()
+}
+
+#[test]
+fn closures_capture_runtime_configuration() {
+    let allowed = HashSet::from(["red".to_owned(), "green".to_owned()]);
+    let colour =
+        JsonValidator::custom(move |x| x.get_string().is_some_and(|s| allowed.contains(&s)));
+    assert!(colour.validate(&JsonValue::String("red".to_owned())));
+    assert!(!colour.validate(&JsonValue::String("blue".to_owned())));
+
+    let e = &colour
+        .validate_detailed(&parse(r#"{"colour": "blue"}"#))
+        .unwrap_err()[0];
+    assert_eq!((e.path.as_str(), e.validator), ("", "Custom"));
+    let nested = JsonValidator::Object(vec![("colour".to_owned(), colour)]);
+    let e = &nested
+        .validate_detailed(&parse(r#"{"colour": "blue"}"#))
+        .unwrap_err()[0];
+    assert_eq!((e.path.as_str(), e.validator), ("/colour", "Custom"));
+}
+
+#[test]
+fn clones_share_the_closure_across_threads() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&calls);
+    let validator = JsonValidator::custom(move |x| {
+        counter.fetch_add(1, Ordering::Relaxed);
+        x.is_null()
+    });
+    let threads = (0..4)
+        .map(|_| {
+            let validator = validator.clone();
+            std::thread::spawn(move || validator.validate(&JsonValue::Null))
+        })
+        .collect::<Vec<_>>();
+    for thread in threads {
+        assert!(thread.join().unwrap());
+    }
+    assert_eq!(calls.load(Ordering::Relaxed), 4);
+}
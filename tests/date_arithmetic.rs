@@ -0,0 +1,85 @@
+use std::collections::HashSet;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use jasper::{DateComponent, DateError, JsonDate, Weekday};
+
+#[test]
+fn builds_and_reads_dates() {
+    let date = JsonDate::new(2024, 5, 1, 13, 45, 0)
+        .unwrap()
+        .with_nanosecond(250_000_000)
+        .unwrap()
+        .with_offset(Some(120))
+        .unwrap();
+    assert_eq!((date.year(), date.month(), date.date()), (2024, 5, 1));
+    assert_eq!(
+        (date.hour(), date.minute(), date.second(), date.nanosecond()),
+        (13, 45, 0, 250_000_000)
+    );
+    assert_eq!(date.offset(), Some(120));
+    assert_eq!(date.weekday(), Weekday::Wednesday);
+    assert_eq!(date.weekday().number_from_monday(), 3);
+    assert_eq!(date.to_string(), "2024-05-01T13:45:00.25+02:00");
+
+    assert_eq!(
+        JsonDate::new(2023, 2, 29, 0, 0, 0),
+        Err(DateError::OutOfRange(DateComponent::Date))
+    );
+    assert!(date.with_nanosecond(1_000_000_000).is_err());
+    assert!(date.with_offset(Some(24 * 60)).is_err());
+}
+
+#[test]
+fn converts_unix_timestamps_and_system_time() {
+    let date = JsonDate::from_rfc3339("2024-05-01T13:45:00.25+02:00").unwrap();
+    assert_eq!(date.unix_timestamp(), 1_714_563_900);
+    assert_eq!(date.unix_timestamp_millis(), 1_714_563_900_250);
+    assert_eq!(
+        JsonDate::from_unix_timestamp_millis(1_714_563_900_250)
+            .unwrap()
+            .to_string(),
+        "2024-05-01T11:45:00.25Z"
+    );
+    assert_eq!(
+        JsonDate::from_unix_timestamp(-1).unwrap().to_string(),
+        "1969-12-31T23:59:59Z"
+    );
+    assert!(JsonDate::from_unix_timestamp(i64::MAX).is_err());
+
+    let time = SystemTime::from(date);
+    assert_eq!(
+        time.duration_since(UNIX_EPOCH).unwrap(),
+        Duration::from_millis(1_714_563_900_250)
+    );
+    let back = JsonDate::try_from(time).unwrap();
+    assert_eq!(back, date.to_offset(0).unwrap());
+    assert_eq!(back.unix_timestamp_nanos(), date.unix_timestamp_nanos());
+}
+
+#[test]
+fn adds_durations_and_measures_differences() {
+    let date = JsonDate::from_rfc3339("2024-02-28T23:30:00+01:00").unwrap();
+    let later = date + Duration::from_secs(3600);
+    assert_eq!(later.to_string(), "2024-02-29T00:30:00+01:00");
+    assert_eq!(later - Duration::from_secs(3600), date);
+    assert_eq!(later.duration_since(&date), Some(Duration::from_secs(3600)));
+    assert_eq!(date.duration_since(&later), None);
+
+    let last = JsonDate::from_rfc3339("9999-12-31T23:59:59Z").unwrap();
+    assert_eq!(last.checked_add(Duration::from_secs(1)), None);
+    let first = JsonDate::from_rfc3339("0000-01-01T00:00:00Z").unwrap();
+    assert_eq!(first.checked_sub(Duration::from_nanos(1)), None);
+}
+
+#[test]
+fn orders_by_instant() {
+    let utc = JsonDate::from_rfc3339("2024-05-01T12:00:00Z").unwrap();
+    let paris = JsonDate::from_rfc3339("2024-05-01T14:00:00+02:00").unwrap();
+    let earlier = JsonDate::from_rfc3339("2024-05-01T13:00:00+02:00").unwrap();
+    assert!(earlier < utc);
+    assert_ne!(utc, paris);
+    assert_eq!(utc.cmp(&paris).reverse(), paris.cmp(&utc));
+    assert_eq!(paris.to_offset(0).unwrap(), utc);
+    let set = HashSet::from([utc, paris, utc]);
+    assert_eq!(set.len(), 2);
+}
@@ -0,0 +1,160 @@
+use jasper::{DateFormat, JsonDate, JsonValue};
+
+fn date(input: JsonValue, format: DateFormat) -> Option<String> {
+    input.get_json_date_with(format).map(|d| d.to_string())
+}
+
+#[test]
+fn reads_unix_timestamps() {
+    assert_eq!(
+        date(JsonValue::Number(0.0), DateFormat::UnixSeconds).as_deref(),
+        Some("1970-01-01T00:00:00Z")
+    );
+    assert_eq!(
+        date(JsonValue::Number(1714571100.25), DateFormat::UnixSeconds).as_deref(),
+        Some("2024-05-01T13:45:00.25Z")
+    );
+    assert_eq!(
+        date(JsonValue::String("-1".to_owned()), DateFormat::UnixSeconds).as_deref(),
+        Some("1969-12-31T23:59:59Z")
+    );
+    assert_eq!(
+        date(JsonValue::Number(1714571100250.0), DateFormat::UnixMillis).as_deref(),
+        Some("2024-05-01T13:45:00.25Z")
+    );
+}
+
+#[test]
+fn rejects_timestamps_that_are_not_instants() {
+    for n in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY, 1e300] {
+        assert_eq!(date(JsonValue::Number(n), DateFormat::UnixSeconds), None);
+        assert_eq!(date(JsonValue::Number(n), DateFormat::UnixMillis), None);
+    }
+    assert_eq!(
+        date(JsonValue::String("NaN".to_owned()), DateFormat::UnixSeconds),
+        None
+    );
+    assert_eq!(
+        date(
+            JsonValue::String("soon".to_owned()),
+            DateFormat::UnixSeconds
+        ),
+        None
+    );
+    assert_eq!(
+        date(JsonValue::Boolean(true), DateFormat::UnixSeconds),
+        None
+    );
+}
+
+#[test]
+fn reads_rfc_2822_and_asp_net_strings() {
+    assert_eq!(
+        date(
+            JsonValue::String("Wed, 01 May 2024 13:45:00 +0200".to_owned()),
+            DateFormat::Rfc2822
+        )
+        .as_deref(),
+        Some("2024-05-01T13:45:00+02:00")
+    );
+    assert_eq!(
+        date(
+            JsonValue::String("Thu, 01 May 2024 13:45:00 +0200".to_owned()),
+            DateFormat::Rfc2822
+        ),
+        None
+    );
+    assert_eq!(
+        date(
+            JsonValue::String("/Date(1714571100000)/".to_owned()),
+            DateFormat::AspNet
+        )
+        .as_deref(),
+        Some("2024-05-01T13:45:00Z")
+    );
+    assert_eq!(
+        date(
+            JsonValue::String("/Date(1714571100000+0200)/".to_owned()),
+            DateFormat::AspNet
+        )
+        .as_deref(),
+        Some("2024-05-01T15:45:00+02:00")
+    );
+    assert_eq!(
+        date(
+            JsonValue::String("/Date(abc)/".to_owned()),
+            DateFormat::AspNet
+        ),
+        None
+    );
+}
+
+#[test]
+fn rfc_3339_requires_an_offset() {
+    assert!(JsonDate::from_rfc3339("2024-05-01T13:45:00Z").is_ok());
+    assert!(JsonDate::from_rfc3339("2024-05-01T13:45:00").is_err());
+    assert_eq!(
+        date(
+            JsonValue::String("2024-05-01T13:45:00".to_owned()),
+            DateFormat::Rfc3339
+        ),
+        None
+    );
+    assert!(date(
+        JsonValue::String("2024-05-01T13:45:00".to_owned()),
+        DateFormat::Iso8601
+    )
+    .is_some());
+}
+
+#[test]
+fn writes_each_format_and_reads_it_back() {
+    let date = JsonDate::from_rfc3339("2024-05-01T13:45:00.5+02:00").unwrap();
+    assert_eq!(
+        date.to_json_with(DateFormat::UnixSeconds),
+        JsonValue::Number(1714563900.5)
+    );
+    assert_eq!(
+        date.to_json_with(DateFormat::UnixMillis),
+        JsonValue::Number(1714563900500.0)
+    );
+    assert_eq!(
+        date.to_json_with(DateFormat::Rfc2822),
+        JsonValue::String("Wed, 01 May 2024 13:45:00 +0200".to_owned())
+    );
+    assert_eq!(
+        date.to_json_with(DateFormat::AspNet),
+        JsonValue::String("/Date(1714563900500+0200)/".to_owned())
+    );
+    for format in [
+        DateFormat::Iso8601,
+        DateFormat::Rfc3339,
+        DateFormat::UnixSeconds,
+        DateFormat::UnixMillis,
+        DateFormat::AspNet,
+    ] {
+        let read = date
+            .to_json_with(format)
+            .get_json_date_with(format)
+            .unwrap();
+        assert_eq!(read.unix_timestamp_nanos(), date.unix_timestamp_nanos());
+    }
+}
+
+#[test]
+fn unix_millis_drop_fractions_of_a_millisecond() {
+    let date = JsonDate::from_rfc3339("2024-05-01T13:45:00.1234567Z").unwrap();
+    assert_eq!(
+        date.to_json_with(DateFormat::UnixMillis),
+        JsonValue::Number(1714571100123.0)
+    );
+    let before_epoch = JsonDate::from_rfc3339("1969-12-31T23:59:59.9995Z").unwrap();
+    assert_eq!(
+        before_epoch.to_json_with(DateFormat::UnixMillis),
+        JsonValue::Number(-1.0)
+    );
+    assert_eq!(
+        before_epoch.to_json_with(DateFormat::AspNet),
+        JsonValue::String("/Date(-1)/".to_owned())
+    );
+}
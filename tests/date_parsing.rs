@@ -0,0 +1,83 @@
+use jasper::{DateComponent, DateError, JsonDate};
+
+fn iso(input: &str) -> String {
+    JsonDate::from_iso8601(input).unwrap().to_string()
+}
+
+#[test]
+fn keeps_fractions_and_offsets() {
+    let date = JsonDate::from_rfc3339("2024-05-01T13:45:00.123456789+05:30").unwrap();
+    assert_eq!(date.nanosecond(), 123_456_789);
+    assert_eq!(date.offset(), Some(330));
+    assert_eq!(date.to_string(), "2024-05-01T13:45:00.123456789+05:30");
+    assert_eq!(
+        JsonDate::from_rfc3339("2024-05-01t13:45:00.500z")
+            .unwrap()
+            .to_string(),
+        "2024-05-01T13:45:00.5Z"
+    );
+    assert_eq!(
+        JsonDate::from_rfc3339("2024-05-01T13:45:00-00:00")
+            .unwrap()
+            .offset(),
+        Some(0)
+    );
+    let local = JsonDate::from_iso8601("2024-05-01T13:45:00").unwrap();
+    assert_eq!(local.offset(), None);
+    assert_eq!(local.to_string(), "2024-05-01T13:45:00");
+}
+
+#[test]
+fn reads_the_other_iso_8601_forms() {
+    for input in [
+        "2024-05-01 13:45:00Z",
+        "2024-W18-3T13:45:00Z",
+        "2024-122T13:45Z",
+        "20240501T134500Z",
+        "2024-05-01T13Z",
+    ] {
+        let expected = if input.ends_with("13Z") {
+            "2024-05-01T13:00:00Z"
+        } else {
+            "2024-05-01T13:45:00Z"
+        };
+        assert_eq!(iso(input), expected, "{}", input);
+        assert!(JsonDate::from_rfc3339(input).is_err(), "{}", input);
+    }
+    assert_eq!(iso("2024-05-01T13:45:00,5Z"), "2024-05-01T13:45:00.5Z");
+    assert_eq!(iso("2024-05-01T13:45:00+0530"), "2024-05-01T13:45:00+05:30");
+    assert_eq!(iso("2020-W53-1"), "2020-12-28T00:00:00");
+    assert_eq!(iso("2024-366T00:00Z"), "2024-12-31T00:00:00Z");
+    assert_eq!(
+        JsonDate::from_str("2024-05-01 13:45:00Z").map(|d| d.to_string()),
+        Some("2024-05-01T13:45:00Z".to_owned())
+    );
+}
+
+#[test]
+fn rfc_3339_is_strict() {
+    for (input, error) in [
+        (
+            "2024-05-01T13:45:00",
+            DateError::Malformed(DateComponent::Offset),
+        ),
+        (
+            "2024-05-01T13:45:00,5Z",
+            DateError::Malformed(DateComponent::Offset),
+        ),
+        (
+            "2024-05-01T13:45:00+0530",
+            DateError::Malformed(DateComponent::Offset),
+        ),
+        (
+            "2024-05-01 13:45:00Z",
+            DateError::Malformed(DateComponent::Hour),
+        ),
+        (
+            "2024-05-01T12:00:00Zjunk",
+            DateError::TrailingCharacters(20),
+        ),
+    ] {
+        assert_eq!(JsonDate::from_rfc3339(input), Err(error), "{}", input);
+    }
+}
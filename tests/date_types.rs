@@ -0,0 +1,128 @@
+use std::time::Duration;
+
+use jasper::{
+    DateComponent, DateError, JsonDate, JsonDateOnly, JsonDuration, JsonTime, JsonValidator,
+    JsonValue,
+};
+
+fn date_only(input: &str) -> Result<String, DateError> {
+    JsonDateOnly::from_iso8601(input).map(|d| d.to_string())
+}
+
+fn time(input: &str) -> Result<String, DateError> {
+    JsonTime::from_iso8601(input).map(|t| t.to_string())
+}
+
+fn duration(input: &str) -> Result<String, DateError> {
+    JsonDuration::from_iso8601(input).map(|d| d.to_string())
+}
+
+#[test]
+fn parses_dates_without_times() {
+    for input in ["2024-05-01", "20240501", "2024-W18-3", "2024122"] {
+        assert_eq!(date_only(input).as_deref(), Ok("2024-05-01"), "{}", input);
+    }
+    assert_eq!(date_only("2024-05").as_deref(), Ok("2024-05-01"));
+    assert_eq!(
+        date_only("2024-02-30"),
+        Err(DateError::OutOfRange(DateComponent::Date))
+    );
+    assert_eq!(
+        date_only("2024-05-01T00:00"),
+        Err(DateError::TrailingCharacters(10))
+    );
+    let date = JsonDateOnly::new(2024, 5, 1).unwrap();
+    assert_eq!((date.year(), date.month(), date.date()), (2024, 5, 1));
+    assert!(JsonDateOnly::new(2024, 5, 32).is_err());
+}
+
+#[test]
+fn parses_times_of_day() {
+    for input in ["13:45:00", "13:45", "134500", "T13:45:00"] {
+        assert_eq!(time(input).as_deref(), Ok("13:45:00"), "{}", input);
+    }
+    assert_eq!(time("13:45:00.5+02:00").as_deref(), Ok("13:45:00.5+02:00"));
+    assert_eq!(time("23:59:60").as_deref(), Ok("23:59:60"));
+    assert_eq!(
+        time("24:00:00"),
+        Err(DateError::OutOfRange(DateComponent::Hour))
+    );
+    let t = JsonTime::new(13, 45, 0)
+        .unwrap()
+        .with_offset(Some(-300))
+        .unwrap();
+    assert_eq!(t.to_string(), "13:45:00-05:00");
+    assert!(JsonTime::new(13, 60, 0).is_err());
+}
+
+#[test]
+fn splits_and_joins_datetimes() {
+    let datetime = JsonDate::from_rfc3339("2024-05-01T13:45:00.5+02:00").unwrap();
+    assert_eq!(datetime.to_date_only().to_string(), "2024-05-01");
+    assert_eq!(datetime.to_time().to_string(), "13:45:00.5+02:00");
+    assert_eq!(
+        datetime.to_date_only().and_time(datetime.to_time()),
+        datetime
+    );
+}
+
+#[test]
+fn parses_durations_component_by_component() {
+    for input in ["P3DT4H", "P1Y2M", "P2W", "PT30M", "PT1.5S"] {
+        assert_eq!(duration(input).as_deref(), Ok(input));
+    }
+    assert_eq!(duration("PT1,5S").as_deref(), Ok("PT1.5S"));
+    assert_eq!(duration("P0D").as_deref(), Ok("PT0S"));
+    for input in ["P", "PT", "P1H", "P1DT", "P1D2Y", "P1.5D", "-P1D"] {
+        assert_eq!(
+            duration(input),
+            Err(DateError::Malformed(DateComponent::Duration)),
+            "{}",
+            input
+        );
+    }
+    let d = JsonDuration::from_iso8601("P1WT1.5S").unwrap();
+    assert_eq!(
+        (d.weeks(), d.seconds(), d.nanosecond()),
+        (1, 1, 500_000_000)
+    );
+    assert_eq!(d.to_std(), Some(Duration::from_millis(604_801_500)));
+    assert_eq!(JsonDuration::new(1, 0, 0, 0, 0, 0).to_std(), None);
+    assert_eq!(
+        JsonDuration::from_std(Duration::from_secs(90_061))
+            .unwrap()
+            .to_string(),
+        "P1DT1H1M1S"
+    );
+    assert_ne!(
+        JsonDuration::from_iso8601("P1D").unwrap(),
+        JsonDuration::from_iso8601("PT24H").unwrap()
+    );
+}
+
+#[test]
+fn reads_and_validates_json_values() {
+    assert_eq!(
+        JsonValue::String("2024-05-01".to_owned()).get_json_date_only(),
+        Some(JsonDateOnly::new(2024, 5, 1).unwrap())
+    );
+    assert_eq!(
+        JsonValue::String("13:45".to_owned()).get_json_time(),
+        Some(JsonTime::new(13, 45, 0).unwrap())
+    );
+    assert_eq!(
+        JsonValue::String("P1D".to_owned()).get_json_duration(),
+        Some(JsonDuration::new(0, 0, 1, 0, 0, 0))
+    );
+    assert_eq!(JsonValue::Number(5.0).get_json_date_only(), None);
+
+    let date = JsonValidator::Date(|d| d.year() >= 2000);
+    assert!(date.validate(&JsonValue::String("2024-05-01".to_owned())));
+    assert!(!date.validate(&JsonValue::String("1999-05-01".to_owned())));
+    assert!(!date.validate(&JsonValue::String("2024-02-30".to_owned())));
+    assert!(JsonValidator::Time(|t| t.hour() < 12).validate(&JsonValue::String("09:00".to_owned())));
+    assert!(!JsonValidator::Time(|_| true).validate(&JsonValue::String("P1D".to_owned())));
+    assert!(
+        JsonValidator::Duration(|d| d.days() == 1).validate(&JsonValue::String("P1D".to_owned()))
+    );
+}
@@ -0,0 +1,77 @@
+use jasper::{DateComponent, DateError, JsonDate, JsonValidator, JsonValue};
+
+fn error(input: &str) -> DateError {
+    JsonDate::from_rfc3339(input).unwrap_err()
+}
+
+#[test]
+fn rejects_dates_that_are_not_on_the_calendar() {
+    assert!(JsonDate::from_rfc3339("2024-02-29T00:00:00Z").is_ok());
+    assert!(JsonDate::from_rfc3339("2000-02-29T00:00:00Z").is_ok());
+    for (input, component) in [
+        ("2023-02-29T00:00:00Z", DateComponent::Date),
+        ("1900-02-29T00:00:00Z", DateComponent::Date),
+        ("2024-04-31T00:00:00Z", DateComponent::Date),
+        ("2024-05-00T00:00:00Z", DateComponent::Date),
+        ("2024-13-01T00:00:00Z", DateComponent::Month),
+        ("2024-00-01T00:00:00Z", DateComponent::Month),
+        ("2024-05-01T24:00:00Z", DateComponent::Hour),
+        ("2024-05-01T12:60:00Z", DateComponent::Minute),
+        ("2024-05-01T12:00:61Z", DateComponent::Second),
+        ("2024-05-01T12:00:00+24:00", DateComponent::Offset),
+    ] {
+        assert_eq!(error(input), DateError::OutOfRange(component), "{}", input);
+    }
+    assert_eq!(
+        JsonDate::from_iso8601("2021-W53-1"),
+        Err(DateError::OutOfRange(DateComponent::Week))
+    );
+    assert_eq!(
+        JsonDate::from_iso8601("2023-366"),
+        Err(DateError::OutOfRange(DateComponent::DayOfYear))
+    );
+}
+
+#[test]
+fn allows_leap_seconds_only_at_the_end_of_a_minute() {
+    assert_eq!(
+        JsonDate::from_rfc3339("2016-12-31T23:59:60Z")
+            .unwrap()
+            .second(),
+        60
+    );
+    assert!(JsonDate::new(2016, 12, 31, 23, 59, 60).is_ok());
+    assert_eq!(
+        error("2024-05-01T12:00:60Z"),
+        DateError::OutOfRange(DateComponent::Second)
+    );
+}
+
+#[test]
+fn reports_malformed_components() {
+    for (input, component) in [
+        ("2024-05-01T12:x:00Z", DateComponent::Minute),
+        ("2024-5-01T12:00:00Z", DateComponent::Month),
+        ("2024-05-01T12:00:0Z", DateComponent::Second),
+        ("2024-05-01T12:00:00.Z", DateComponent::Fraction),
+        ("24-05-01T12:00:00Z", DateComponent::Year),
+    ] {
+        assert_eq!(error(input), DateError::Malformed(component), "{}", input);
+    }
+    assert_eq!(
+        error("2024-05-01T12:00:00Z!").to_string(),
+        "unexpected characters at byte 20"
+    );
+    assert_eq!(
+        error("2024-02-30T12:00:00Z").to_string(),
+        "day of the month out of range"
+    );
+}
+
+#[test]
+fn datetime_validators_reject_impossible_dates() {
+    let validator = JsonValidator::DateTime(|_| true);
+    assert!(validator.validate(&JsonValue::String("2024-02-29T12:00:00Z".to_owned())));
+    assert!(!validator.validate(&JsonValue::String("2023-02-29T12:00:00Z".to_owned())));
+    assert!(!validator.validate(&JsonValue::String("2024-05-01T99:00:00Z".to_owned())));
+}
@@ -0,0 +1,143 @@
+#![cfg(feature = "derive")]
+
+use jasper::{FromJson, JsonValue, ToJson};
+
+fn parse(input: &str) -> JsonValue {
+    JsonValue::parse(input.as_bytes().to_vec()).unwrap()
+}
+
+#[derive(Debug, PartialEq, ToJson, FromJson)]
+#[json(rename_all = "camelCase")]
+struct Order {
+    order_id: u32,
+    customer_name: String,
+    note: Option<String>,
+    #[json(rename = "lines")]
+    items: Vec<Line>,
+    #[json(default)]
+    priority: bool,
+    #[json(skip)]
+    cached: u8,
+}
+
+#[derive(Debug, PartialEq, ToJson, FromJson)]
+struct Line(String, u16);
+
+#[derive(Debug, PartialEq, ToJson, FromJson)]
+#[json(tag = "kind")]
+enum Shape {
+    Circle { radius: f64 },
+    Square { side: f64 },
+    Empty,
+}
+
+#[derive(Debug, PartialEq, ToJson, FromJson)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+//Newtype variants of an internally tagged enum must hold something which converts to an
+//object, so that the tag can be added to it; `Message::Wait(u32)` would not compile
+#[derive(Debug, PartialEq, ToJson, FromJson)]
+#[json(tag = "type")]
+enum Message {
+    Move(Point),
+    Draw(Shape),
+    Quit,
+}
+
+#[derive(Debug, PartialEq, ToJson, FromJson)]
+enum Event {
+    Started,
+    Moved(i32, i32),
+    Renamed { name: String },
+}
+
+#[test]
+fn derives_structs_with_renames_defaults_and_skips() {
+    let order = Order {
+        order_id: 7,
+        customer_name: "Ada".to_owned(),
+        note: None,
+        items: vec![Line("tea".to_owned(), 2)],
+        priority: true,
+        cached: 0,
+    };
+    let json = order.to_json();
+    assert_eq!(
+        json,
+        parse(r#"{"orderId": 7, "customerName": "Ada", "lines": [["tea", 2]], "priority": true}"#)
+    );
+    assert_eq!(Order::from_json(&json).unwrap(), order);
+    let sparse = Order::from_json(&parse(
+        r#"{"orderId": 8, "customerName": "Bo", "lines": []}"#,
+    ));
+    assert!(!sparse.unwrap().priority);
+}
+
+#[test]
+fn derives_tagged_enums() {
+    let shape = Shape::Circle { radius: 1.5 };
+    assert_eq!(
+        shape.to_json(),
+        parse(r#"{"kind": "Circle", "radius": 1.5}"#)
+    );
+    assert_eq!(Shape::from_json(&shape.to_json()).unwrap(), shape);
+    assert_eq!(Shape::Empty.to_json(), parse(r#"{"kind": "Empty"}"#));
+
+    for event in [
+        Event::Started,
+        Event::Moved(1, -2),
+        Event::Renamed {
+            name: "x".to_owned(),
+        },
+    ] {
+        assert_eq!(Event::from_json(&event.to_json()).unwrap(), event);
+    }
+    assert_eq!(
+        Event::Started.to_json(),
+        JsonValue::String("Started".to_owned())
+    );
+    assert_eq!(
+        Event::Moved(1, -2).to_json(),
+        parse(r#"{"Moved": [1, -2]}"#)
+    );
+}
+
+#[test]
+fn reports_the_path_of_conversion_errors() {
+    let error = Order::from_json(&parse(
+        r#"{
+            "orderId": 7,
+            "customerName": "Ada",
+            "lines": [["tea", 2], ["cake", "two"]]
+        }"#,
+    ))
+    .unwrap_err();
+    assert_eq!(error.path, "/lines/1/1");
+    assert!(Shape::from_json(&parse(r#"{"kind": "Triangle"}"#)).is_err());
+    assert_eq!(
+        Order::from_json(&parse(r#"{"orderId": 7}"#))
+            .unwrap_err()
+            .message,
+        "missing key \"customerName\""
+    );
+}
+
+#[test]
+fn tags_the_content_of_newtype_variants() {
+    let message = Message::Move(Point { x: 1, y: 2 });
+    assert_eq!(
+        message.to_json(),
+        parse(r#"{"type": "Move", "x": 1, "y": 2}"#)
+    );
+    assert_eq!(Message::from_json(&message.to_json()).unwrap(), message);
+    let message = Message::Draw(Shape::Square { side: 2.0 });
+    assert_eq!(
+        message.to_json(),
+        parse(r#"{"type": "Draw", "kind": "Square", "side": 2}"#)
+    );
+    assert_eq!(Message::from_json(&message.to_json()).unwrap(), message);
+    assert_eq!(Message::Quit.to_json(), parse(r#"{"type": "Quit"}"#));
+}
@@ -0,0 +1,110 @@
+use jasper::{json, Dialect, JsonValue, ParseOptions};
+
+fn parse(input: &str, dialect: Dialect) -> Option<JsonValue> {
+    let options = ParseOptions {
+        dialect,
+        ..ParseOptions::default()
+    };
+    JsonValue::parse_with(input.as_bytes().to_vec(), &options)
+}
+
+#[test]
+fn jsonc_allows_comments_and_trailing_commas() {
+    let input = "// settings\n{\"a\": 1, /* inline */ \"b\": [1, 2,],}";
+    assert_eq!(parse(input, Dialect::Strict), None);
+    assert_eq!(
+        parse(input, Dialect::Jsonc),
+        Some(json!({"a": 1, "b": [1, 2]}))
+    );
+    assert_eq!(
+        parse(input, Dialect::Json5),
+        Some(json!({"a": 1, "b": [1, 2]}))
+    );
+    for bad in ["[1,,]", "[,]", "{,}", "[1, /* unterminated ]"] {
+        assert_eq!(parse(bad, Dialect::Jsonc), None, "{}", bad);
+    }
+    assert_eq!(
+        parse("{\"url\": \"http://x/*y*/\"}", Dialect::Jsonc),
+        Some(json!({"url": "http://x/*y*/"}))
+    );
+    assert_eq!(parse("{a: 1}", Dialect::Jsonc), None);
+}
+
+#[test]
+fn json5_allows_javascript_syntax() {
+    assert_eq!(
+        parse("{unquoted: 'single', $_id1: \"x\"}", Dialect::Json5),
+        Some(json!({"unquoted": "single", "$_id1": "x"}))
+    );
+    assert_eq!(
+        parse("'line \\\ncontinued \\x41 \\' \\v'", Dialect::Json5),
+        Some(json!("line continued A ' \u{b}"))
+    );
+    assert_eq!(
+        parse("[0x1F, .5, 5., +1, -0xA]", Dialect::Json5),
+        Some(json!([31, 0.5, 5, 1, -10]))
+    );
+    match parse("[Infinity, -Infinity, NaN]", Dialect::Json5) {
+        Some(JsonValue::Array(arr)) => {
+            assert_eq!(arr[0], JsonValue::Number(f64::INFINITY));
+            assert_eq!(arr[1], JsonValue::Number(f64::NEG_INFINITY));
+            assert!(matches!(arr[2], JsonValue::Number(n) if n.is_nan()));
+        }
+        other => panic!("expected an array, found {:?}", other),
+    }
+    for input in ["{a: 1}", "'x'", "[0x1F]", "[.5]", "[+1]", "[NaN]"] {
+        assert_eq!(parse(input, Dialect::Strict), None, "{}", input);
+        assert_eq!(parse(input, Dialect::Jsonc), None, "{}", input);
+    }
+}
+
+#[test]
+fn limits_depth_in_every_dialect() {
+    let nested = format!("{}{}", "[".repeat(128), "]".repeat(128));
+    let deep = format!("{}{}", "[".repeat(129), "]".repeat(129));
+    for dialect in [Dialect::Strict, Dialect::Jsonc, Dialect::Json5] {
+        assert!(parse(&nested, dialect).is_some());
+        assert_eq!(parse(&deep, dialect), None);
+    }
+}
+
+#[test]
+fn requires_commas_and_a_single_value_in_every_dialect() {
+    for dialect in [Dialect::Strict, Dialect::Jsonc, Dialect::Json5] {
+        for bad in [
+            "[1 \"a\"]",
+            "{\"a\":1 \"b\":2}",
+            "[[1][2]]",
+            "1 2",
+            "tr ue",
+            "[1 /* comment */ 2]",
+            "{\"a\":1}x",
+            "[1] [2]",
+        ] {
+            assert_eq!(parse(bad, dialect), None, "{}", bad);
+        }
+        assert_eq!(parse(" [ 1 , true ] ", dialect), Some(json!([1, true])));
+        assert_eq!(parse("{\"a\" : 1 }", dialect), Some(json!({"a": 1})));
+    }
+    assert_eq!(parse("{a b: 1}", Dialect::Json5), None);
+    for bad in ["[1,]", "{\"a\":1,}", "TRUE"] {
+        assert_eq!(parse(bad, Dialect::Strict), None, "{}", bad);
+    }
+}
+
+#[test]
+fn decodes_unicode_escapes() {
+    for dialect in [Dialect::Strict, Dialect::Jsonc, Dialect::Json5] {
+        assert_eq!(parse("\"\\u00e9\"", dialect), Some(json!("é")));
+        assert_eq!(parse("\"\\ud83d\\ude00!\"", dialect), Some(json!("😀!")));
+        for bad in [
+            "\"\\ud83d\"",
+            "\"\\ude00\"",
+            "\"\\ud83d\\u0041\"",
+            "\"\\u00\"",
+            "\"\\u00g0\"",
+        ] {
+            assert_eq!(parse(bad, dialect), None, "{}", bad);
+        }
+    }
+}
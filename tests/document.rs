@@ -0,0 +1,116 @@
+use jasper::{json, Dialect, DocumentError, JsonDocument, JsonValue, ParseOptions};
+
+const CONFIG: &str = "// config
+{
+    \"name\": \"app\", // the name
+    \"servers\": [
+        {\"port\": 80},
+        {\"port\": 81}
+    ],
+    /* flags */
+    \"debug\": false
+}
+";
+
+fn jsonc() -> ParseOptions {
+    ParseOptions {
+        dialect: Dialect::Jsonc,
+        ..ParseOptions::default()
+    }
+}
+
+#[test]
+fn reads_values_and_key_order() {
+    let doc = JsonDocument::parse(CONFIG, &jsonc()).unwrap();
+    assert_eq!(doc.as_str(), CONFIG);
+    assert_eq!(doc.keys("").unwrap(), ["name", "servers", "debug"]);
+    assert_eq!(doc.get("/servers/1/port"), Some(json!(81)));
+    assert_eq!(doc.get("/servers/2"), None);
+    assert_eq!(
+        doc.value(),
+        json!({"name": "app", "servers": [{"port": 80}, {"port": 81}], "debug": false})
+    );
+    assert!(JsonDocument::parse(CONFIG, &ParseOptions::default()).is_err());
+}
+
+#[test]
+fn edits_leave_the_rest_of_the_text_alone() {
+    let mut doc = JsonDocument::parse(CONFIG, &jsonc()).unwrap();
+    doc.set("/servers/1/port", &json!(8081)).unwrap();
+    doc.set("/servers/-", &json!({"port": 82})).unwrap();
+    doc.set("/timeout", &json!(30)).unwrap();
+    assert_eq!(doc.remove("/debug").unwrap(), json!(false));
+    assert_eq!(
+        doc.as_str(),
+        "// config
+{
+    \"name\": \"app\", // the name
+    \"servers\": [
+        {\"port\": 80},
+        {\"port\": 8081},
+        {\"port\": 82}
+    ],
+    /* flags */
+    \"timeout\": 30
+}
+"
+    );
+    let reparsed = JsonDocument::parse(doc.as_str(), &jsonc()).unwrap();
+    assert_eq!(reparsed.value(), doc.value());
+}
+
+#[test]
+fn edits_empty_containers_and_escaped_pointers() {
+    let mut doc = JsonDocument::parse("{}", &jsonc()).unwrap();
+    doc.set("/a~1b", &json!([])).unwrap();
+    doc.set("/a~1b/0", &json!(1)).unwrap();
+    assert_eq!(doc.value(), json!({"a/b": [1]}));
+    doc.set("", &json!(null)).unwrap();
+    assert_eq!(doc.as_str(), "null");
+}
+
+#[test]
+fn rejects_bad_pointers_and_values() {
+    let mut doc = JsonDocument::parse(CONFIG, &jsonc()).unwrap();
+    let pointer_error = |result: Result<(), DocumentError>| match result {
+        Err(DocumentError::Pointer { message, .. }) => message,
+        other => panic!("expected a pointer error, found {:?}", other),
+    };
+    assert_eq!(
+        pointer_error(doc.set("/nope/x", &json!(1))),
+        "parent does not exist"
+    );
+    assert_eq!(
+        pointer_error(doc.set("/servers/9", &json!(1))),
+        "no such array index"
+    );
+    assert_eq!(
+        pointer_error(doc.set("/name/x", &json!(1))),
+        "parent is not an object or array"
+    );
+    assert!(doc.remove("").is_err());
+    assert!(matches!(
+        doc.set("/name", &JsonValue::Number(f64::NAN)),
+        Err(DocumentError::Syntax(_))
+    ));
+    assert_eq!(doc.as_str(), CONFIG);
+
+    assert_eq!(
+        JsonDocument::parse("{} x", &jsonc()).unwrap_err().message,
+        "unexpected characters after the value"
+    );
+    let deep = "[".repeat(100_000);
+    assert_eq!(
+        JsonDocument::parse(&deep, &jsonc()).unwrap_err().message,
+        "nesting is too deep"
+    );
+}
+
+#[test]
+fn decodes_unicode_escapes() {
+    let text = "{\"caf\\u00e9\": \"\\ud83d\\ude00\"}";
+    let doc = JsonDocument::parse(text, &ParseOptions::default()).unwrap();
+    assert_eq!(doc.keys("").unwrap(), ["café"]);
+    assert_eq!(doc.get("/café"), Some(json!("😀")));
+    assert_eq!(doc.as_str(), text);
+}
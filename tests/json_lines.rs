@@ -0,0 +1,86 @@
+use jasper::{json, JsonLinesError, JsonLinesReader, JsonLinesWriter, JsonValue, ParseOptions};
+
+fn read_all(reader: JsonLinesReader<&[u8]>) -> Vec<Result<JsonValue, (usize, String)>> {
+    reader
+        .map(|item| {
+            item.map_err(|(line_no, e)| match e {
+                JsonLinesError::Syntax(e) => (line_no, e.message),
+                JsonLinesError::Io(e) => panic!("unexpected I/O error {}", e),
+            })
+        })
+        .collect()
+}
+
+#[test]
+fn round_trips_values_line_by_line() {
+    let values = [json!({"a": "line\nbreak"}), json!([1, 2]), json!(null)];
+    let mut writer = JsonLinesWriter::new(vec![]);
+    writer.write_all(&values).unwrap();
+    let output = writer.into_inner();
+    assert_eq!(output.iter().filter(|b| **b == b'\n').count(), 3);
+    let read = JsonLinesReader::new(&output[..])
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(read, values);
+}
+
+#[test]
+fn ignores_blank_lines_and_carriage_returns() {
+    let input = b"1\r\n\n   \r\n{\"a\": true}\r\n\"last\"";
+    let mut reader = JsonLinesReader::new(&input[..]);
+    assert_eq!(reader.next().unwrap().unwrap(), json!(1));
+    assert_eq!(reader.next().unwrap().unwrap(), json!({"a": true}));
+    assert_eq!(reader.line_no(), 4);
+    assert_eq!(reader.next().unwrap().unwrap(), json!("last"));
+    assert!(reader.next().is_none());
+}
+
+#[test]
+fn stops_at_or_skips_bad_lines() {
+    let input = b"1\n[2,\n3\n";
+    assert_eq!(
+        read_all(JsonLinesReader::new(&input[..])),
+        [Ok(json!(1)), Err((2, "unexpected end of input".to_owned()))]
+    );
+    let read = read_all(JsonLinesReader::new(&input[..]).skip_errors());
+    assert_eq!(read.len(), 3);
+    assert_eq!(read[1].as_ref().unwrap_err().0, 2);
+    assert_eq!(read[2], Ok(json!(3)));
+}
+
+#[test]
+fn limits_the_depth_and_size_of_each_line() {
+    let input = format!("1\n{}\n2\n", "[".repeat(100_000));
+    assert_eq!(
+        read_all(JsonLinesReader::new(input.as_bytes())),
+        [Ok(json!(1)), Err((2, "nesting is too deep".to_owned()))]
+    );
+    assert_eq!(
+        read_all(JsonLinesReader::new(input.as_bytes()).skip_errors()),
+        [
+            Ok(json!(1)),
+            Err((2, "nesting is too deep".to_owned())),
+            Ok(json!(2))
+        ]
+    );
+
+    let options = ParseOptions {
+        max_size: Some(5),
+        ..ParseOptions::default()
+    };
+    let input = format!("[1,2]\r\n\"{}\"\n[123]\n[1234]\n[5]", "x".repeat(100_000));
+    assert_eq!(
+        read_all(JsonLinesReader::with_options(input.as_bytes(), &options).skip_errors()),
+        [
+            Ok(json!([1, 2])),
+            Err((2, "input is too large".to_owned())),
+            Ok(json!([123])),
+            Err((4, "input is too large".to_owned())),
+            Ok(json!([5])),
+        ]
+    );
+    assert_eq!(
+        read_all(JsonLinesReader::with_options(input.as_bytes(), &options)).len(),
+        2
+    );
+}
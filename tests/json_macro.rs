@@ -0,0 +1,69 @@
+use std::collections::HashMap;
+
+use jasper::{json, JsonValue};
+
+#[test]
+fn builds_literals_arrays_and_objects() {
+    assert_eq!(json!(null), JsonValue::Null);
+    assert_eq!(json!(false), JsonValue::Boolean(false));
+    assert_eq!(json!([]), JsonValue::Array(vec![]));
+    assert_eq!(json!({}), JsonValue::Object(HashMap::new()));
+    assert_eq!(
+        json!([1, true, null, "s", [], {},]),
+        JsonValue::Array(vec![
+            JsonValue::Number(1.0),
+            JsonValue::Boolean(true),
+            JsonValue::Null,
+            JsonValue::String("s".to_owned()),
+            JsonValue::Array(vec![]),
+            JsonValue::Object(HashMap::new()),
+        ])
+    );
+    assert_eq!(
+        json!({"a": {"b": [null, {"c": false}]}, "d": -1.5,}),
+        JsonValue::build_object(vec![
+            (
+                "a".to_owned(),
+                JsonValue::build_object(vec![(
+                    "b".to_owned(),
+                    JsonValue::Array(vec![
+                        JsonValue::Null,
+                        JsonValue::build_object(vec![("c".to_owned(), JsonValue::Boolean(false))]),
+                    ]),
+                )]),
+            ),
+            ("d".to_owned(), JsonValue::Number(-1.5)),
+        ])
+    );
+}
+
+#[test]
+fn interpolates_expressions_and_keys() {
+    let name = "Ada";
+    let scores = vec![1, 2];
+    let key = String::from("dynamic");
+    let value = json!({
+        "name": name,
+        "scores": scores,
+        "total": 1 + 2,
+        "nickname": None::<&str>,
+        key: [name, 3 * 2],
+        "nested": json!([true]),
+    });
+    assert_eq!(
+        value,
+        json!({
+            "name": "Ada",
+            "scores": [1, 2],
+            "total": 3,
+            "nickname": null,
+            "dynamic": ["Ada", 6],
+            "nested": [true]
+        })
+    );
+}
+
+#[test]
+fn later_keys_replace_earlier_ones() {
+    assert_eq!(json!({"a": 1, "a": 2}), json!({"a": 2}));
+}
@@ -0,0 +1,107 @@
+use jasper::{json, JsonValue, ParseOptions};
+
+#[test]
+fn round_trips_values() {
+    for value in [
+        json!(null),
+        json!(false),
+        json!(127),
+        json!(-33),
+        json!(4294967296_u64),
+        json!(0.5),
+        json!(0.1),
+        json!("x".repeat(300)),
+        json!([1, [2, [3]], {"a": null}]),
+        json!({"b": 1, "a": [true, "x"]}),
+    ] {
+        assert_eq!(JsonValue::from_msgpack(&value.to_msgpack()).unwrap(), value);
+    }
+}
+
+#[test]
+fn encodes_the_smallest_formats() {
+    assert_eq!(json!(5).to_msgpack(), [0x05]);
+    assert_eq!(json!(-1).to_msgpack(), [0xFF]);
+    assert_eq!(json!(200).to_msgpack(), [0xCC, 0xC8]);
+    assert_eq!(json!(-200).to_msgpack(), [0xD1, 0xFF, 0x38]);
+    assert_eq!(json!(1.5).to_msgpack(), [0xCA, 0x3F, 0xC0, 0x00, 0x00]);
+    assert_eq!(
+        json!({"b": 1, "a": 2}).to_msgpack(),
+        [0x82, 0xA1, b'a', 0x02, 0xA1, b'b', 0x01]
+    );
+}
+
+#[test]
+fn converts_binary_timestamps_and_integer_keys() {
+    assert_eq!(
+        JsonValue::from_msgpack(&[0xC4, 0x03, 0xFB, 0xFF, 0x00]).unwrap(),
+        json!("+/8A")
+    );
+    assert_eq!(
+        JsonValue::from_msgpack(&[0xD6, 0xFF, 0x66, 0x32, 0x47, 0x5C]).unwrap(),
+        json!("2024-05-01T13:45:00Z")
+    );
+    assert_eq!(
+        JsonValue::from_msgpack(&[0x81, 0x07, 0xC0]).unwrap(),
+        json!({"7": null})
+    );
+}
+
+#[test]
+fn reports_malformed_input_with_offsets() {
+    for (input, offset, message) in [
+        (&[][..], 0, "unexpected end of input"),
+        (&[0xC1], 0, "0xC1 is never used"),
+        (&[0x92, 0x01], 2, "unexpected end of input"),
+        (
+            &[0xDD, 0xFF, 0xFF, 0xFF, 0xFF],
+            0,
+            "length exceeds the input",
+        ),
+        (&[0xA2, 0xFF, 0xFE], 0, "string is not valid UTF-8"),
+        (
+            &[0x81, 0x90, 0x01],
+            1,
+            "map keys must be strings or integers",
+        ),
+        (&[0x82, 0x01, 0x02, 0x01, 0x03], 3, "duplicate map key"),
+        (&[0xD4, 0x05, 0x00], 0, "unsupported extension type 5"),
+        (&[0x01, 0x02], 1, "unexpected bytes after the value"),
+    ] {
+        let e = JsonValue::from_msgpack(input).unwrap_err();
+        assert_eq!(
+            (e.offset, e.message.as_str()),
+            (offset, message),
+            "{:?}",
+            input
+        );
+    }
+}
+
+#[test]
+fn limits_depth_size_and_allocation() {
+    assert_eq!(
+        JsonValue::from_msgpack(&vec![0x91; 100_000])
+            .unwrap_err()
+            .message,
+        "nesting is too deep"
+    );
+
+    //Each array claims nearly all of the input, which must not be allocated up front
+    let mut input = [0xDC, 0xFF, 0xFF].repeat(100);
+    input.resize(70_000, 0xC0);
+    let e = JsonValue::from_msgpack(&input).unwrap_err();
+    assert_eq!(e.message, "unexpected end of input");
+
+    let options = ParseOptions {
+        max_size: Some(2),
+        ..ParseOptions::default()
+    };
+    assert!(JsonValue::from_msgpack_with(&[0x91, 0x01], &options).is_ok());
+    assert_eq!(
+        JsonValue::from_msgpack_with(&[0x92, 0x01, 0x02], &options)
+            .unwrap_err()
+            .message,
+        "input is too large"
+    );
+}
@@ -0,0 +1,81 @@
+use jasper::{AdditionalKeys, ArrayConstraints, Field, JsonValidator, JsonValue};
+
+fn parse(input: &str) -> JsonValue {
+    JsonValue::parse(input.as_bytes().to_vec()).unwrap()
+}
+
+#[test]
+fn coerces_fills_defaults_and_strips_keys() {
+    let validator = JsonValidator::ObjectFields {
+        fields: vec![
+            Field::required("id", JsonValidator::Integer(|_| true)),
+            Field::optional("active", JsonValidator::Boolean(|_| true))
+                .with_default(JsonValue::String("true".to_owned())),
+            Field::optional(
+                "scores",
+                JsonValidator::Array(Box::new(JsonValidator::Number(|_| true))),
+            ),
+        ],
+        patterns: vec![],
+        additional_keys: AdditionalKeys::Strip,
+    };
+    let output = validator
+        .normalize(parse(
+            r#"{"id": "42", "scores": ["1.5", 2], "debug": true}"#,
+        ))
+        .unwrap();
+    assert_eq!(
+        output,
+        parse(r#"{"id": 42, "active": true, "scores": [1.5, 2]}"#)
+    );
+}
+
+#[test]
+fn reports_values_that_cannot_be_coerced() {
+    let validator =
+        JsonValidator::Object(vec![("id".to_owned(), JsonValidator::Integer(|_| true))]);
+    let errors = validator
+        .normalize(parse(r#"{"id": "forty-two"}"#))
+        .unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].path, "/id");
+}
+
+#[test]
+fn array_validators_do_not_flatten_other_values() {
+    let strings = JsonValidator::Array(Box::new(JsonValidator::String(|_| true)));
+    let object = parse(r#"{"a": "x", "b": "y"}"#);
+    assert_eq!(strings.normalize(object.clone()).unwrap(), object);
+    assert_eq!(
+        strings
+            .normalize(JsonValue::String("x".to_owned()))
+            .unwrap(),
+        JsonValue::String("x".to_owned())
+    );
+    let numbers = JsonValidator::Array(Box::new(JsonValidator::Number(|_| true)));
+    assert_eq!(
+        numbers.normalize(parse(r#"["1", 2]"#)).unwrap(),
+        parse("[1, 2]")
+    );
+
+    let loose = JsonValidator::ArrayOf(ArrayConstraints::new(JsonValidator::Number(|_| true)));
+    assert_eq!(
+        loose.normalize(JsonValue::String("3".to_owned())).unwrap(),
+        parse("[3]")
+    );
+    assert_eq!(
+        loose.normalize(parse(r#"{"a": 1}"#)).unwrap(),
+        parse(r#"{"a": 1}"#)
+    );
+    let strict = JsonValidator::ArrayOf(ArrayConstraints {
+        strict: true,
+        ..ArrayConstraints::new(JsonValidator::Number(|_| true))
+    });
+    assert_eq!(
+        strict
+            .normalize(JsonValue::String("3".to_owned()))
+            .unwrap_err()[0]
+            .validator,
+        "ArrayOf"
+    );
+}
@@ -0,0 +1,98 @@
+use jasper::{AdditionalKeys, Field, JsonValidator, JsonValue, KeyPattern};
+
+fn parse(input: &str) -> JsonValue {
+    JsonValue::parse(input.as_bytes().to_vec()).unwrap()
+}
+
+fn messages(validator: &JsonValidator, input: &JsonValue) -> Vec<(String, String)> {
+    match validator.validate_detailed(input) {
+        Ok(()) => vec![],
+        Err(errors) => errors.into_iter().map(|e| (e.path, e.message)).collect(),
+    }
+}
+
+fn user(additional_keys: AdditionalKeys) -> JsonValidator {
+    JsonValidator::ObjectFields {
+        fields: vec![
+            Field::required("name", JsonValidator::String(|_| true)),
+            Field::optional("age", JsonValidator::Integer(|_| true)),
+        ],
+        patterns: vec![(
+            KeyPattern::regex("^x-").unwrap(),
+            JsonValidator::String(|_| true),
+        )],
+        additional_keys,
+    }
+}
+
+#[test]
+fn checks_required_and_optional_fields() {
+    let validator = user(AdditionalKeys::Allow);
+    assert!(validator.validate(&parse(r#"{"name": "Ada"}"#)));
+    assert!(validator.validate(&parse(r#"{"name": "Ada", "age": 36, "other": [1]}"#)));
+    assert_eq!(
+        messages(&validator, &parse(r#"{"age": 36}"#)),
+        [(
+            "/name".to_owned(),
+            "missing required key \"name\"".to_owned()
+        )]
+    );
+    assert_eq!(
+        messages(&validator, &parse(r#"{"name": "Ada", "age": -1}"#)).len(),
+        1
+    );
+    assert_eq!(
+        messages(&validator, &JsonValue::String("Ada".to_owned())),
+        [(String::new(), "value is not an object".to_owned())]
+    );
+}
+
+#[test]
+fn applies_the_additional_keys_policy() {
+    let input = parse(r#"{"name": "Ada", "x-team": "core", "extra": 1}"#);
+    assert!(user(AdditionalKeys::Allow).validate(&input));
+    assert!(user(AdditionalKeys::Strip).validate(&input));
+    assert_eq!(
+        messages(&user(AdditionalKeys::Deny), &input),
+        [("/extra".to_owned(), "unexpected key \"extra\"".to_owned())]
+    );
+    let numbers = user(AdditionalKeys::Validate(Box::new(JsonValidator::Number(
+        |n| *n > 0.0,
+    ))));
+    assert!(numbers.validate(&input));
+    assert_eq!(
+        messages(&numbers, &parse(r#"{"name": "Ada", "extra": -1}"#))[0].0,
+        "/extra"
+    );
+    assert_eq!(
+        user(AdditionalKeys::Strip).normalize(input).unwrap(),
+        parse(r#"{"name": "Ada", "x-team": "core"}"#)
+    );
+}
+
+#[test]
+fn validates_keys_by_pattern() {
+    let validator = JsonValidator::ObjectFields {
+        fields: vec![],
+        patterns: vec![
+            (
+                KeyPattern::regex("^[0-9]+$").unwrap(),
+                JsonValidator::Number(|_| true),
+            ),
+            (
+                KeyPattern::predicate(|key| key.len() > 3),
+                JsonValidator::Number(|n| *n < 100.0),
+            ),
+        ],
+        additional_keys: AdditionalKeys::Deny,
+    };
+    assert!(validator.validate(&parse(r#"{"1": 1, "22": 2}"#)));
+    assert_eq!(
+        messages(&validator, &parse(r#"{"1234": 500, "ab": 1}"#))
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect::<Vec<_>>(),
+        ["/1234", "/ab"]
+    );
+    assert!(KeyPattern::regex("(").is_err());
+}
@@ -0,0 +1,105 @@
+use jasper::{
+    AdditionalKeys, Field, JsonValidator, JsonValue, ParseError, ParseOptions, ParseValidateError,
+};
+
+fn parse(input: &str) -> JsonValue {
+    JsonValue::parse(input.as_bytes().to_vec()).unwrap()
+}
+
+fn syntax_error(result: Result<JsonValue, ParseValidateError>) -> ParseError {
+    match result {
+        Err(ParseValidateError::Syntax(e)) => e,
+        other => panic!("expected a syntax error, found {:?}", other),
+    }
+}
+
+#[test]
+fn parses_valid_documents() {
+    let validator = JsonValidator::ObjectFields {
+        fields: vec![
+            Field::required("id", JsonValidator::Integer(|_| true)),
+            Field::optional(
+                "tags",
+                JsonValidator::Array(Box::new(JsonValidator::String(|_| true))),
+            ),
+        ],
+        patterns: vec![],
+        additional_keys: AdditionalKeys::Deny,
+    };
+    let value = validator
+        .parse_and_validate(br#" {"id": 7, "tags": ["a", "b"]} "#)
+        .unwrap();
+    assert_eq!(value, parse(r#"{"id": 7, "tags": ["a", "b"]}"#));
+}
+
+#[test]
+fn stops_at_the_first_violation() {
+    let validator = JsonValidator::ObjectFields {
+        fields: vec![Field::required("id", JsonValidator::Integer(|_| true))],
+        patterns: vec![],
+        additional_keys: AdditionalKeys::Deny,
+    };
+    match validator.parse_and_validate(br#"{"id": 1, "extra": [1, 2, 3"#) {
+        Err(ParseValidateError::Invalid { offset, error }) => {
+            assert_eq!(offset, 10);
+            assert_eq!(error.path, "/extra");
+        }
+        other => panic!("expected a violation, found {:?}", other),
+    }
+    let tuple = JsonValidator::Tuple(vec![JsonValidator::Null]);
+    match tuple.parse_and_validate(b"[null, 1]") {
+        Err(ParseValidateError::Invalid { offset, .. }) => assert_eq!(offset, 7),
+        other => panic!("expected a violation, found {:?}", other),
+    }
+}
+
+#[test]
+fn reports_malformed_input_with_offsets() {
+    let any = JsonValidator::RubberStamp;
+    for (input, offset) in [
+        (&b"[1, 2"[..], 5),
+        (b"{\"a\" 1}", 5),
+        (b"[01]", 2),
+        (b"\"\\x\"", 1),
+        (b"{\"a\": 1, \"a\": 2}", 9),
+        (b"[1] 2", 4),
+        (b"", 0),
+    ] {
+        let e = syntax_error(any.parse_and_validate(input));
+        assert_eq!(e.offset, offset, "{:?}", e);
+    }
+}
+
+#[test]
+fn limits_nesting_depth() {
+    let any = JsonValidator::RubberStamp;
+    let deep = "[".repeat(200_000);
+    let e = syntax_error(any.parse_and_validate(deep.as_bytes()));
+    assert_eq!((e.offset, e.message.as_str()), (128, "nesting is too deep"));
+
+    let nested = format!("{}{}", "[".repeat(128), "]".repeat(128));
+    assert!(any.parse_and_validate(nested.as_bytes()).is_ok());
+    let e = syntax_error(any.parse_and_validate(format!("[{}]", nested).as_bytes()));
+    assert_eq!(e.message, "nesting is too deep");
+
+    let options = ParseOptions {
+        max_depth: Some(2),
+        ..ParseOptions::default()
+    };
+    assert!(any.parse_and_validate_with(b"[{}]", &options).is_ok());
+    assert!(any
+        .parse_and_validate_with(b"[{\"a\": []}]", &options)
+        .is_err());
+}
+
+#[test]
+fn limits_input_size() {
+    let options = ParseOptions {
+        max_size: Some(4),
+        ..ParseOptions::default()
+    };
+    let any = JsonValidator::RubberStamp;
+    assert!(any.parse_and_validate_with(b"[12]", &options).is_ok());
+    let e = syntax_error(any.parse_and_validate_with(b"[123]", &options));
+    assert_eq!(e.message, "input is too large");
+}
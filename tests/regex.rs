@@ -0,0 +1,95 @@
+use jasper::Regex;
+use std::time::{Duration, Instant};
+
+fn is_match(pattern: &str, input: &str) -> bool {
+    Regex::new(pattern).unwrap().is_match(input)
+}
+
+#[test]
+fn matches_literals_classes_and_anchors() {
+    assert!(is_match("bc", "abcd"));
+    assert!(!is_match("^bc", "abcd"));
+    assert!(is_match("^a.c$", "abc"));
+    assert!(!is_match("^a.c$", "a\nc"));
+    assert!(is_match("^[a-c]+$", "abcabc"));
+    assert!(!is_match("^[^a-c]+$", "xyzb"));
+    assert!(is_match(r"^\d{3}-\d{4}$", "555-1234"));
+    assert!(is_match(r"^[\w.]+@\w+$", "first.last@host"));
+    assert!(is_match(r"\bcat\b", "a cat sat"));
+    assert!(!is_match(r"\bcat\b", "concatenate"));
+    assert!(is_match(r"\bcat", " cat"));
+    assert!(is_match("$", "abc"));
+    assert!(is_match("c$", "abc"));
+    assert!(is_match(r"^é\x41$", "éA"));
+}
+
+#[test]
+fn matches_alternation_and_counted_repetition() {
+    assert!(is_match("^(cat|dog|bird)s?$", "dogs"));
+    assert!(!is_match("^(cat|dog)$", "cow"));
+    assert!(is_match("^a{2,3}$", "aaa"));
+    assert!(!is_match("^a{2,3}$", "aaaa"));
+    assert!(!is_match("^a{2,3}$", "a"));
+    assert!(is_match("^a{2,}$", "aaaaaa"));
+    assert!(is_match("^(ab){2}$", "abab"));
+    assert!(is_match("^a+?b*?$", "aabb"));
+    assert!(is_match("^x{y$", "x{y"));
+    assert!(is_match("^(a*)*$", "aaa"));
+    assert!(is_match("^(|a)+$", "aa"));
+}
+
+#[test]
+fn matches_lookaheads() {
+    assert!(is_match("^(?=.*\\d)(?=.*[a-z]).{6,}$", "abc123"));
+    assert!(!is_match("^(?=.*\\d)(?=.*[a-z]).{6,}$", "abcdef"));
+    assert!(is_match("^(?!admin$).*$", "user"));
+    assert!(!is_match("^(?!admin$).*$", "admin"));
+    assert!(is_match("^(?:(?!ab).)*$", "aacbb"));
+    assert!(!is_match("^(?:(?!ab).)*$", "aabb"));
+}
+
+#[test]
+fn rejects_malformed_patterns() {
+    for pattern in [
+        "(a", "a)", "*a", "a{3,2}", "[b-a]", "\\", "\\k<x>", "(?<x>a)", "^*",
+    ] {
+        assert!(Regex::new(pattern).is_err(), "{} compiled", pattern);
+    }
+}
+
+#[test]
+fn rejects_patterns_that_are_too_large_or_deep() {
+    assert!(Regex::new("(a{1000}){1000}").is_err());
+    assert!(Regex::new("a{4294967295}").is_err());
+    let deep = format!("{}a{}", "(".repeat(100_000), ")".repeat(100_000));
+    assert!(Regex::new(&deep).is_err());
+    let nested = format!("{}a{}", "(".repeat(100), ")".repeat(100));
+    assert!(is_match(&nested, "a"));
+}
+
+#[test]
+fn long_inputs_do_not_overflow_the_stack() {
+    let input = "a".repeat(1_000_000);
+    assert!(is_match("^a*$", &input));
+    assert!(!is_match("^a*b$", &input));
+    assert!(is_match("^(?=a+$)(a|b)*$", &input));
+}
+
+#[test]
+fn nested_quantifiers_match_in_linear_time() {
+    let start = Instant::now();
+    let input = format!("{}b", "a".repeat(28));
+    assert!(!is_match("^(a+)+$", &input));
+    assert!(!is_match("^(a|aa)*$", &input));
+    assert!(!is_match("^(a*)*(a*)*c$", &"a".repeat(10_000)));
+    assert!(start.elapsed() < Duration::from_secs(5));
+}
+
+#[test]
+fn repeating_empty_groups_compiles_quickly() {
+    let start = Instant::now();
+    assert!(is_match("^(?:){100000000}a$", "a"));
+    assert!(is_match("^(?:(?:){20000}){20000}$", ""));
+    assert!(Regex::new("(?:\\b){1000000}").is_err());
+    assert!(start.elapsed() < Duration::from_secs(5));
+}
@@ -0,0 +1,126 @@
+use jasper::{JsonSchema, JsonValue};
+
+fn parse(input: &str) -> JsonValue {
+    JsonValue::parse(input.as_bytes().to_vec()).unwrap()
+}
+
+fn schema(document: &str) -> JsonSchema {
+    JsonSchema::compile(&parse(document)).unwrap()
+}
+
+#[test]
+fn validates_types_ranges_and_strings() {
+    let schema = schema(
+        r#"{
+            "type": "object",
+            "required": ["name", "age"],
+            "properties": {
+                "name": {"type": "string", "minLength": 1, "pattern": "^[A-Z]"},
+                "age": {"type": "integer", "minimum": 0, "exclusiveMaximum": 150}
+            },
+            "additionalProperties": false
+        }"#,
+    );
+    assert!(schema.is_valid(&parse(r#"{"name": "Ada", "age": 36}"#)));
+    let errors = schema
+        .validate(&parse(r#"{"name": "ada", "age": 150.5, "extra": true}"#))
+        .unwrap_err();
+    let mut found = errors
+        .iter()
+        .map(|e| (e.path.as_str(), e.validator))
+        .collect::<Vec<_>>();
+    found.sort();
+    assert_eq!(
+        found,
+        [
+            ("/age", "exclusiveMaximum"),
+            ("/age", "type"),
+            ("/extra", "false"),
+            ("/name", "pattern")
+        ]
+    );
+    assert!(!schema.is_valid(&parse(r#"{"name": "Ada"}"#)));
+}
+
+#[test]
+fn validates_arrays_and_applicators() {
+    let schema = schema(
+        r#"{
+            "type": "array",
+            "prefixItems": [{"const": "header"}],
+            "items": {"anyOf": [{"type": "number"}, {"type": "null"}]},
+            "contains": {"type": "number"},
+            "uniqueItems": true
+        }"#,
+    );
+    assert!(schema.is_valid(&parse(r#"["header", 1, null, 2]"#)));
+    assert!(!schema.is_valid(&parse(r#"["header", null]"#)));
+    assert!(!schema.is_valid(&parse(r#"["header", 1, 1]"#)));
+    assert!(!schema.is_valid(&parse(r#"["footer", 1]"#)));
+    let one_of = self::schema(r#"{"oneOf": [{"minimum": 0}, {"multipleOf": 2}]}"#);
+    assert!(one_of.is_valid(&JsonValue::Number(3.0)));
+    assert!(!one_of.is_valid(&JsonValue::Number(4.0)));
+    assert!(self::schema(r#"{"not": {"type": "string"}}"#).is_valid(&JsonValue::Number(1.0)));
+}
+
+#[test]
+fn follows_recursive_references_that_move_into_the_instance() {
+    let schema = schema(
+        r##"{
+            "$defs": {
+                "node": {
+                    "type": "object",
+                    "properties": {"children": {"type": "array", "items": {"$ref": "#/$defs/node"}}}
+                }
+            },
+            "$ref": "#/$defs/node"
+        }"##,
+    );
+    assert!(schema.is_valid(&parse(r#"{"children": [{"children": []}, {}]}"#)));
+    assert!(!schema.is_valid(&parse(r#"{"children": [{"children": [1]}]}"#)));
+}
+
+#[test]
+fn rejects_cycles_that_do_not_move_into_the_instance() {
+    for document in [
+        parse(r##"{"allOf": [{"$ref": "#"}]}"##),
+        parse(r##"{"anyOf": [{"type": "null"}, {"$ref": "#"}]}"##),
+        parse(r##"{"oneOf": [{"$ref": "#"}]}"##),
+        parse(r##"{"not": {"$ref": "#"}}"##),
+        parse(r##"{"$ref": "#"}"##),
+        parse(
+            r##"{"$defs": {"a": {"$ref": "#/$defs/b"}, "b": {"allOf": [{"$ref": "#/$defs/a"}]}}}"##,
+        ),
+    ] {
+        let error = JsonSchema::compile(&document).unwrap_err();
+        assert!(error.message.contains("refers back to itself"), "{}", error);
+    }
+}
+
+#[test]
+fn rejects_malformed_schemas() {
+    for document in [
+        JsonValue::Number(1.0),
+        parse(r#"{"type": "text"}"#),
+        parse(r#"{"pattern": "("}"#),
+        parse(r#"{"multipleOf": 0}"#),
+        parse(r#"{"allOf": []}"#),
+        parse(r#"{"$ref": "https://example.com/schema"}"#),
+        parse(r##"{"$ref": "#/$defs/missing"}"##),
+    ] {
+        assert!(
+            JsonSchema::compile(&document).is_err(),
+            "{}",
+            document.stringify()
+        );
+    }
+}
+
+#[test]
+fn patterns_on_long_hostile_strings_finish() {
+    let schema = schema(r#"{"patternProperties": {"^(a+)+$": {"type": "null"}}}"#);
+    let key = format!("{}b", "a".repeat(10_000));
+    assert!(schema.is_valid(&JsonValue::Object(
+        [(key, JsonValue::Number(1.0))].into_iter().collect()
+    )));
+}
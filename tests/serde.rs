@@ -0,0 +1,58 @@
+#![cfg(feature = "serde")]
+
+use std::collections::BTreeMap;
+
+use jasper::{from_value, json, to_value, JsonDate, JsonValue};
+
+#[test]
+fn round_trips_through_serde_types() {
+    let map: BTreeMap<String, (u8, Option<String>, Vec<f64>)> =
+        [("a".to_owned(), (1, None, vec![1.5, -2.0]))].into();
+    let value = to_value(&map).unwrap();
+    assert_eq!(value, json!({"a": [1, null, [1.5, -2]]}));
+    assert_eq!(
+        from_value::<BTreeMap<String, (u8, Option<String>, Vec<f64>)>>(value).unwrap(),
+        map
+    );
+
+    let date = JsonDate::from_rfc3339("2024-05-01T13:45:00Z").unwrap();
+    assert_eq!(to_value(&date).unwrap(), json!("2024-05-01T13:45:00Z"));
+    assert_eq!(
+        from_value::<JsonDate>(json!("2024-05-01T13:45:00Z")).unwrap(),
+        date
+    );
+
+    let nested = json!({"list": [1, -1, 0.5, "s", true, null]});
+    assert_eq!(to_value(&nested).unwrap(), nested);
+}
+
+#[test]
+fn keeps_whole_numbers_as_integers_but_not_negative_zero() {
+    assert_eq!(from_value::<u64>(json!(7)).unwrap(), 7);
+    assert_eq!(from_value::<i64>(json!(-7)).unwrap(), -7);
+    assert!(from_value::<u64>(JsonValue::Number(-0.0)).is_err());
+    let zero = from_value::<f64>(JsonValue::Number(-0.0)).unwrap();
+    assert!(zero == 0.0 && zero.is_sign_negative());
+    match to_value(&JsonValue::Number(-0.0)).unwrap() {
+        JsonValue::Number(n) => assert!(n.is_sign_negative()),
+        other => panic!("expected a number, found {:?}", other),
+    }
+}
+
+#[test]
+fn rejects_numbers_json_cannot_represent() {
+    for n in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+        assert!(to_value(&n).is_err());
+        assert!(to_value(&(n as f32)).is_err());
+    }
+    let e = to_value(&vec![1.0, f64::NAN]).unwrap_err();
+    assert_eq!(e.message, "NaN cannot be represented as a JSON number");
+    assert!(to_value(&f32::MAX).is_ok());
+}
+
+#[test]
+fn reports_the_path_of_deserialization_errors() {
+    let e =
+        from_value::<BTreeMap<String, Vec<u8>>>(json!({"a": [1, 2], "b": [3, "x"]})).unwrap_err();
+    assert_eq!(e.path, "/b/1");
+}
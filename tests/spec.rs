@@ -0,0 +1,129 @@
+use jasper::{AdditionalKeysSpec, FieldSpec, JsonValue, ValidatorSpec};
+
+fn parse(input: &str) -> JsonValue {
+    JsonValue::parse(input.as_bytes().to_vec()).unwrap()
+}
+
+fn user_spec() -> ValidatorSpec {
+    ValidatorSpec::Object {
+        fields: vec![
+            FieldSpec {
+                name: "name".to_owned(),
+                spec: ValidatorSpec::String {
+                    min_length: Some(2),
+                    max_length: Some(8),
+                    pattern: Some("^[a-z]+$".to_owned()),
+                },
+                required: true,
+                default: None,
+            },
+            FieldSpec {
+                name: "age".to_owned(),
+                spec: ValidatorSpec::Integer {
+                    min: Some(18),
+                    max: Some(130),
+                },
+                required: false,
+                default: Some(JsonValue::Number(18.0)),
+            },
+            FieldSpec {
+                name: "score".to_owned(),
+                spec: ValidatorSpec::Number {
+                    min: Some(0.0),
+                    max: Some(1.0),
+                },
+                required: false,
+                default: None,
+            },
+        ],
+        additional_keys: AdditionalKeysSpec::Deny,
+    }
+}
+
+#[test]
+fn round_trips_through_json() {
+    let spec = user_spec();
+    let json = spec.to_json();
+    assert_eq!(ValidatorSpec::from_json(&json).unwrap(), spec);
+    let reparsed = JsonValue::parse(json.stringify().into_bytes()).unwrap();
+    assert_eq!(ValidatorSpec::from_json(&reparsed).unwrap(), spec);
+}
+
+#[test]
+fn keeps_large_integer_bounds_exact() {
+    let spec = ValidatorSpec::Integer {
+        min: Some(9_007_199_254_740_993),
+        max: Some(u64::MAX),
+    };
+    let json = spec.to_json();
+    assert_eq!(
+        json.get_object().unwrap()["min"],
+        JsonValue::String("9007199254740993".to_owned())
+    );
+    assert_eq!(ValidatorSpec::from_json(&json).unwrap(), spec);
+    let validator = spec.compile().unwrap();
+    assert!(!validator.validate(&JsonValue::String("9007199254740992".to_owned())));
+    assert!(validator.validate(&JsonValue::String("9007199254740993".to_owned())));
+}
+
+#[test]
+fn reports_which_constraint_failed() {
+    let validator = user_spec().compile().unwrap();
+    assert!(validator.validate(&parse(r#"{"name": "ada", "age": 36, "score": 0.5}"#)));
+    let errors = validator
+        .validate_detailed(&parse(r#"{"name": "A", "age": 12, "score": 1.5}"#))
+        .unwrap_err();
+    let mut found = errors
+        .iter()
+        .map(|e| (e.path.as_str(), e.validator, e.message.as_str()))
+        .collect::<Vec<_>>();
+    found.sort();
+    assert_eq!(
+        found,
+        [
+            (
+                "/age",
+                "IntegerRange",
+                "integer 12 is less than the minimum 18"
+            ),
+            (
+                "/name",
+                "StringOf",
+                "expected at least 2 characters, found 1"
+            ),
+            (
+                "/name",
+                "StringOf",
+                "string \"A\" does not match \"^[a-z]+$\""
+            ),
+            (
+                "/score",
+                "NumberRange",
+                "number 1.5 is greater than the maximum 1"
+            ),
+        ]
+    );
+}
+
+#[test]
+fn rejects_malformed_specs() {
+    for (input, path) in [
+        (parse(r#"{"type": "string", "pattern": "("}"#), "/pattern"),
+        (parse(r#"{"type": "string", "max_len": 3}"#), "/max_len"),
+        (parse(r#"{"type": "integer", "min": -1}"#), "/min"),
+        (parse(r#"{"type": "integer", "min": "ten"}"#), "/min"),
+        (parse(r#"{"type": "array"}"#), ""),
+        (parse(r#"{"type": "list"}"#), "/type"),
+        (
+            parse(r#"{"type": "object", "fields": [{"name": "a", "validator": 1}]}"#),
+            "/fields/0/validator",
+        ),
+        (
+            parse(r#"{"type": "datetime", "formats": ["unix_weeks"]}"#),
+            "/formats/0",
+        ),
+    ] {
+        let error = ValidatorSpec::from_json(&input).unwrap_err();
+        assert_eq!(error.path, path, "{}", error);
+    }
+}
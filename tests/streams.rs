@@ -0,0 +1,90 @@
+use jasper::{json, JsonValue, ParseError, ParseOptions, StreamFormat};
+
+fn parse(input: &[u8], format: StreamFormat) -> Vec<Result<JsonValue, (usize, String)>> {
+    collect(JsonValue::parse_stream(input, format))
+}
+
+fn collect(
+    stream: impl Iterator<Item = Result<JsonValue, ParseError>>,
+) -> Vec<Result<JsonValue, (usize, String)>> {
+    stream
+        .map(|r| r.map_err(|e| (e.offset, e.message)))
+        .collect()
+}
+
+#[test]
+fn splits_whitespace_separated_and_concatenated_values() {
+    assert_eq!(
+        parse(b" 1 [2]\n{\"a\":3} ", StreamFormat::WhitespaceSeparated),
+        [Ok(json!(1)), Ok(json!([2])), Ok(json!({"a": 3}))]
+    );
+    assert_eq!(
+        parse(b"[1]{}\"s\"2", StreamFormat::Concatenated),
+        [Ok(json!([1])), Ok(json!({})), Ok(json!("s")), Ok(json!(2))]
+    );
+    assert_eq!(
+        parse(b"[1][2]", StreamFormat::WhitespaceSeparated),
+        [
+            Ok(json!([1])),
+            Err((3, "expected whitespace between values".to_owned()))
+        ]
+    );
+    assert!(parse(b"  ", StreamFormat::Concatenated).is_empty());
+}
+
+#[test]
+fn stops_at_malformed_values_but_skips_bad_records() {
+    let values = parse(b"1 [2, 3", StreamFormat::WhitespaceSeparated);
+    assert_eq!(values.len(), 2);
+    assert_eq!(values[1].as_ref().unwrap_err().0, 7);
+
+    let input = b"\x1e1\n\x1e[2,\n\x1e{\"a\":1}\n\x1e4";
+    let records = parse(input, StreamFormat::RecordSeparated);
+    assert_eq!(records.len(), 4);
+    assert_eq!(records[0], Ok(json!(1)));
+    assert!(records[1].is_err());
+    assert_eq!(records[2], Ok(json!({"a": 1})));
+    assert_eq!(
+        records[3],
+        Err((input.len(), "value may have been truncated".to_owned()))
+    );
+    assert_eq!(
+        parse(b"1\n", StreamFormat::RecordSeparated),
+        [Err((0, "expected a record separator".to_owned()))]
+    );
+}
+
+#[test]
+fn limits_depth_and_size() {
+    let deep = format!("1 {}", "[".repeat(100_000));
+    assert_eq!(
+        parse(deep.as_bytes(), StreamFormat::WhitespaceSeparated),
+        [Ok(json!(1)), Err((130, "nesting is too deep".to_owned()))]
+    );
+    let records = format!("\x1e{}\n\x1e2\n", "[".repeat(100_000));
+    let records = parse(records.as_bytes(), StreamFormat::RecordSeparated);
+    assert_eq!(records[0].as_ref().unwrap_err().1, "nesting is too deep");
+    assert_eq!(records[1], Ok(json!(2)));
+
+    let options = ParseOptions {
+        max_depth: Some(1),
+        max_size: Some(9),
+        ..ParseOptions::default()
+    };
+    assert_eq!(
+        collect(JsonValue::parse_stream_with(
+            b"[1] [[2]]",
+            StreamFormat::WhitespaceSeparated,
+            &options
+        )),
+        [Ok(json!([1])), Err((5, "nesting is too deep".to_owned()))]
+    );
+    assert_eq!(
+        collect(JsonValue::parse_stream_with(
+            b"[1] [2] [3] ",
+            StreamFormat::WhitespaceSeparated,
+            &options
+        )),
+        [Err((0, "input is too large".to_owned()))]
+    );
+}
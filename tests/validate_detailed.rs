@@ -0,0 +1,84 @@
+use jasper::{JsonValidator, JsonValue};
+
+fn parse(input: &str) -> JsonValue {
+    JsonValue::parse(input.as_bytes().to_vec()).unwrap()
+}
+
+fn errors(validator: &JsonValidator, input: &JsonValue) -> Vec<(String, &'static str)> {
+    let mut found = validator
+        .validate_detailed(input)
+        .unwrap_err()
+        .into_iter()
+        .map(|e| (e.path, e.validator))
+        .collect::<Vec<_>>();
+    found.sort();
+    found
+}
+
+fn record() -> JsonValidator {
+    JsonValidator::Object(vec![
+        ("id".to_owned(), JsonValidator::Integer(|_| true)),
+        (
+            "a/b~c".to_owned(),
+            JsonValidator::Array(Box::new(JsonValidator::String(|s| !s.is_empty()))),
+        ),
+        ("flag".to_owned(), JsonValidator::Boolean(|b| *b)),
+    ])
+}
+
+#[test]
+fn reports_every_failure_with_its_path() {
+    let validator = record();
+    let input = parse(r#"{"id": 1.5, "a/b~c": ["x", "", "y", ""], "flag": false}"#);
+    assert!(!validator.validate(&input));
+    assert_eq!(
+        errors(&validator, &input),
+        [
+            ("/a~1b~0c/1".to_owned(), "String"),
+            ("/a~1b~0c/3".to_owned(), "String"),
+            ("/flag".to_owned(), "Boolean"),
+            ("/id".to_owned(), "Integer"),
+        ]
+    );
+    assert!(validator
+        .validate_detailed(&parse(r#"{"id": 1, "a/b~c": [], "flag": true}"#))
+        .is_ok());
+}
+
+#[test]
+fn reports_missing_keys_and_wrong_types() {
+    let validator = record();
+    assert_eq!(
+        errors(&validator, &parse(r#"{"flag": true}"#)),
+        [
+            ("/a~1b~0c".to_owned(), "Object"),
+            ("/id".to_owned(), "Object"),
+        ]
+    );
+    let e = &validator.validate_detailed(&parse("[1]")).unwrap_err()[0];
+    assert_eq!(e.path, "");
+    assert_eq!(e.expected, "object");
+    assert_eq!(
+        e.to_string(),
+        "/: value is not an object (validator Object, expected object)"
+    );
+}
+
+#[test]
+fn describes_the_failure() {
+    let validator =
+        JsonValidator::Object(vec![("flag".to_owned(), JsonValidator::Boolean(|b| *b))]);
+    let e = &validator
+        .validate_detailed(&parse(r#"{"flag": false}"#))
+        .unwrap_err()[0];
+    assert_eq!(
+        e.to_string(),
+        "/flag: boolean false was rejected (validator Boolean, expected boolean)"
+    );
+    let either = JsonValidator::Or(vec![
+        JsonValidator::Null,
+        JsonValidator::Number(|n| *n > 0.0),
+    ]);
+    let e = &either.validate_detailed(&parse("-1")).unwrap_err()[0];
+    assert_eq!(e.message, "value matched none of the 2 alternatives");
+}
@@ -0,0 +1,62 @@
+use std::collections::HashMap;
+
+use jasper::JsonValue;
+
+fn parse(input: &str) -> JsonValue {
+    JsonValue::parse(input.as_bytes().to_vec()).unwrap()
+}
+
+#[test]
+fn converts_rust_values_into_json() {
+    assert_eq!(JsonValue::from(true), JsonValue::Boolean(true));
+    assert_eq!(JsonValue::from(-3_i64), JsonValue::Number(-3.0));
+    assert_eq!(JsonValue::from(2.5_f32), JsonValue::Number(2.5));
+    assert_eq!(JsonValue::from("s"), JsonValue::String("s".to_owned()));
+    assert_eq!(JsonValue::from('c'), JsonValue::String("c".to_owned()));
+    assert_eq!(JsonValue::from(vec![1, 2]), parse("[1, 2]"));
+    assert_eq!(
+        JsonValue::from(Some("x")),
+        JsonValue::String("x".to_owned())
+    );
+    assert_eq!(JsonValue::from(None::<i32>), JsonValue::Null);
+    assert_eq!(JsonValue::from(()), JsonValue::Null);
+    let obj = HashMap::from([("k".to_owned(), JsonValue::Number(1.0))]);
+    assert_eq!(JsonValue::from(obj), parse(r#"{"k": 1}"#));
+}
+
+#[test]
+fn converts_json_back_into_rust_values() {
+    assert!(bool::try_from(JsonValue::Boolean(true)).unwrap());
+    assert_eq!(i64::try_from(JsonValue::Number(-3.0)).unwrap(), -3);
+    assert_eq!(
+        String::try_from(JsonValue::String("s".to_owned())).unwrap(),
+        "s"
+    );
+    assert_eq!(
+        Vec::<JsonValue>::try_from(parse(r#"[1, "a"]"#)).unwrap(),
+        [JsonValue::Number(1.0), JsonValue::String("a".to_owned())]
+    );
+    assert_eq!(
+        HashMap::<String, JsonValue>::try_from(parse(r#"{"a": null}"#)).unwrap()["a"],
+        JsonValue::Null
+    );
+    assert!(u8::try_from(JsonValue::Number(-1.0)).is_err());
+    assert!(String::try_from(JsonValue::Number(1.0)).is_err());
+    assert_eq!(
+        Vec::<JsonValue>::try_from(parse("{}")).unwrap_err().message,
+        "expected an array, found an object"
+    );
+}
+
+#[test]
+fn collects_arrays_and_objects() {
+    let squares = (1..4)
+        .map(|n| JsonValue::from(n * n))
+        .collect::<JsonValue>();
+    assert_eq!(squares, parse("[1, 4, 9]"));
+    let obj = [("a", 1), ("b", 2), ("a", 3)]
+        .into_iter()
+        .map(|(k, v)| (k.to_owned(), JsonValue::from(v)))
+        .collect::<JsonValue>();
+    assert_eq!(obj, parse(r#"{"a": 3, "b": 2}"#));
+}